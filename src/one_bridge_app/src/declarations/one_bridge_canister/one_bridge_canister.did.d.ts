@@ -7,14 +7,39 @@ export interface BridgeLog {
   'to' : BridgeTarget,
   'fee' : bigint,
   'to_tx' : [] | [BridgeTx],
+  'token' : string,
+  'from_addr' : [] | [string],
   'to_addr' : [] | [string],
+  'svm_tx' : [] | [SvmOutboundTx],
+  'dust' : bigint,
   'from' : BridgeTarget,
+  'fee_detail' : [] | [FeeDetail],
   'user' : Principal,
+  'errors' : number,
   'from_tx' : BridgeTx,
   'created_at' : bigint,
+  'refund_tx' : [] | [BridgeTx],
   'error' : [] | [string],
+  'evm_tx' : [] | [EvmOutboundTx],
+  'from_log_index' : [] | [bigint],
+  'release_at' : bigint,
   'icp_amount' : bigint,
+  'failed' : boolean,
+  'next_retry_at' : bigint,
   'finalized_at' : bigint,
+  'retries' : number,
+  'refund' : [] | [bigint],
+}
+export interface BridgeQuote {
+  'fee' : FeeDetail,
+  'min_threshold_to_bridge' : bigint,
+  'dust' : bigint,
+  'blocked' : Array<string>,
+  'liquidity' : [] | [bigint],
+  'receive_amount' : bigint,
+  'release_at' : bigint,
+  'allowance' : [] | [bigint],
+  'estimated_secs' : [] | [bigint],
 }
 export type BridgeTarget = { 'Evm' : string } |
   { 'Icp' : null } |
@@ -24,105 +49,362 @@ export type BridgeTx = { 'Evm' : [boolean, Uint8Array | number[]] } |
   { 'Sol' : [boolean, Uint8Array | number[]] };
 export type CanisterArgs = { 'Upgrade' : UpgradeArgs } |
   { 'Init' : InitArgs };
+export interface EvmDeposit {
+  'token' : string,
+  'log_index' : bigint,
+  'chain' : string,
+  'sender' : string,
+  'block_number' : bigint,
+  'tx_hash' : Uint8Array | number[],
+  'amount' : bigint,
+}
+export interface EvmDepositIntent {
+  'to_addr' : [] | [string],
+  'to_chain' : string,
+  'user' : Principal,
+  'created_at' : bigint,
+}
+export interface EvmOutboundTx {
+  'tx' : Uint8Array | number[],
+  'replaced' : Array<Uint8Array | number[]>,
+  'max_priority_fee_per_gas' : bigint,
+  'max_fee_per_gas' : bigint,
+  'nonce' : bigint,
+  'sent_at' : bigint,
+  'capped' : boolean,
+  'nonce_used_checks' : number,
+}
+export type EvmTxType = { 'Eip1559' : null } |
+  { 'Eip2930' : null } |
+  { 'Legacy' : null };
+export interface FeeDetail {
+  'gas' : bigint,
+  'base' : bigint,
+  'flat' : bigint,
+  'percentage' : bigint,
+}
+export interface FeeSchedule {
+  'bps' : number,
+  'max' : bigint,
+  'min' : bigint,
+  'flat' : bigint,
+  'native_price' : bigint,
+}
+export interface GasLimitConfig { 'cap' : bigint, 'multiplier_pct' : number }
 export interface InitArgs {
-  'min_threshold_to_bridge' : bigint,
-  'token_symbol' : string,
   'governance_canister' : [] | [Principal],
-  'token_bridge_fee' : bigint,
+  'tokens' : Array<TokenArgs>,
   'key_name' : string,
-  'token_decimals' : number,
-  'token_ledger' : Principal,
-  'token_logo' : string,
-  'token_name' : string,
+}
+export interface RateLimit {
+  'per_user' : bigint,
+  'window_secs' : bigint,
+  'per_chain' : bigint,
+  'global' : bigint,
+}
+export interface ReserveReport {
+  'violation' : [] | [string],
+  'circulating' : bigint,
+  'unwithdrawn_fees' : bigint,
+  'chains' : Array<[string, [bigint, bigint]]>,
+  'in_flight' : bigint,
+  'icp_balance' : bigint,
+  'checked_at' : bigint,
 }
 export type Result = { 'Ok' : null } |
   { 'Err' : string };
-export type Result_1 = { 'Ok' : BridgeTx } |
+export type Result_1 = { 'Ok' : string } |
+  { 'Err' : string };
+export type Result_10 = { 'Ok' : BridgeQuote } |
   { 'Err' : string };
-export type Result_2 = { 'Ok' : string } |
+export type Result_2 = { 'Ok' : BridgeTx } |
   { 'Err' : string };
-export type Result_3 = { 'Ok' : Uint8Array | number[] } |
+export type Result_3 = { 'Ok' : [] | [bigint] } |
   { 'Err' : string };
 export type Result_4 = { 'Ok' : Array<BridgeLog> } |
   { 'Err' : string };
-export type Result_5 = { 'Ok' : StateInfo } |
+export type Result_5 = { 'Ok' : Uint8Array | number[] } |
   { 'Err' : string };
-export type Result_6 = { 'Ok' : BridgeLog } |
+export type Result_6 = { 'Ok' : Array<EvmDeposit> } |
+  { 'Err' : string };
+export type Result_7 = { 'Ok' : StateInfo } |
+  { 'Err' : string };
+export type Result_8 = { 'Ok' : BridgeLog } |
+  { 'Err' : string };
+export type Result_9 = { 'Ok' : Array<[string, EvmDepositIntent]> } |
   { 'Err' : string };
 export interface StateInfo {
-  'total_withdrawn_fees' : bigint,
-  'error_rounds' : bigint,
+  'dead_letters' : bigint,
+  'evm_nonces' : Array<[string, bigint]>,
+  'gas_minimums' : Array<[string, bigint]>,
+  'evm_gas_limits' : Array<[string, GasLimitConfig]>,
   'evm_address' : string,
   'evm_latest_gas' : Array<[string, [bigint, bigint, bigint]]>,
   'svm_address' : string,
   'finalize_bridging_round' : [bigint, boolean],
-  'total_collected_fees' : bigint,
-  'min_threshold_to_bridge' : bigint,
-  'token_symbol' : string,
   'governance_canister' : [] | [Principal],
+  'svm_priority_fee' : SvmPriorityFeeConfig,
+  'reserves' : Array<[string, ReserveReport]>,
   'icp_address' : Principal,
   'total_bridge_count' : bigint,
-  'evm_token_contracts' : Array<[string, [string, number, bigint]]>,
+  'svm_nonce_accounts' : Array<string>,
+  'evm_tx_timeouts' : Array<[string, bigint]>,
+  'paused_chains' : Array<[string, boolean]>,
+  'svm_nonce_accounts_pending' : Array<string>,
+  'paused_routes' : Array<[string, string]>,
+  'evm_deposit_cursors' : Array<[string, bigint]>,
+  'evm_max_fees' : Array<[string, bigint]>,
   'svm_providers' : Array<string>,
-  'svm_token_address' : [string, number, string],
-  'token_bridge_fee' : bigint,
+  'tokens' : Array<TokenInfo>,
+  'svm_latest_priority_fee' : [bigint, bigint],
+  'rpc_quorums' : Array<[string, number]>,
   'key_name' : string,
-  'total_bridged_tokens' : bigint,
   'evm_providers' : Array<[string, [bigint, Array<string>]]>,
-  'token_decimals' : number,
-  'token_ledger' : Principal,
-  'token_logo' : string,
-  'token_name' : string,
+  'evm_rpc_canisters' : Array<[string, Principal]>,
+  'refund_after_retries' : number,
+  'gas_balances' : Array<[string, [bigint, bigint]]>,
   'sub_bridges' : Array<Principal>,
+  'evm_tx_types' : Array<[string, EvmTxType]>,
+}
+export interface SvmOutboundTx {
+  'tx' : Uint8Array | number[],
+  'nonce_account' : [] | [string],
+  'blockhash' : Uint8Array | number[],
+  'last_valid_block_height' : bigint,
+  'sent_at' : bigint,
+}
+export interface SvmPriorityFeeConfig {
+  'max_micro_lamports' : bigint,
+  'compute_unit_limit' : number,
+  'percentile' : number,
 }
-export interface UpgradeArgs {
+export interface TokenArgs {
+  'decimals' : number,
+  'min_threshold_to_bridge' : bigint,
+  'logo' : string,
+  'name' : string,
+  'ledger' : Principal,
+  'bridge_fee' : bigint,
+  'symbol' : string,
+}
+export interface TokenInfo {
+  'decimals' : number,
+  'total_withdrawn_fees' : bigint,
+  'total_collected_fees' : bigint,
+  'min_threshold_to_bridge' : bigint,
+  'deposit_dust' : Array<[string, bigint]>,
+  'logo' : string,
+  'name' : string,
+  'svm_contract' : [] | [[string, number, string]],
+  'rate_limit' : [] | [RateLimit],
+  'total_ledger_fees' : bigint,
+  'reserve_tolerance' : bigint,
+  'total_dust' : bigint,
+  'mint_burn_chains' : Array<string>,
+  'refund_fee' : bigint,
+  'evm_contracts' : Array<[string, [string, number, bigint]]>,
+  'ledger' : Principal,
+  'total_bridged_tokens' : bigint,
+  'bridge_fee' : bigint,
+  'timelock_threshold' : bigint,
+  'symbol' : string,
+  'paused' : boolean,
+  'timelock_secs' : bigint,
+  'fee_schedules' : Array<[string, FeeSchedule]>,
+}
+export interface UpdateTokenArgs {
   'min_threshold_to_bridge' : [] | [bigint],
-  'token_symbol' : [] | [string],
-  'governance_canister' : [] | [Principal],
-  'token_bridge_fee' : [] | [bigint],
-  'token_ledger' : [] | [Principal],
-  'token_logo' : [] | [string],
-  'token_name' : [] | [string],
+  'logo' : [] | [string],
+  'name' : [] | [string],
+  'refund_fee' : [] | [bigint],
+  'bridge_fee' : [] | [bigint],
+  'symbol' : string,
 }
+export interface UpgradeArgs { 'governance_canister' : [] | [Principal] }
 export interface _SERVICE {
   'admin_add_bridges' : ActorMethod<[Array<Principal>], Result>,
-  'admin_add_evm_contract' : ActorMethod<[string, bigint, string], Result>,
-  'admin_add_svm_contract' : ActorMethod<[string], Result>,
-  'admin_collect_fees' : ActorMethod<[Principal, bigint], Result_1>,
+  'admin_add_evm_contract' : ActorMethod<
+    [string, string, bigint, string],
+    Result
+  >,
+  'admin_add_svm_contract' : ActorMethod<[string, string], Result>,
+  'admin_add_svm_nonce_account' : ActorMethod<[], Result_1>,
+  'admin_add_token' : ActorMethod<[TokenArgs], Result>,
+  'admin_cancel_pending' : ActorMethod<
+    [string, BridgeTx, [] | [bigint]],
+    Result
+  >,
+  'admin_check_gas_balances' : ActorMethod<[], Result>,
+  'admin_collect_fees' : ActorMethod<[string, Principal, bigint], Result_2>,
+  'admin_mark_finalized' : ActorMethod<
+    [string, BridgeTx, BridgeTx, [] | [bigint]],
+    Result
+  >,
+  'admin_reconcile_reserves' : ActorMethod<[], Result>,
+  'admin_release_pending' : ActorMethod<
+    [string, BridgeTx, [] | [bigint]],
+    Result
+  >,
   'admin_remove_bridges' : ActorMethod<[Array<Principal>], Result>,
+  'admin_remove_svm_nonce_account' : ActorMethod<[string], Result>,
+  'admin_reset_error_rounds' : ActorMethod<[], Result>,
+  'admin_retry_pending' : ActorMethod<
+    [string, BridgeTx, [] | [bigint]],
+    Result
+  >,
+  'admin_set_chain_paused' : ActorMethod<[string, boolean, boolean], Result>,
+  'admin_set_evm_gas_limit' : ActorMethod<
+    [string, [] | [GasLimitConfig]],
+    Result
+  >,
+  'admin_set_evm_max_fee' : ActorMethod<[string, [] | [bigint]], Result>,
   'admin_set_evm_providers' : ActorMethod<
     [string, bigint, Array<string>],
     Result
   >,
+  'admin_set_evm_rpc_canister' : ActorMethod<
+    [string, [] | [Principal]],
+    Result
+  >,
+  'admin_set_evm_tx_timeout' : ActorMethod<[string, bigint], Result>,
+  'admin_set_evm_tx_type' : ActorMethod<[string, EvmTxType], Result>,
+  'admin_set_fee_schedule' : ActorMethod<
+    [string, string, [] | [FeeSchedule]],
+    Result
+  >,
+  'admin_set_gas_minimum' : ActorMethod<[string, bigint], Result>,
+  'admin_set_mint_burn' : ActorMethod<[string, string, boolean], Result>,
+  'admin_set_rate_limit' : ActorMethod<[string, [] | [RateLimit]], Result>,
+  'admin_set_refund_after_retries' : ActorMethod<[number], Result>,
+  'admin_set_reserve_tolerance' : ActorMethod<[string, bigint], Result>,
+  'admin_set_route_paused' : ActorMethod<[string, string, boolean], Result>,
+  'admin_set_rpc_quorum' : ActorMethod<[string, number], Result>,
+  'admin_set_svm_priority_fee' : ActorMethod<[SvmPriorityFeeConfig], Result>,
   'admin_set_svm_providers' : ActorMethod<[Array<string>], Result>,
-  'bridge' : ActorMethod<[string, string, bigint, [] | [string]], Result_1>,
-  'erc20_transfer' : ActorMethod<[string, string, bigint], Result_2>,
-  'erc20_transfer_tx' : ActorMethod<[string, string, bigint], Result_2>,
-  'evm_address' : ActorMethod<[[] | [Principal]], Result_2>,
-  'evm_sign' : ActorMethod<[Uint8Array | number[]], Result_3>,
-  'evm_transfer_tx' : ActorMethod<[string, string, bigint], Result_2>,
+  'admin_set_timelock' : ActorMethod<[string, bigint, bigint], Result>,
+  'admin_set_token_paused' : ActorMethod<[string, boolean], Result>,
+  'admin_update_token' : ActorMethod<[UpdateTokenArgs], Result>,
+  'bridge' : ActorMethod<
+    [string, string, string, bigint, [] | [string]],
+    Result_2
+  >,
+  'bridge_allowance' : ActorMethod<[string, string, string], Result_3>,
+  'dead_letter_logs' : ActorMethod<[], Result_4>,
+  'erc20_transfer' : ActorMethod<[string, string, string, bigint], Result_1>,
+  'erc20_transfer_tx' : ActorMethod<[string, string, string, bigint], Result_1>,
+  'evm_address' : ActorMethod<[[] | [Principal]], Result_1>,
+  'evm_deposit_message' : ActorMethod<[string], Result_1>,
+  'evm_sign' : ActorMethod<[Uint8Array | number[]], Result_5>,
+  'evm_transfer_tx' : ActorMethod<[string, string, bigint], Result_1>,
+  'evm_unmatched_deposits' : ActorMethod<[], Result_6>,
   'finalized_logs' : ActorMethod<[number, [] | [bigint]], Result_4>,
-  'info' : ActorMethod<[], Result_5>,
-  'my_bridge_log' : ActorMethod<[BridgeTx], Result_6>,
+  'info' : ActorMethod<[], Result_7>,
+  'my_bridge_log' : ActorMethod<[BridgeTx], Result_8>,
+  'my_dead_letter_logs' : ActorMethod<[], Result_4>,
+  'my_evm_deposits' : ActorMethod<[], Result_9>,
   'my_finalized_logs' : ActorMethod<[number, [] | [bigint]], Result_4>,
   'my_pending_logs' : ActorMethod<[], Result_4>,
   'pending_logs' : ActorMethod<[], Result_4>,
-  'sol_transfer_tx' : ActorMethod<[string, bigint], Result_2>,
-  'spl_transfer_tx' : ActorMethod<[string, bigint], Result_2>,
-  'svm_address' : ActorMethod<[[] | [Principal]], Result_2>,
-  'validate_admin_add_bridges' : ActorMethod<[Array<Principal>], Result_2>,
+  'quote_bridge' : ActorMethod<
+    [string, string, string, bigint, [] | [string]],
+    Result_10
+  >,
+  'register_evm_deposit' : ActorMethod<
+    [string, string, string, [] | [string]],
+    Result
+  >,
+  'sol_transfer_tx' : ActorMethod<[string, bigint], Result_1>,
+  'spl_transfer_tx' : ActorMethod<[string, string, bigint], Result_1>,
+  'svm_address' : ActorMethod<[[] | [Principal]], Result_1>,
+  'validate_admin_add_bridges' : ActorMethod<[Array<Principal>], Result_1>,
   'validate_admin_add_evm_contract' : ActorMethod<
-    [string, bigint, string],
-    Result_2
+    [string, string, bigint, string],
+    Result_1
+  >,
+  'validate_admin_add_svm_contract' : ActorMethod<[string, string], Result_1>,
+  'validate_admin_add_svm_nonce_account' : ActorMethod<[], Result_1>,
+  'validate_admin_add_token' : ActorMethod<[TokenArgs], Result_1>,
+  'validate_admin_cancel_pending' : ActorMethod<
+    [string, BridgeTx, [] | [bigint]],
+    Result_1
+  >,
+  'validate_admin_check_gas_balances' : ActorMethod<[], Result_1>,
+  'validate_admin_collect_fees' : ActorMethod<
+    [string, Principal, bigint],
+    Result_1
+  >,
+  'validate_admin_mark_finalized' : ActorMethod<
+    [string, BridgeTx, BridgeTx, [] | [bigint]],
+    Result_1
+  >,
+  'validate_admin_reconcile_reserves' : ActorMethod<[], Result_1>,
+  'validate_admin_release_pending' : ActorMethod<
+    [string, BridgeTx, [] | [bigint]],
+    Result_1
+  >,
+  'validate_admin_remove_bridges' : ActorMethod<[Array<Principal>], Result_1>,
+  'validate_admin_remove_svm_nonce_account' : ActorMethod<[string], Result_1>,
+  'validate_admin_reset_error_rounds' : ActorMethod<[], Result_1>,
+  'validate_admin_retry_pending' : ActorMethod<
+    [string, BridgeTx, [] | [bigint]],
+    Result_1
+  >,
+  'validate_admin_set_chain_paused' : ActorMethod<
+    [string, boolean, boolean],
+    Result_1
+  >,
+  'validate_admin_set_evm_gas_limit' : ActorMethod<
+    [string, [] | [GasLimitConfig]],
+    Result_1
+  >,
+  'validate_admin_set_evm_max_fee' : ActorMethod<
+    [string, [] | [bigint]],
+    Result_1
   >,
-  'validate_admin_add_svm_contract' : ActorMethod<[string], Result_2>,
-  'validate_admin_collect_fees' : ActorMethod<[Principal, bigint], Result_2>,
-  'validate_admin_remove_bridges' : ActorMethod<[Array<Principal>], Result_2>,
   'validate_admin_set_evm_providers' : ActorMethod<
     [string, bigint, Array<string>],
-    Result_2
+    Result_1
+  >,
+  'validate_admin_set_evm_rpc_canister' : ActorMethod<
+    [string, [] | [Principal]],
+    Result_1
+  >,
+  'validate_admin_set_evm_tx_timeout' : ActorMethod<[string, bigint], Result_1>,
+  'validate_admin_set_evm_tx_type' : ActorMethod<[string, EvmTxType], Result_1>,
+  'validate_admin_set_fee_schedule' : ActorMethod<
+    [string, string, [] | [FeeSchedule]],
+    Result_1
+  >,
+  'validate_admin_set_gas_minimum' : ActorMethod<[string, bigint], Result_1>,
+  'validate_admin_set_mint_burn' : ActorMethod<
+    [string, string, boolean],
+    Result_1
+  >,
+  'validate_admin_set_rate_limit' : ActorMethod<
+    [string, [] | [RateLimit]],
+    Result_1
+  >,
+  'validate_admin_set_refund_after_retries' : ActorMethod<[number], Result_1>,
+  'validate_admin_set_reserve_tolerance' : ActorMethod<
+    [string, bigint],
+    Result_1
+  >,
+  'validate_admin_set_route_paused' : ActorMethod<
+    [string, string, boolean],
+    Result_1
+  >,
+  'validate_admin_set_rpc_quorum' : ActorMethod<[string, number], Result_1>,
+  'validate_admin_set_svm_priority_fee' : ActorMethod<
+    [SvmPriorityFeeConfig],
+    Result_1
+  >,
+  'validate_admin_set_svm_providers' : ActorMethod<[Array<string>], Result_1>,
+  'validate_admin_set_timelock' : ActorMethod<
+    [string, bigint, bigint],
+    Result_1
   >,
-  'validate_admin_set_svm_providers' : ActorMethod<[Array<string>], Result_2>,
+  'validate_admin_set_token_paused' : ActorMethod<[string, boolean], Result_1>,
+  'validate_admin_update_token' : ActorMethod<[UpdateTokenArgs], Result_1>,
 }
 export declare const idlFactory: IDL.InterfaceFactory;
-export declare const init: (args: { IDL: typeof IDL }) => IDL.Type[];
+export declare const init: (args: { IDL: typeof IDL }) => IDL.Type[];
\ No newline at end of file
@@ -1,128 +1,378 @@
 export const idlFactory = ({ IDL }) => {
   const UpgradeArgs = IDL.Record({
-    'min_threshold_to_bridge' : IDL.Opt(IDL.Nat),
-    'token_symbol' : IDL.Opt(IDL.Text),
     'governance_canister' : IDL.Opt(IDL.Principal),
-    'token_bridge_fee' : IDL.Opt(IDL.Nat),
-    'token_ledger' : IDL.Opt(IDL.Principal),
-    'token_logo' : IDL.Opt(IDL.Text),
-    'token_name' : IDL.Opt(IDL.Text),
   });
-  const InitArgs = IDL.Record({
+  const TokenArgs = IDL.Record({
+    'decimals' : IDL.Nat8,
     'min_threshold_to_bridge' : IDL.Nat,
-    'token_symbol' : IDL.Text,
+    'logo' : IDL.Text,
+    'name' : IDL.Text,
+    'ledger' : IDL.Principal,
+    'bridge_fee' : IDL.Nat,
+    'symbol' : IDL.Text,
+  });
+  const InitArgs = IDL.Record({
     'governance_canister' : IDL.Opt(IDL.Principal),
-    'token_bridge_fee' : IDL.Nat,
+    'tokens' : IDL.Vec(TokenArgs),
     'key_name' : IDL.Text,
-    'token_decimals' : IDL.Nat8,
-    'token_ledger' : IDL.Principal,
-    'token_logo' : IDL.Text,
-    'token_name' : IDL.Text,
   });
   const CanisterArgs = IDL.Variant({
     'Upgrade' : UpgradeArgs,
     'Init' : InitArgs,
   });
   const Result = IDL.Variant({ 'Ok' : IDL.Null, 'Err' : IDL.Text });
+  const Result_1 = IDL.Variant({ 'Ok' : IDL.Text, 'Err' : IDL.Text });
   const BridgeTx = IDL.Variant({
     'Evm' : IDL.Tuple(IDL.Bool, IDL.Vec(IDL.Nat8)),
     'Icp' : IDL.Tuple(IDL.Bool, IDL.Nat64),
     'Sol' : IDL.Tuple(IDL.Bool, IDL.Vec(IDL.Nat8)),
   });
-  const Result_1 = IDL.Variant({ 'Ok' : BridgeTx, 'Err' : IDL.Text });
-  const Result_2 = IDL.Variant({ 'Ok' : IDL.Text, 'Err' : IDL.Text });
-  const Result_3 = IDL.Variant({ 'Ok' : IDL.Vec(IDL.Nat8), 'Err' : IDL.Text });
+  const Result_2 = IDL.Variant({ 'Ok' : BridgeTx, 'Err' : IDL.Text });
+  const GasLimitConfig = IDL.Record({
+    'cap' : IDL.Nat64,
+    'multiplier_pct' : IDL.Nat16,
+  });
+  const EvmTxType = IDL.Variant({
+    'Eip1559' : IDL.Null,
+    'Eip2930' : IDL.Null,
+    'Legacy' : IDL.Null,
+  });
+  const FeeSchedule = IDL.Record({
+    'bps' : IDL.Nat16,
+    'max' : IDL.Nat,
+    'min' : IDL.Nat,
+    'flat' : IDL.Nat,
+    'native_price' : IDL.Nat,
+  });
+  const RateLimit = IDL.Record({
+    'per_user' : IDL.Nat,
+    'window_secs' : IDL.Nat64,
+    'per_chain' : IDL.Nat,
+    'global' : IDL.Nat,
+  });
+  const SvmPriorityFeeConfig = IDL.Record({
+    'max_micro_lamports' : IDL.Nat64,
+    'compute_unit_limit' : IDL.Nat32,
+    'percentile' : IDL.Nat8,
+  });
+  const UpdateTokenArgs = IDL.Record({
+    'min_threshold_to_bridge' : IDL.Opt(IDL.Nat),
+    'logo' : IDL.Opt(IDL.Text),
+    'name' : IDL.Opt(IDL.Text),
+    'refund_fee' : IDL.Opt(IDL.Nat),
+    'bridge_fee' : IDL.Opt(IDL.Nat),
+    'symbol' : IDL.Text,
+  });
+  const Result_3 = IDL.Variant({ 'Ok' : IDL.Opt(IDL.Nat), 'Err' : IDL.Text });
   const BridgeTarget = IDL.Variant({
     'Evm' : IDL.Text,
     'Icp' : IDL.Null,
     'Sol' : IDL.Null,
   });
+  const SvmOutboundTx = IDL.Record({
+    'tx' : IDL.Vec(IDL.Nat8),
+    'nonce_account' : IDL.Opt(IDL.Text),
+    'blockhash' : IDL.Vec(IDL.Nat8),
+    'last_valid_block_height' : IDL.Nat64,
+    'sent_at' : IDL.Nat64,
+  });
+  const FeeDetail = IDL.Record({
+    'gas' : IDL.Nat,
+    'base' : IDL.Nat,
+    'flat' : IDL.Nat,
+    'percentage' : IDL.Nat,
+  });
+  const EvmOutboundTx = IDL.Record({
+    'tx' : IDL.Vec(IDL.Nat8),
+    'replaced' : IDL.Vec(IDL.Vec(IDL.Nat8)),
+    'max_priority_fee_per_gas' : IDL.Nat,
+    'max_fee_per_gas' : IDL.Nat,
+    'nonce' : IDL.Nat64,
+    'sent_at' : IDL.Nat64,
+    'capped' : IDL.Bool,
+    'nonce_used_checks' : IDL.Nat32,
+  });
   const BridgeLog = IDL.Record({
     'id' : IDL.Opt(IDL.Nat64),
     'to' : BridgeTarget,
     'fee' : IDL.Nat,
     'to_tx' : IDL.Opt(BridgeTx),
+    'token' : IDL.Text,
+    'from_addr' : IDL.Opt(IDL.Text),
     'to_addr' : IDL.Opt(IDL.Text),
+    'svm_tx' : IDL.Opt(SvmOutboundTx),
+    'dust' : IDL.Nat,
     'from' : BridgeTarget,
+    'fee_detail' : IDL.Opt(FeeDetail),
     'user' : IDL.Principal,
+    'errors' : IDL.Nat32,
     'from_tx' : BridgeTx,
     'created_at' : IDL.Nat64,
+    'refund_tx' : IDL.Opt(BridgeTx),
     'error' : IDL.Opt(IDL.Text),
+    'evm_tx' : IDL.Opt(EvmOutboundTx),
+    'from_log_index' : IDL.Opt(IDL.Nat64),
+    'release_at' : IDL.Nat64,
     'icp_amount' : IDL.Nat,
+    'failed' : IDL.Bool,
+    'next_retry_at' : IDL.Nat64,
     'finalized_at' : IDL.Nat64,
+    'retries' : IDL.Nat32,
+    'refund' : IDL.Opt(IDL.Nat),
   });
   const Result_4 = IDL.Variant({ 'Ok' : IDL.Vec(BridgeLog), 'Err' : IDL.Text });
-  const StateInfo = IDL.Record({
+  const Result_5 = IDL.Variant({ 'Ok' : IDL.Vec(IDL.Nat8), 'Err' : IDL.Text });
+  const EvmDeposit = IDL.Record({
+    'token' : IDL.Text,
+    'log_index' : IDL.Nat64,
+    'chain' : IDL.Text,
+    'sender' : IDL.Text,
+    'block_number' : IDL.Nat64,
+    'tx_hash' : IDL.Vec(IDL.Nat8),
+    'amount' : IDL.Nat,
+  });
+  const Result_6 = IDL.Variant({
+    'Ok' : IDL.Vec(EvmDeposit),
+    'Err' : IDL.Text,
+  });
+  const ReserveReport = IDL.Record({
+    'violation' : IDL.Opt(IDL.Text),
+    'circulating' : IDL.Nat,
+    'unwithdrawn_fees' : IDL.Nat,
+    'chains' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Tuple(IDL.Nat, IDL.Nat))),
+    'in_flight' : IDL.Nat,
+    'icp_balance' : IDL.Nat,
+    'checked_at' : IDL.Nat64,
+  });
+  const TokenInfo = IDL.Record({
+    'decimals' : IDL.Nat8,
     'total_withdrawn_fees' : IDL.Nat,
-    'error_rounds' : IDL.Nat64,
+    'total_collected_fees' : IDL.Nat,
+    'min_threshold_to_bridge' : IDL.Nat,
+    'deposit_dust' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Nat)),
+    'logo' : IDL.Text,
+    'name' : IDL.Text,
+    'svm_contract' : IDL.Opt(IDL.Tuple(IDL.Text, IDL.Nat8, IDL.Text)),
+    'rate_limit' : IDL.Opt(RateLimit),
+    'total_ledger_fees' : IDL.Nat,
+    'reserve_tolerance' : IDL.Nat,
+    'total_dust' : IDL.Nat,
+    'mint_burn_chains' : IDL.Vec(IDL.Text),
+    'refund_fee' : IDL.Nat,
+    'evm_contracts' : IDL.Vec(
+      IDL.Tuple(IDL.Text, IDL.Tuple(IDL.Text, IDL.Nat8, IDL.Nat64))
+    ),
+    'ledger' : IDL.Principal,
+    'total_bridged_tokens' : IDL.Nat,
+    'bridge_fee' : IDL.Nat,
+    'timelock_threshold' : IDL.Nat,
+    'symbol' : IDL.Text,
+    'paused' : IDL.Bool,
+    'timelock_secs' : IDL.Nat64,
+    'fee_schedules' : IDL.Vec(IDL.Tuple(IDL.Text, FeeSchedule)),
+  });
+  const StateInfo = IDL.Record({
+    'dead_letters' : IDL.Nat64,
+    'evm_nonces' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Nat64)),
+    'gas_minimums' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Nat)),
+    'evm_gas_limits' : IDL.Vec(IDL.Tuple(IDL.Text, GasLimitConfig)),
     'evm_address' : IDL.Text,
     'evm_latest_gas' : IDL.Vec(
       IDL.Tuple(IDL.Text, IDL.Tuple(IDL.Nat64, IDL.Nat, IDL.Nat))
     ),
     'svm_address' : IDL.Text,
     'finalize_bridging_round' : IDL.Tuple(IDL.Nat64, IDL.Bool),
-    'total_collected_fees' : IDL.Nat,
-    'min_threshold_to_bridge' : IDL.Nat,
-    'token_symbol' : IDL.Text,
     'governance_canister' : IDL.Opt(IDL.Principal),
+    'svm_priority_fee' : SvmPriorityFeeConfig,
+    'reserves' : IDL.Vec(IDL.Tuple(IDL.Text, ReserveReport)),
     'icp_address' : IDL.Principal,
     'total_bridge_count' : IDL.Nat64,
-    'evm_token_contracts' : IDL.Vec(
-      IDL.Tuple(IDL.Text, IDL.Tuple(IDL.Text, IDL.Nat8, IDL.Nat64))
-    ),
+    'svm_nonce_accounts' : IDL.Vec(IDL.Text),
+    'evm_tx_timeouts' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Nat64)),
+    'paused_chains' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Bool)),
+    'svm_nonce_accounts_pending' : IDL.Vec(IDL.Text),
+    'paused_routes' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Text)),
+    'evm_deposit_cursors' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Nat64)),
+    'evm_max_fees' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Nat)),
     'svm_providers' : IDL.Vec(IDL.Text),
-    'svm_token_address' : IDL.Tuple(IDL.Text, IDL.Nat8, IDL.Text),
-    'token_bridge_fee' : IDL.Nat,
+    'tokens' : IDL.Vec(TokenInfo),
+    'svm_latest_priority_fee' : IDL.Tuple(IDL.Nat64, IDL.Nat64),
+    'rpc_quorums' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Nat8)),
     'key_name' : IDL.Text,
-    'total_bridged_tokens' : IDL.Nat,
     'evm_providers' : IDL.Vec(
       IDL.Tuple(IDL.Text, IDL.Tuple(IDL.Nat64, IDL.Vec(IDL.Text)))
     ),
-    'token_decimals' : IDL.Nat8,
-    'token_ledger' : IDL.Principal,
-    'token_logo' : IDL.Text,
-    'token_name' : IDL.Text,
+    'evm_rpc_canisters' : IDL.Vec(IDL.Tuple(IDL.Text, IDL.Principal)),
+    'refund_after_retries' : IDL.Nat32,
+    'gas_balances' : IDL.Vec(
+      IDL.Tuple(IDL.Text, IDL.Tuple(IDL.Nat64, IDL.Nat))
+    ),
     'sub_bridges' : IDL.Vec(IDL.Principal),
+    'evm_tx_types' : IDL.Vec(IDL.Tuple(IDL.Text, EvmTxType)),
+  });
+  const Result_7 = IDL.Variant({ 'Ok' : StateInfo, 'Err' : IDL.Text });
+  const Result_8 = IDL.Variant({ 'Ok' : BridgeLog, 'Err' : IDL.Text });
+  const EvmDepositIntent = IDL.Record({
+    'to_addr' : IDL.Opt(IDL.Text),
+    'to_chain' : IDL.Text,
+    'user' : IDL.Principal,
+    'created_at' : IDL.Nat64,
   });
-  const Result_5 = IDL.Variant({ 'Ok' : StateInfo, 'Err' : IDL.Text });
-  const Result_6 = IDL.Variant({ 'Ok' : BridgeLog, 'Err' : IDL.Text });
+  const Result_9 = IDL.Variant({
+    'Ok' : IDL.Vec(IDL.Tuple(IDL.Text, EvmDepositIntent)),
+    'Err' : IDL.Text,
+  });
+  const BridgeQuote = IDL.Record({
+    'fee' : FeeDetail,
+    'min_threshold_to_bridge' : IDL.Nat,
+    'dust' : IDL.Nat,
+    'blocked' : IDL.Vec(IDL.Text),
+    'liquidity' : IDL.Opt(IDL.Nat),
+    'receive_amount' : IDL.Nat,
+    'release_at' : IDL.Nat64,
+    'allowance' : IDL.Opt(IDL.Nat),
+    'estimated_secs' : IDL.Opt(IDL.Nat64),
+  });
+  const Result_10 = IDL.Variant({ 'Ok' : BridgeQuote, 'Err' : IDL.Text });
   return IDL.Service({
     'admin_add_bridges' : IDL.Func([IDL.Vec(IDL.Principal)], [Result], []),
     'admin_add_evm_contract' : IDL.Func(
-        [IDL.Text, IDL.Nat64, IDL.Text],
+        [IDL.Text, IDL.Text, IDL.Nat64, IDL.Text],
+        [Result],
+        [],
+      ),
+    'admin_add_svm_contract' : IDL.Func([IDL.Text, IDL.Text], [Result], []),
+    'admin_add_svm_nonce_account' : IDL.Func([], [Result_1], []),
+    'admin_add_token' : IDL.Func([TokenArgs], [Result], []),
+    'admin_cancel_pending' : IDL.Func(
+        [IDL.Text, BridgeTx, IDL.Opt(IDL.Nat64)],
+        [Result],
+        [],
+      ),
+    'admin_check_gas_balances' : IDL.Func([], [Result], []),
+    'admin_collect_fees' : IDL.Func(
+        [IDL.Text, IDL.Principal, IDL.Nat],
+        [Result_2],
+        [],
+      ),
+    'admin_mark_finalized' : IDL.Func(
+        [IDL.Text, BridgeTx, BridgeTx, IDL.Opt(IDL.Nat64)],
+        [Result],
+        [],
+      ),
+    'admin_reconcile_reserves' : IDL.Func([], [Result], []),
+    'admin_release_pending' : IDL.Func(
+        [IDL.Text, BridgeTx, IDL.Opt(IDL.Nat64)],
         [Result],
         [],
       ),
-    'admin_add_svm_contract' : IDL.Func([IDL.Text], [Result], []),
-    'admin_collect_fees' : IDL.Func([IDL.Principal, IDL.Nat], [Result_1], []),
     'admin_remove_bridges' : IDL.Func([IDL.Vec(IDL.Principal)], [Result], []),
+    'admin_remove_svm_nonce_account' : IDL.Func([IDL.Text], [Result], []),
+    'admin_reset_error_rounds' : IDL.Func([], [Result], []),
+    'admin_retry_pending' : IDL.Func(
+        [IDL.Text, BridgeTx, IDL.Opt(IDL.Nat64)],
+        [Result],
+        [],
+      ),
+    'admin_set_chain_paused' : IDL.Func(
+        [IDL.Text, IDL.Bool, IDL.Bool],
+        [Result],
+        [],
+      ),
+    'admin_set_evm_gas_limit' : IDL.Func(
+        [IDL.Text, IDL.Opt(GasLimitConfig)],
+        [Result],
+        [],
+      ),
+    'admin_set_evm_max_fee' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Nat)],
+        [Result],
+        [],
+      ),
     'admin_set_evm_providers' : IDL.Func(
         [IDL.Text, IDL.Nat64, IDL.Vec(IDL.Text)],
         [Result],
         [],
       ),
+    'admin_set_evm_rpc_canister' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Principal)],
+        [Result],
+        [],
+      ),
+    'admin_set_evm_tx_timeout' : IDL.Func([IDL.Text, IDL.Nat64], [Result], []),
+    'admin_set_evm_tx_type' : IDL.Func([IDL.Text, EvmTxType], [Result], []),
+    'admin_set_fee_schedule' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(FeeSchedule)],
+        [Result],
+        [],
+      ),
+    'admin_set_gas_minimum' : IDL.Func([IDL.Text, IDL.Nat], [Result], []),
+    'admin_set_mint_burn' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Bool],
+        [Result],
+        [],
+      ),
+    'admin_set_rate_limit' : IDL.Func(
+        [IDL.Text, IDL.Opt(RateLimit)],
+        [Result],
+        [],
+      ),
+    'admin_set_refund_after_retries' : IDL.Func([IDL.Nat32], [Result], []),
+    'admin_set_reserve_tolerance' : IDL.Func([IDL.Text, IDL.Nat], [Result], []),
+    'admin_set_route_paused' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Bool],
+        [Result],
+        [],
+      ),
+    'admin_set_rpc_quorum' : IDL.Func([IDL.Text, IDL.Nat8], [Result], []),
+    'admin_set_svm_priority_fee' : IDL.Func(
+        [SvmPriorityFeeConfig],
+        [Result],
+        [],
+      ),
     'admin_set_svm_providers' : IDL.Func([IDL.Vec(IDL.Text)], [Result], []),
+    'admin_set_timelock' : IDL.Func(
+        [IDL.Text, IDL.Nat, IDL.Nat64],
+        [Result],
+        [],
+      ),
+    'admin_set_token_paused' : IDL.Func([IDL.Text, IDL.Bool], [Result], []),
+    'admin_update_token' : IDL.Func([UpdateTokenArgs], [Result], []),
     'bridge' : IDL.Func(
-        [IDL.Text, IDL.Text, IDL.Nat, IDL.Opt(IDL.Text)],
+        [IDL.Text, IDL.Text, IDL.Text, IDL.Nat, IDL.Opt(IDL.Text)],
+        [Result_2],
+        [],
+      ),
+    'bridge_allowance' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Text],
+        [Result_3],
+        ['query'],
+      ),
+    'dead_letter_logs' : IDL.Func([], [Result_4], ['query']),
+    'erc20_transfer' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Text, IDL.Nat],
         [Result_1],
         [],
       ),
-    'erc20_transfer' : IDL.Func([IDL.Text, IDL.Text, IDL.Nat], [Result_2], []),
     'erc20_transfer_tx' : IDL.Func(
-        [IDL.Text, IDL.Text, IDL.Nat],
-        [Result_2],
+        [IDL.Text, IDL.Text, IDL.Text, IDL.Nat],
+        [Result_1],
         [],
       ),
-    'evm_address' : IDL.Func([IDL.Opt(IDL.Principal)], [Result_2], ['query']),
-    'evm_sign' : IDL.Func([IDL.Vec(IDL.Nat8)], [Result_3], []),
-    'evm_transfer_tx' : IDL.Func([IDL.Text, IDL.Text, IDL.Nat], [Result_2], []),
+    'evm_address' : IDL.Func([IDL.Opt(IDL.Principal)], [Result_1], ['query']),
+    'evm_deposit_message' : IDL.Func([IDL.Text], [Result_1], ['query']),
+    'evm_sign' : IDL.Func([IDL.Vec(IDL.Nat8)], [Result_5], []),
+    'evm_transfer_tx' : IDL.Func([IDL.Text, IDL.Text, IDL.Nat], [Result_1], []),
+    'evm_unmatched_deposits' : IDL.Func([], [Result_6], ['query']),
     'finalized_logs' : IDL.Func(
         [IDL.Nat32, IDL.Opt(IDL.Nat64)],
         [Result_4],
         ['query'],
       ),
-    'info' : IDL.Func([], [Result_5], ['query']),
-    'my_bridge_log' : IDL.Func([BridgeTx], [Result_6], ['query']),
+    'info' : IDL.Func([], [Result_7], ['query']),
+    'my_bridge_log' : IDL.Func([BridgeTx], [Result_8], ['query']),
+    'my_dead_letter_logs' : IDL.Func([], [Result_4], ['query']),
+    'my_evm_deposits' : IDL.Func([], [Result_9], ['query']),
     'my_finalized_logs' : IDL.Func(
         [IDL.Nat32, IDL.Opt(IDL.Nat64)],
         [Result_4],
@@ -130,66 +380,193 @@ export const idlFactory = ({ IDL }) => {
       ),
     'my_pending_logs' : IDL.Func([], [Result_4], ['query']),
     'pending_logs' : IDL.Func([], [Result_4], ['query']),
-    'sol_transfer_tx' : IDL.Func([IDL.Text, IDL.Nat64], [Result_2], []),
-    'spl_transfer_tx' : IDL.Func([IDL.Text, IDL.Nat], [Result_2], []),
-    'svm_address' : IDL.Func([IDL.Opt(IDL.Principal)], [Result_2], ['query']),
+    'quote_bridge' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Text, IDL.Nat, IDL.Opt(IDL.Text)],
+        [Result_10],
+        ['query'],
+      ),
+    'register_evm_deposit' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Text, IDL.Opt(IDL.Text)],
+        [Result],
+        [],
+      ),
+    'sol_transfer_tx' : IDL.Func([IDL.Text, IDL.Nat64], [Result_1], []),
+    'spl_transfer_tx' : IDL.Func([IDL.Text, IDL.Text, IDL.Nat], [Result_1], []),
+    'svm_address' : IDL.Func([IDL.Opt(IDL.Principal)], [Result_1], ['query']),
     'validate_admin_add_bridges' : IDL.Func(
         [IDL.Vec(IDL.Principal)],
-        [Result_2],
+        [Result_1],
         [],
       ),
     'validate_admin_add_evm_contract' : IDL.Func(
-        [IDL.Text, IDL.Nat64, IDL.Text],
-        [Result_2],
+        [IDL.Text, IDL.Text, IDL.Nat64, IDL.Text],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_add_svm_contract' : IDL.Func(
+        [IDL.Text, IDL.Text],
+        [Result_1],
         [],
       ),
-    'validate_admin_add_svm_contract' : IDL.Func([IDL.Text], [Result_2], []),
+    'validate_admin_add_svm_nonce_account' : IDL.Func([], [Result_1], []),
+    'validate_admin_add_token' : IDL.Func([TokenArgs], [Result_1], []),
+    'validate_admin_cancel_pending' : IDL.Func(
+        [IDL.Text, BridgeTx, IDL.Opt(IDL.Nat64)],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_check_gas_balances' : IDL.Func([], [Result_1], []),
     'validate_admin_collect_fees' : IDL.Func(
-        [IDL.Principal, IDL.Nat],
-        [Result_2],
+        [IDL.Text, IDL.Principal, IDL.Nat],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_mark_finalized' : IDL.Func(
+        [IDL.Text, BridgeTx, BridgeTx, IDL.Opt(IDL.Nat64)],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_reconcile_reserves' : IDL.Func([], [Result_1], []),
+    'validate_admin_release_pending' : IDL.Func(
+        [IDL.Text, BridgeTx, IDL.Opt(IDL.Nat64)],
+        [Result_1],
         [],
       ),
     'validate_admin_remove_bridges' : IDL.Func(
         [IDL.Vec(IDL.Principal)],
-        [Result_2],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_remove_svm_nonce_account' : IDL.Func(
+        [IDL.Text],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_reset_error_rounds' : IDL.Func([], [Result_1], []),
+    'validate_admin_retry_pending' : IDL.Func(
+        [IDL.Text, BridgeTx, IDL.Opt(IDL.Nat64)],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_chain_paused' : IDL.Func(
+        [IDL.Text, IDL.Bool, IDL.Bool],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_evm_gas_limit' : IDL.Func(
+        [IDL.Text, IDL.Opt(GasLimitConfig)],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_evm_max_fee' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Nat)],
+        [Result_1],
         [],
       ),
     'validate_admin_set_evm_providers' : IDL.Func(
         [IDL.Text, IDL.Nat64, IDL.Vec(IDL.Text)],
-        [Result_2],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_evm_rpc_canister' : IDL.Func(
+        [IDL.Text, IDL.Opt(IDL.Principal)],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_evm_tx_timeout' : IDL.Func(
+        [IDL.Text, IDL.Nat64],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_evm_tx_type' : IDL.Func(
+        [IDL.Text, EvmTxType],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_fee_schedule' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Opt(FeeSchedule)],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_gas_minimum' : IDL.Func(
+        [IDL.Text, IDL.Nat],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_mint_burn' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Bool],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_rate_limit' : IDL.Func(
+        [IDL.Text, IDL.Opt(RateLimit)],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_refund_after_retries' : IDL.Func(
+        [IDL.Nat32],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_reserve_tolerance' : IDL.Func(
+        [IDL.Text, IDL.Nat],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_route_paused' : IDL.Func(
+        [IDL.Text, IDL.Text, IDL.Bool],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_rpc_quorum' : IDL.Func(
+        [IDL.Text, IDL.Nat8],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_svm_priority_fee' : IDL.Func(
+        [SvmPriorityFeeConfig],
+        [Result_1],
         [],
       ),
     'validate_admin_set_svm_providers' : IDL.Func(
         [IDL.Vec(IDL.Text)],
-        [Result_2],
+        [Result_1],
         [],
       ),
+    'validate_admin_set_timelock' : IDL.Func(
+        [IDL.Text, IDL.Nat, IDL.Nat64],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_set_token_paused' : IDL.Func(
+        [IDL.Text, IDL.Bool],
+        [Result_1],
+        [],
+      ),
+    'validate_admin_update_token' : IDL.Func([UpdateTokenArgs], [Result_1], []),
   });
 };
 export const init = ({ IDL }) => {
   const UpgradeArgs = IDL.Record({
-    'min_threshold_to_bridge' : IDL.Opt(IDL.Nat),
-    'token_symbol' : IDL.Opt(IDL.Text),
     'governance_canister' : IDL.Opt(IDL.Principal),
-    'token_bridge_fee' : IDL.Opt(IDL.Nat),
-    'token_ledger' : IDL.Opt(IDL.Principal),
-    'token_logo' : IDL.Opt(IDL.Text),
-    'token_name' : IDL.Opt(IDL.Text),
   });
-  const InitArgs = IDL.Record({
+  const TokenArgs = IDL.Record({
+    'decimals' : IDL.Nat8,
     'min_threshold_to_bridge' : IDL.Nat,
-    'token_symbol' : IDL.Text,
+    'logo' : IDL.Text,
+    'name' : IDL.Text,
+    'ledger' : IDL.Principal,
+    'bridge_fee' : IDL.Nat,
+    'symbol' : IDL.Text,
+  });
+  const InitArgs = IDL.Record({
     'governance_canister' : IDL.Opt(IDL.Principal),
-    'token_bridge_fee' : IDL.Nat,
+    'tokens' : IDL.Vec(TokenArgs),
     'key_name' : IDL.Text,
-    'token_decimals' : IDL.Nat8,
-    'token_ledger' : IDL.Principal,
-    'token_logo' : IDL.Text,
-    'token_name' : IDL.Text,
   });
   const CanisterArgs = IDL.Variant({
     'Upgrade' : UpgradeArgs,
     'Init' : InitArgs,
   });
   return [IDL.Opt(CanisterArgs)];
-};
+};
\ No newline at end of file
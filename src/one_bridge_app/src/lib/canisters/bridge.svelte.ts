@@ -4,6 +4,7 @@ import {
   type BridgeTarget,
   type BridgeTx,
   type StateInfo,
+  type TokenInfo as BridgeTokenInfo,
   type _SERVICE
 } from '$declarations/one_bridge_canister/one_bridge_canister.did.js'
 import {
@@ -32,22 +33,30 @@ export {
 
 const base58 = getBase58Codec()
 
+// A token bridged by a bridge canister, a canister bridges one or more tokens.
 export class BridgeCanisterAPI {
   static #bridges: SvelteMap<string, BridgeCanisterAPI> = new SvelteMap()
 
-  static async loadBridge(canisterId: string): Promise<BridgeCanisterAPI> {
-    if (this.#bridges.has(canisterId)) {
-      return this.#bridges.get(canisterId) as BridgeCanisterAPI
+  // Loads the bridge of a token of the canister, its first token if not given.
+  static async loadBridge(
+    canisterId: string,
+    tokenSymbol: string = ''
+  ): Promise<BridgeCanisterAPI> {
+    const key = `${canisterId}:${tokenSymbol}`
+    if (this.#bridges.has(key)) {
+      return this.#bridges.get(key) as BridgeCanisterAPI
     }
 
-    const bridge = new BridgeCanisterAPI(canisterId)
-    this.#bridges.set(canisterId, bridge)
+    const bridge = new BridgeCanisterAPI(canisterId, tokenSymbol)
+    this.#bridges.set(key, bridge)
     await bridge.loadState()
     return bridge
   }
 
   readonly canisterId: Principal
   #actor: _SERVICE
+  #tokenSymbol: string
+  #bridgeToken: BridgeTokenInfo | null = null
   #token: TokenInfo | null = null
   #tokenDisplay: TokenDisplay | null = null
   #tokenLedger: TokenLedgerAPI | null = null
@@ -55,8 +64,9 @@ export class BridgeCanisterAPI {
   #evmRPC: Map<string, EvmRpc> = new Map()
   #state = $state<StateInfo | null>(null)
 
-  private constructor(canisterId: string) {
+  private constructor(canisterId: string, tokenSymbol: string) {
     this.canisterId = Principal.fromText(canisterId)
+    this.#tokenSymbol = tokenSymbol
     this.#actor = createActor<_SERVICE>({
       canisterId: this.canisterId,
       idlFactory: idlFactory
@@ -71,21 +81,27 @@ export class BridgeCanisterAPI {
     return this.#token
   }
 
+  // the token as configured in the bridge canister
+  get bridgeToken(): BridgeTokenInfo | null {
+    return this.#bridgeToken
+  }
+
   get tokenDisplay(): TokenDisplay | null {
     return this.#tokenDisplay
   }
 
   getTokenUrl(chain: string): [string, string] {
-    if (!this.#state) return ['', '']
+    if (!this.#bridgeToken) return ['', '']
     if (chain === 'ICP') {
-      const token = this.#state.token_ledger.toText()
+      const token = this.#bridgeToken.ledger.toText()
       return [token, `https://dashboard.internetcomputer.org/canister/${token}`]
     }
     if (chain === 'SOL') {
-      const token = this.#state.svm_token_address[0]
+      const token = this.#bridgeToken.svm_contract[0]?.[0]
+      if (!token) return ['', '']
       return [token, `https://solscan.io/token/${token}`]
     }
-    const contract = this.#state.evm_token_contracts.find(
+    const contract = this.#bridgeToken.evm_contracts.find(
       ([name, _]) => name === chain
     )?.[1][0]
     if (!contract) return ['', '']
@@ -98,28 +114,28 @@ export class BridgeCanisterAPI {
   }
 
   evmToIcpAmount(chain: string, evmBalance: bigint): bigint {
-    if (!this.#state) return evmBalance
-    const evmDecimals = this.#state.evm_token_contracts.find(
+    if (!this.#bridgeToken) return evmBalance
+    const evmDecimals = this.#bridgeToken.evm_contracts.find(
       ([name, _]) => name === chain
     )?.[1][1]
     if (!evmDecimals) return evmBalance
-    if (this.#state.token_decimals > evmDecimals) {
-      const diff = this.#state.token_decimals - evmDecimals
+    if (this.#bridgeToken.decimals > evmDecimals) {
+      const diff = this.#bridgeToken.decimals - evmDecimals
       return evmBalance * 10n ** BigInt(diff)
     }
-    const diff = evmDecimals - this.#state.token_decimals
+    const diff = evmDecimals - this.#bridgeToken.decimals
     return evmBalance / 10n ** BigInt(diff)
   }
 
   svmToIcpAmount(svmBalance: bigint): bigint {
-    if (!this.#state) return svmBalance
-    const svmDecimals = this.#state.svm_token_address[1]
+    if (!this.#bridgeToken) return svmBalance
+    const svmDecimals = this.#bridgeToken.svm_contract[0]?.[1]
     if (!svmDecimals) return svmBalance
-    if (this.#state.token_decimals > svmDecimals) {
-      const diff = this.#state.token_decimals - svmDecimals
+    if (this.#bridgeToken.decimals > svmDecimals) {
+      const diff = this.#bridgeToken.decimals - svmDecimals
       return svmBalance * 10n ** BigInt(diff)
     }
-    const diff = svmDecimals - this.#state.token_decimals
+    const diff = svmDecimals - this.#bridgeToken.decimals
     return svmBalance / 10n ** BigInt(diff)
   }
 
@@ -181,35 +197,50 @@ export class BridgeCanisterAPI {
 
   async loadState(): Promise<StateInfo> {
     if (this.#state == null) {
-      const state = await this.refreshState()
+      await this.refreshState()
+      const bt = this.#bridgeToken
+      if (!bt) {
+        throw new Error(
+          `token ${this.#tokenSymbol} not found in bridge ${this.canisterId.toText()}`
+        )
+      }
       const token: TokenInfo = {
-        name: state.token_name,
-        symbol: state.token_symbol,
-        decimals: state.token_decimals,
+        name: bt.name,
+        symbol: bt.symbol,
+        decimals: bt.decimals,
         fee: 0n,
-        one: 10n ** BigInt(state.token_decimals),
-        logo: state.token_logo,
-        canisterId: state.token_ledger.toText()
+        one: 10n ** BigInt(bt.decimals),
+        logo: bt.logo,
+        canisterId: bt.ledger.toText()
       }
 
       this.#token = token
-      const td = new TokenDisplay(token, state.min_threshold_to_bridge)
-      td.fee = state.token_bridge_fee
+      const td = new TokenDisplay(token, bt.min_threshold_to_bridge)
+      td.fee = bt.bridge_fee
       this.#tokenDisplay = td
     }
 
     return this.#state as StateInfo
   }
 
+  // Loads the bridges of the other tokens of the canister and of the sub-bridge canisters.
   async loadSubBridges(): Promise<BridgeCanisterAPI[]> {
     const state = await this.loadState()
+    const targets: [string, string][] = [
+      ...state.tokens
+        .filter((t) => t.symbol !== this.#bridgeToken?.symbol)
+        .map((t) => [this.canisterId.toText(), t.symbol] as [string, string]),
+      ...state.sub_bridges.map(
+        (canisterId) => [canisterId.toText(), ''] as [string, string]
+      )
+    ]
     const subBridges = await Promise.all(
-      state.sub_bridges.map(async (canisterId) => {
+      targets.map(async ([canisterId, symbol]) => {
         try {
-          return await BridgeCanisterAPI.loadBridge(canisterId.toText())
+          return await BridgeCanisterAPI.loadBridge(canisterId, symbol)
         } catch (error) {
           console.error(
-            `Failed to load sub-bridge ${canisterId.toText()}:`,
+            `Failed to load sub-bridge ${canisterId} ${symbol}:`,
             error
           )
 
@@ -222,21 +253,29 @@ export class BridgeCanisterAPI {
   }
 
   async refreshState(): Promise<StateInfo> {
-    const state = await this.#actor.info()
-    this.#state = unwrapResult(state, 'call get_state failed')
+    const res = await this.#actor.info()
+    const state = unwrapResult(res, 'call get_state failed')
+    const bt = this.#tokenSymbol
+      ? state.tokens.find((t) => t.symbol === this.#tokenSymbol)
+      : state.tokens[0]
+    this.#bridgeToken = bt || null
+    if (bt) {
+      this.#tokenSymbol = bt.symbol
+    }
+    this.#state = state
     return this.#state as StateInfo
   }
 
   async supportChains(): Promise<Chain[]> {
-    const state = await this.loadState()
+    await this.loadState()
+    const bt = this.#bridgeToken!
     const chains = ['ICP']
-    if (state.svm_token_address[0] !== '11111111111111111111111111111111') {
+    if (bt.svm_contract.length > 0) {
       chains.push('SOL')
     }
-    return [
-      ...chains,
-      ...state.evm_token_contracts.map(([name, _]) => name)
-    ].map(getChain)
+    return [...chains, ...bt.evm_contracts.map(([name, _]) => name)].map(
+      getChain
+    )
   }
 
   async loadICPTokenAPI(): Promise<TokenLedgerAPI> {
@@ -258,14 +297,12 @@ export class BridgeCanisterAPI {
   async loadSvmTokenAPI(): Promise<SvmRpc | null> {
     if (!this.#svmRpc) {
       const state = await this.loadState()
-      if (
-        state.svm_providers.length > 0 &&
-        state.svm_token_address[0] !== '11111111111111111111111111111111'
-      ) {
+      const svmContract = this.#bridgeToken?.svm_contract[0]
+      if (state.svm_providers.length > 0 && svmContract) {
         this.#svmRpc = new SvmRpc(
           state.svm_providers,
-          state.svm_token_address[0],
-          state.svm_token_address[2]
+          svmContract[0],
+          svmContract[2]
         )
         this.#svmRpc.selectProvider()
       }
@@ -280,7 +317,7 @@ export class BridgeCanisterAPI {
     }
 
     const state = await this.loadState()
-    const contract = state.evm_token_contracts.find(
+    const contract = this.#bridgeToken?.evm_contracts.find(
       ([name, _]) => name === chain
     )
     if (!contract) {
@@ -316,9 +353,15 @@ export class BridgeCanisterAPI {
     return unwrapResult(res, 'call my_bridge_log failed')
   }
 
+  // the canister returns logs of all its tokens
+  #isOwnLog(log: BridgeLog): boolean {
+    return log.token === this.#tokenSymbol
+  }
+
   async listMyPendingLogs(): Promise<BridgeLog[]> {
     const res = await this.#actor.my_pending_logs()
-    return unwrapResult(res, 'call my_pending_logs failed')
+    const logs = unwrapResult(res, 'call my_pending_logs failed')
+    return logs.filter((log) => this.#isOwnLog(log))
   }
 
   async listMyFinalizedLogs(
@@ -327,12 +370,15 @@ export class BridgeCanisterAPI {
   ): Promise<BridgeLogInfo[]> {
     const res = await this.#actor.my_finalized_logs(take, prev ? [prev] : [])
     const logs = unwrapResult(res, 'call my_finalized_logs failed')
-    return logs.map((log) => this.toBridgeLogInfo(log))
+    return logs
+      .filter((log) => this.#isOwnLog(log))
+      .map((log) => this.toBridgeLogInfo(log))
   }
 
   async listPendingLogs(): Promise<BridgeLog[]> {
     const res = await this.#actor.pending_logs()
-    return unwrapResult(res, 'call pending_logs failed')
+    const logs = unwrapResult(res, 'call pending_logs failed')
+    return logs.filter((log) => this.#isOwnLog(log))
   }
 
   async listFinalizedLogs(
@@ -341,7 +387,9 @@ export class BridgeCanisterAPI {
   ): Promise<BridgeLogInfo[]> {
     const res = await this.#actor.finalized_logs(take, prev ? [prev] : [])
     const logs = unwrapResult(res, 'call finalized_logs failed')
-    return logs.map((log) => this.toBridgeLogInfo(log))
+    return logs
+      .filter((log) => this.#isOwnLog(log))
+      .map((log) => this.toBridgeLogInfo(log))
   }
 
   async bridge(
@@ -351,6 +399,7 @@ export class BridgeCanisterAPI {
     toAddr?: string
   ): Promise<BridgingProgress> {
     const res = await this.#actor.bridge(
+      this.#tokenSymbol,
       fromChain,
       toChain,
       icpAmount,
@@ -366,7 +415,12 @@ export class BridgeCanisterAPI {
     toAddr: string,
     icpAmount: bigint
   ): Promise<string> {
-    const tx = await this.#actor.erc20_transfer_tx(chain, toAddr, icpAmount)
+    const tx = await this.#actor.erc20_transfer_tx(
+      this.#tokenSymbol,
+      chain,
+      toAddr,
+      icpAmount
+    )
     return unwrapResult(tx, 'call erc20_transfer_tx failed')
   }
 
@@ -382,7 +436,11 @@ export class BridgeCanisterAPI {
 
   // return signed erc20 transfer transaction
   async buildSplTransferTx(toAddr: string, icpAmount: bigint): Promise<string> {
-    const tx = await this.#actor.spl_transfer_tx(toAddr, icpAmount)
+    const tx = await this.#actor.spl_transfer_tx(
+      this.#tokenSymbol,
+      toAddr,
+      icpAmount
+    )
     return unwrapResult(tx, 'call spl_transfer_tx failed')
   }
 
@@ -396,7 +454,7 @@ export class BridgeCanisterAPI {
     return {
       id: log.id[0] || 0n,
       user: log.user.toText(),
-      token: this.#token?.symbol || '',
+      token: log.token,
       from: getChainName(log.from),
       to: getChainName(log.to),
       amount: this.displayAmount(log.icp_amount),
@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use alloy_eips::eip2718::Encodable2718;
-use alloy_primitives::{Address, Bytes};
+use alloy_primitives::{Address, Bytes, Signature};
 use candid::Principal;
 use ic_auth_types::ByteBufB64;
 use serde_bytes::ByteBuf;
@@ -39,13 +39,21 @@ fn my_pending_logs() -> Result<Vec<store::BridgeLog>, String> {
     let rt = store::state::with(|s| {
         s.pending
             .iter()
-            .filter_map(|item| {
-                if item.user == caller {
-                    Some(item.clone())
-                } else {
-                    None
-                }
-            })
+            .filter(|item| item.user == caller)
+            .cloned()
+            .collect::<Vec<store::BridgeLog>>()
+    });
+    Ok(rt)
+}
+
+#[ic_cdk::query]
+fn my_dead_letter_logs() -> Result<Vec<store::BridgeLog>, String> {
+    let caller = msg_caller()?;
+    let rt = store::state::with(|s| {
+        s.dead_letters
+            .iter()
+            .filter(|item| item.user == caller)
+            .cloned()
             .collect::<Vec<store::BridgeLog>>()
     });
     Ok(rt)
@@ -72,6 +80,12 @@ fn pending_logs() -> Result<Vec<store::BridgeLog>, String> {
     Ok(rt)
 }
 
+#[ic_cdk::query]
+fn dead_letter_logs() -> Result<Vec<store::BridgeLog>, String> {
+    let rt = store::state::with(|s| s.dead_letters.clone());
+    Ok(rt)
+}
+
 #[ic_cdk::query]
 fn finalized_logs(take: u32, prev: Option<u64>) -> Result<Vec<store::BridgeLog>, String> {
     let take = take.clamp(2, 100) as usize;
@@ -81,6 +95,7 @@ fn finalized_logs(take: u32, prev: Option<u64>) -> Result<Vec<store::BridgeLog>,
 
 #[ic_cdk::update]
 async fn bridge(
+    token: String,
     from_chain: String,
     to_chain: String,
     icp_amount: u128,
@@ -88,34 +103,123 @@ async fn bridge(
 ) -> Result<store::BridgeTx, String> {
     let caller = msg_caller()?;
     let now_ms = ic_cdk::api::time() / 1_000_000;
-    store::state::bridge(from_chain, to_chain, icp_amount, to, caller, now_ms).await
+    store::state::bridge(token, from_chain, to_chain, icp_amount, to, caller, now_ms).await
+}
+
+#[ic_cdk::query]
+fn quote_bridge(
+    token: String,
+    from_chain: String,
+    to_chain: String,
+    icp_amount: u128,
+    to: Option<String>,
+) -> Result<store::BridgeQuote, String> {
+    let caller = msg_caller()?;
+    let now_ms = ic_cdk::api::time() / 1_000_000;
+    store::state::quote_bridge(
+        &token,
+        &from_chain,
+        &to_chain,
+        icp_amount,
+        to.as_deref(),
+        &caller,
+        now_ms,
+    )
+}
+
+#[ic_cdk::query]
+fn bridge_allowance(
+    token: String,
+    from_chain: String,
+    to_chain: String,
+) -> Result<Option<u128>, String> {
+    let caller = msg_caller()?;
+    let now_ms = ic_cdk::api::time() / 1_000_000;
+    store::state::bridge_allowance(&token, &caller, &from_chain, &to_chain, now_ms)
+}
+
+#[ic_cdk::query]
+fn evm_deposit_message(sender: String) -> Result<String, String> {
+    let sender = sender
+        .parse::<Address>()
+        .map_err(|err| format!("invalid sender address: {}", err))?;
+    let caller = msg_caller()?;
+    Ok(store::state::evm_deposit_message(&sender, &caller))
+}
+
+#[ic_cdk::update]
+async fn register_evm_deposit(
+    sender: String,
+    signature: String,
+    to_chain: String,
+    to: Option<String>,
+) -> Result<(), String> {
+    let sender = sender
+        .parse::<Address>()
+        .map_err(|err| format!("invalid sender address: {}", err))?;
+    let signature =
+        Signature::from_str(&signature).map_err(|err| format!("invalid signature: {}", err))?;
+    let caller = msg_caller()?;
+    let now_ms = ic_cdk::api::time() / 1_000_000;
+    store::state::register_evm_deposit(caller, sender, &signature, to_chain, to, now_ms).await
+}
+
+#[ic_cdk::query]
+fn my_evm_deposits() -> Result<Vec<(String, store::EvmDepositIntent)>, String> {
+    let caller = msg_caller()?;
+    let rt = store::state::with(|s| {
+        s.evm_deposit_intents
+            .iter()
+            .filter(|(_, intent)| intent.user == caller)
+            .map(|(sender, intent)| (sender.to_string(), intent.clone()))
+            .collect::<Vec<_>>()
+    });
+    Ok(rt)
+}
+
+#[ic_cdk::query]
+fn evm_unmatched_deposits() -> Result<Vec<store::EvmDeposit>, String> {
+    let rt = store::state::with(|s| s.evm_unmatched_deposits.clone());
+    Ok(rt)
 }
 
 #[ic_cdk::update]
-async fn erc20_transfer_tx(chain: String, to: String, icp_amount: u128) -> Result<String, String> {
+async fn erc20_transfer_tx(
+    token: String,
+    chain: String,
+    to: String,
+    icp_amount: u128,
+) -> Result<String, String> {
     let to_addr = to
         .parse::<Address>()
         .map_err(|err| format!("invalid to address: {}", err))?;
     let caller = msg_caller()?;
     let now_ms = ic_cdk::api::time() / 1_000_000;
-    let (_, signed_tx) =
-        store::state::build_erc20_transfer_tx(&chain, &caller, &to_addr, icp_amount, now_ms)
-            .await?;
+    let (_, signed_tx) = store::state::build_erc20_transfer_tx(
+        &token, &chain, &caller, &to_addr, icp_amount, now_ms,
+    )
+    .await?;
     let data = signed_tx.encoded_2718();
     Ok(Bytes::from(data).to_string())
 }
 
 #[ic_cdk::update]
-async fn erc20_transfer(chain: String, to: String, icp_amount: u128) -> Result<String, String> {
+async fn erc20_transfer(
+    token: String,
+    chain: String,
+    to: String,
+    icp_amount: u128,
+) -> Result<String, String> {
     let to_addr = to
         .parse::<Address>()
         .map_err(|err| format!("invalid to address: {}", err))?;
     let caller = msg_caller()?;
     let now_ms = ic_cdk::api::time() / 1_000_000;
-    let (cli, signed_tx) =
-        store::state::build_erc20_transfer_tx(&chain, &caller, &to_addr, icp_amount, now_ms)
-            .await?;
-    let tx_hash = signed_tx.hash().to_string();
+    let (cli, signed_tx) = store::state::build_erc20_transfer_tx(
+        &token, &chain, &caller, &to_addr, icp_amount, now_ms,
+    )
+    .await?;
+    let tx_hash = signed_tx.tx_hash().to_string();
 
     let data = signed_tx.encoded_2718();
     let _ = cli
@@ -139,12 +243,12 @@ async fn evm_transfer_tx(chain: String, to: String, evm_amount: u128) -> Result<
 }
 
 #[ic_cdk::update]
-async fn spl_transfer_tx(to: String, icp_amount: u128) -> Result<String, String> {
+async fn spl_transfer_tx(token: String, to: String, icp_amount: u128) -> Result<String, String> {
     let to_addr = Pubkey::from_str(&to).map_err(|err| format!("invalid to address: {}", err))?;
     let caller = msg_caller()?;
     let now_ms = ic_cdk::api::time() / 1_000_000;
     let (_, signed_tx) =
-        store::state::build_spl_transfer_tx(&caller, &to_addr, icp_amount, now_ms).await?;
+        store::state::build_spl_transfer_tx(&token, &caller, &to_addr, icp_amount, now_ms).await?;
     let data = bincode::serialize(&signed_tx)
         .map_err(|err| format!("failed to serialize signed tx: {}", err))?;
     Ok(ByteBufB64::from(data).to_base64())
@@ -1,14 +1,26 @@
 use alloy_primitives::Address;
-use candid::Principal;
+use candid::{CandidType, Principal};
+use serde::Deserialize;
 use std::collections::BTreeSet;
 use url::Url;
 
 use crate::{
+    api_init::TokenArgs,
     helper::{pretty_format, validate_principals},
     store,
     svm::{Pubkey, TokenAccountType, get_token_account},
 };
 
+#[derive(Clone, Debug, CandidType, Deserialize)]
+pub struct UpdateTokenArgs {
+    pub symbol: String,
+    pub name: Option<String>,
+    pub logo: Option<String>,
+    pub bridge_fee: Option<u128>,
+    pub min_threshold_to_bridge: Option<u128>,
+    pub refund_fee: Option<u128>,
+}
+
 #[ic_cdk::update(guard = "is_controller")]
 fn admin_add_bridges(args: BTreeSet<Principal>) -> Result<(), String> {
     validate_principals(&args)?;
@@ -40,13 +52,71 @@ fn validate_admin_remove_bridges(args: BTreeSet<Principal>) -> Result<String, St
     pretty_format(&(args,))
 }
 
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_add_token(args: TokenArgs) -> Result<(), String> {
+    check_admin_add_token(&args)?;
+    store::state::with_mut(|s| {
+        s.tokens.insert(args.symbol.clone(), args.into());
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_add_token(args: TokenArgs) -> Result<String, String> {
+    check_admin_add_token(&args)?;
+    pretty_format(&(args,))
+}
+
+fn check_admin_add_token(args: &TokenArgs) -> Result<(), String> {
+    args.validate()?;
+    store::state::with(|s| {
+        if s.tokens.contains_key(&args.symbol) {
+            return Err(format!("token {} already exists", args.symbol));
+        }
+        if s.tokens.values().any(|t| t.ledger == args.ledger) {
+            return Err(format!("token ledger {} already exists", args.ledger));
+        }
+        Ok(())
+    })
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_update_token(args: UpdateTokenArgs) -> Result<(), String> {
+    store::state::with_mut(|s| {
+        let token = s.token_mut(&args.symbol)?;
+        if let Some(name) = args.name {
+            token.name = name;
+        }
+        if let Some(logo) = args.logo {
+            token.logo = logo;
+        }
+        if let Some(bridge_fee) = args.bridge_fee {
+            token.bridge_fee = bridge_fee;
+        }
+        if let Some(min_threshold_to_bridge) = args.min_threshold_to_bridge {
+            token.min_threshold_to_bridge = min_threshold_to_bridge;
+        }
+        if let Some(refund_fee) = args.refund_fee {
+            token.refund_fee = refund_fee;
+        }
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_update_token(args: UpdateTokenArgs) -> Result<String, String> {
+    store::state::with(|s| s.token(&args.symbol).map(|_| ()))?;
+    pretty_format(&(args,))
+}
+
 #[ic_cdk::update(guard = "is_controller")]
 async fn admin_add_evm_contract(
+    token: String,
     chain_name: String,
     chain_id: u64,
     address: String,
 ) -> Result<(), String> {
-    let address = check_admin_add_evm_contract(&chain_name, chain_id, &address)?;
+    let address = check_admin_add_evm_contract(&token, &chain_name, chain_id, &address)?;
     let cli = store::state::evm_client(&chain_name);
     let now_ms = ic_cdk::api::time() / 1_000_000;
     let (cid, gas_price, max_priority_fee_per_gas, decimals) = futures::future::try_join4(
@@ -65,7 +135,8 @@ async fn admin_add_evm_contract(
     }
 
     store::state::with_mut(|s| {
-        s.evm_token_contracts
+        s.token_mut(&token)?
+            .evm_contracts
             .insert(chain_name.clone(), (address, decimals, chain_id));
         s.evm_latest_gas
             .insert(chain_name, (now_ms, gas_price, max_priority_fee_per_gas));
@@ -75,15 +146,17 @@ async fn admin_add_evm_contract(
 
 #[ic_cdk::update]
 fn validate_admin_add_evm_contract(
+    token: String,
     chain_name: String,
     chain_id: u64,
     address: String,
 ) -> Result<String, String> {
-    check_admin_add_evm_contract(&chain_name, chain_id, &address)?;
-    pretty_format(&(chain_name, chain_id, address))
+    check_admin_add_evm_contract(&token, &chain_name, chain_id, &address)?;
+    pretty_format(&(token, chain_name, chain_id, address))
 }
 
 fn check_admin_add_evm_contract(
+    token: &str,
     chain_name: &str,
     chain_id: u64,
     address: &str,
@@ -99,15 +172,24 @@ fn check_admin_add_evm_contract(
         .map_err(|err| format!("invalid address {address}: {err:?}"))?;
 
     store::state::with(|s| {
-        if s.evm_token_contracts.contains_key(chain_name) {
-            return Err("chain_id already exists".to_string());
+        if s.token(token)?.evm_contracts.contains_key(chain_name) {
+            return Err(format!(
+                "chain {chain_name} already exists for token {token}"
+            ));
         }
 
-        if s.evm_token_contracts
-            .values()
-            .any(|(_, _, cid)| *cid == chain_id)
-        {
-            return Err("chain_id already exists".to_string());
+        // the same chain must have the same chain_id across all tokens
+        for (name, (_, _, cid)) in s.tokens.values().flat_map(|t| t.evm_contracts.iter()) {
+            if name == chain_name && *cid != chain_id {
+                return Err(format!(
+                    "chain {chain_name} already exists with chain_id {cid}"
+                ));
+            }
+            if name != chain_name && *cid == chain_id {
+                return Err(format!(
+                    "chain_id {chain_id} already exists for chain {name}"
+                ));
+            }
         }
         Ok(())
     })?;
@@ -115,8 +197,8 @@ fn check_admin_add_evm_contract(
 }
 
 #[ic_cdk::update(guard = "is_controller")]
-async fn admin_add_svm_contract(address: String) -> Result<(), String> {
-    let addr = check_admin_add_svm_contract(&address)?;
+async fn admin_add_svm_contract(token: String, address: String) -> Result<(), String> {
+    let addr = check_admin_add_svm_contract(&token, &address)?;
     let cli = store::state::svm_client();
     let now_ms = ic_cdk::api::time() / 1_000_000;
     let account = cli.get_account_info(now_ms, &address).await?;
@@ -130,23 +212,23 @@ async fn admin_add_svm_contract(address: String) -> Result<(), String> {
     };
 
     store::state::with_mut(|s| {
-        s.svm_token_address = (addr, decimals, token_program);
-    });
-    Ok(())
+        s.token_mut(&token)?.svm_contract = Some((addr, decimals, token_program));
+        Ok(())
+    })
 }
 
 #[ic_cdk::update]
-fn validate_admin_add_svm_contract(address: String) -> Result<String, String> {
-    check_admin_add_svm_contract(&address)?;
-    pretty_format(&(address,))
+fn validate_admin_add_svm_contract(token: String, address: String) -> Result<String, String> {
+    check_admin_add_svm_contract(&token, &address)?;
+    pretty_format(&(token, address))
 }
 
-fn check_admin_add_svm_contract(address: &str) -> Result<Pubkey, String> {
+fn check_admin_add_svm_contract(token: &str, address: &str) -> Result<Pubkey, String> {
     let addr =
         Pubkey::try_from(address).map_err(|err| format!("invalid address {address}: {err:?}"))?;
 
     store::state::with(|s| {
-        if s.svm_token_address.0 != Pubkey::default() {
+        if s.token(token)?.svm_contract.is_some() {
             return Err("address already exists".to_string());
         }
         Ok(())
@@ -154,6 +236,53 @@ fn check_admin_add_svm_contract(address: &str) -> Result<Pubkey, String> {
     Ok(addr)
 }
 
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_mint_burn(token: String, chain: String, enabled: bool) -> Result<(), String> {
+    check_admin_set_mint_burn(&token, &chain)?;
+    store::state::with_mut(|s| {
+        let t = s.token_mut(&token)?;
+        if enabled {
+            t.mint_burn_chains.insert(chain);
+        } else {
+            t.mint_burn_chains.remove(&chain);
+        }
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_mint_burn(
+    token: String,
+    chain: String,
+    enabled: bool,
+) -> Result<String, String> {
+    check_admin_set_mint_burn(&token, &chain)?;
+    pretty_format(&(token, chain, enabled))
+}
+
+fn check_admin_set_mint_burn(token: &str, chain: &str) -> Result<(), String> {
+    store::state::with(|s| {
+        let t = s.token(token)?;
+        let exists = if chain == "SOL" {
+            t.svm_contract.is_some()
+        } else {
+            t.evm_contracts.contains_key(chain)
+        };
+        if !exists {
+            return Err(format!("{token} contract on chain {chain} not found"));
+        }
+        // switching modes with in-flight tasks would burn or mint against the wrong balance
+        if s.pending.iter().any(|log| {
+            log.token == token && (log.from.chain_name() == chain || log.to.chain_name() == chain)
+        }) {
+            return Err(format!(
+                "{token} has pending bridging tasks on chain {chain}"
+            ));
+        }
+        Ok(())
+    })
+}
+
 #[ic_cdk::update(guard = "is_controller")]
 fn admin_set_evm_providers(
     chain_name: String,
@@ -222,45 +351,696 @@ fn validate_admin_set_svm_providers(providers: Vec<String>) -> Result<String, St
 }
 
 #[ic_cdk::update(guard = "is_controller")]
-async fn admin_collect_fees(to: Principal, icp_amount: u128) -> Result<store::BridgeTx, String> {
-    let ledger = store::state::with(|s| {
+fn admin_set_rpc_quorum(chain: String, quorum: u8) -> Result<(), String> {
+    check_admin_set_rpc_quorum(&chain, quorum)?;
+    store::state::with_mut(|s| {
+        s.rpc_quorums.insert(chain, quorum);
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_rpc_quorum(chain: String, quorum: u8) -> Result<String, String> {
+    check_admin_set_rpc_quorum(&chain, quorum)?;
+    pretty_format(&(chain, quorum))
+}
+
+fn check_admin_set_rpc_quorum(chain: &str, quorum: u8) -> Result<(), String> {
+    if quorum == 0 {
+        return Err("quorum must be at least 1".to_string());
+    }
+
+    store::state::with(|s| {
+        let providers = if chain == "SOL" {
+            s.svm_providers.len()
+        } else {
+            s.evm_providers
+                .get(chain)
+                .map(|(_, providers)| providers.len())
+                .ok_or_else(|| format!("chain {chain} providers not found"))?
+        };
+        if quorum as usize > providers {
+            return Err(format!(
+                "quorum {quorum} exceeds the number of providers {providers} of chain {chain}"
+            ));
+        }
+        Ok(())
+    })
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_chain_paused(chain: String, paused: bool, halt_outbound: bool) -> Result<(), String> {
+    check_chain_exists(&chain)?;
+    store::state::set_chain_paused(chain, paused, halt_outbound);
+    Ok(())
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_chain_paused(
+    chain: String,
+    paused: bool,
+    halt_outbound: bool,
+) -> Result<String, String> {
+    check_chain_exists(&chain)?;
+    pretty_format(&(chain, paused, halt_outbound))
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_route_paused(
+    from_chain: String,
+    to_chain: String,
+    paused: bool,
+) -> Result<(), String> {
+    check_chain_exists(&from_chain)?;
+    check_chain_exists(&to_chain)?;
+    store::state::with_mut(|s| {
+        let route = (from_chain, to_chain);
+        if paused {
+            s.paused_routes.insert(route);
+        } else {
+            s.paused_routes.remove(&route);
+        }
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_route_paused(
+    from_chain: String,
+    to_chain: String,
+    paused: bool,
+) -> Result<String, String> {
+    check_chain_exists(&from_chain)?;
+    check_chain_exists(&to_chain)?;
+    pretty_format(&(from_chain, to_chain, paused))
+}
+
+fn check_chain_exists(chain: &str) -> Result<(), String> {
+    if chain == "ICP" || chain == "SOL" {
+        return Ok(());
+    }
+    store::state::with(|s| {
+        if s.evm_providers.contains_key(chain) {
+            Ok(())
+        } else {
+            Err(format!("chain {chain} not found"))
+        }
+    })
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_evm_gas_limit(
+    chain: String,
+    config: Option<store::GasLimitConfig>,
+) -> Result<(), String> {
+    check_admin_set_evm_gas_limit(&chain, config.as_ref())?;
+    store::state::with_mut(|s| {
+        match config {
+            Some(config) => s.evm_gas_limits.insert(chain, config),
+            None => s.evm_gas_limits.remove(&chain),
+        };
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_evm_gas_limit(
+    chain: String,
+    config: Option<store::GasLimitConfig>,
+) -> Result<String, String> {
+    check_admin_set_evm_gas_limit(&chain, config.as_ref())?;
+    pretty_format(&(chain, config))
+}
+
+fn check_admin_set_evm_gas_limit(
+    chain: &str,
+    config: Option<&store::GasLimitConfig>,
+) -> Result<(), String> {
+    if chain == "ICP" || chain == "SOL" {
+        return Err(format!("chain {chain} is not an EVM chain"));
+    }
+    check_chain_exists(chain)?;
+    if let Some(config) = config {
+        if !(100..=300).contains(&config.multiplier_pct) {
+            return Err("multiplier_pct must be between 100 and 300".to_string());
+        }
+        if config.cap < 21_000 {
+            return Err("cap must be at least 21000".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_evm_tx_type(chain: String, tx_type: store::EvmTxType) -> Result<(), String> {
+    check_admin_set_evm_tx_type(&chain)?;
+    store::state::with_mut(|s| {
+        // cached gas prices of another transaction type lack the priority fee
+        s.evm_latest_gas.remove(&chain);
+        s.evm_tx_types.insert(chain, tx_type);
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_evm_tx_type(
+    chain: String,
+    tx_type: store::EvmTxType,
+) -> Result<String, String> {
+    check_admin_set_evm_tx_type(&chain)?;
+    pretty_format(&(chain, tx_type))
+}
+
+fn check_admin_set_evm_tx_type(chain: &str) -> Result<(), String> {
+    if chain == "ICP" || chain == "SOL" {
+        return Err(format!("chain {chain} is not an EVM chain"));
+    }
+    check_chain_exists(chain)
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_evm_tx_timeout(chain: String, timeout_secs: u64) -> Result<(), String> {
+    check_admin_set_evm_tx_timeout(&chain, timeout_secs)?;
+    store::state::with_mut(|s| {
+        s.evm_tx_timeouts.insert(chain, timeout_secs);
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_evm_tx_timeout(chain: String, timeout_secs: u64) -> Result<String, String> {
+    check_admin_set_evm_tx_timeout(&chain, timeout_secs)?;
+    pretty_format(&(chain, timeout_secs))
+}
+
+fn check_admin_set_evm_tx_timeout(chain: &str, timeout_secs: u64) -> Result<(), String> {
+    if chain == "ICP" || chain == "SOL" {
+        return Err(format!("chain {chain} is not an EVM chain"));
+    }
+    check_chain_exists(chain)?;
+    if timeout_secs > 0 && timeout_secs < 30 {
+        return Err("timeout_secs must be 0 (disabled) or at least 30 seconds".to_string());
+    }
+    Ok(())
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_evm_max_fee(chain: String, max_fee_per_gas: Option<u128>) -> Result<(), String> {
+    check_admin_set_evm_max_fee(&chain, max_fee_per_gas)?;
+    store::state::with_mut(|s| {
+        match max_fee_per_gas {
+            Some(max) => s.evm_max_fees.insert(chain, max),
+            None => s.evm_max_fees.remove(&chain),
+        };
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_evm_max_fee(
+    chain: String,
+    max_fee_per_gas: Option<u128>,
+) -> Result<String, String> {
+    check_admin_set_evm_max_fee(&chain, max_fee_per_gas)?;
+    pretty_format(&(chain, max_fee_per_gas))
+}
+
+fn check_admin_set_evm_max_fee(chain: &str, max_fee_per_gas: Option<u128>) -> Result<(), String> {
+    if chain == "ICP" || chain == "SOL" {
+        return Err(format!("chain {chain} is not an EVM chain"));
+    }
+    check_chain_exists(chain)?;
+    if max_fee_per_gas == Some(0) {
+        return Err("max_fee_per_gas must be greater than 0".to_string());
+    }
+    Ok(())
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_evm_rpc_canister(chain: String, canister: Option<Principal>) -> Result<(), String> {
+    check_admin_set_evm_rpc_canister(&chain)?;
+    store::state::with_mut(|s| {
+        match canister {
+            Some(canister) => s.evm_rpc_canisters.insert(chain, canister),
+            None => s.evm_rpc_canisters.remove(&chain),
+        };
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_evm_rpc_canister(
+    chain: String,
+    canister: Option<Principal>,
+) -> Result<String, String> {
+    check_admin_set_evm_rpc_canister(&chain)?;
+    pretty_format(&(chain, canister))
+}
+
+fn check_admin_set_evm_rpc_canister(chain: &str) -> Result<(), String> {
+    store::state::with(|s| {
+        if !s.evm_providers.contains_key(chain) {
+            return Err(format!("chain {chain} providers not found"));
+        }
+        Ok(())
+    })
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_retry_pending(
+    token: String,
+    from_tx: store::BridgeTx,
+    from_log_index: Option<u64>,
+) -> Result<(), String> {
+    store::state::retry_task(&token, &from_tx, from_log_index)
+}
+
+#[ic_cdk::update]
+fn validate_admin_retry_pending(
+    token: String,
+    from_tx: store::BridgeTx,
+    from_log_index: Option<u64>,
+) -> Result<String, String> {
+    store::state::with(|s| {
+        store::state::find_task(s, &token, &from_tx, from_log_index).map(|_| ())
+    })?;
+    pretty_format(&(token, from_tx, from_log_index))
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_cancel_pending(
+    token: String,
+    from_tx: store::BridgeTx,
+    from_log_index: Option<u64>,
+) -> Result<(), String> {
+    store::state::cancel_task(&token, &from_tx, from_log_index)
+}
+
+#[ic_cdk::update]
+fn validate_admin_cancel_pending(
+    token: String,
+    from_tx: store::BridgeTx,
+    from_log_index: Option<u64>,
+) -> Result<String, String> {
+    store::state::with(|s| {
+        let task = store::state::find_task(s, &token, &from_tx, from_log_index)?;
+        store::state::check_cancel_task(task)
+    })?;
+    pretty_format(&(token, from_tx, from_log_index))
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_release_pending(
+    token: String,
+    from_tx: store::BridgeTx,
+    from_log_index: Option<u64>,
+) -> Result<(), String> {
+    store::state::release_task(&token, &from_tx, from_log_index)
+}
+
+#[ic_cdk::update]
+fn validate_admin_release_pending(
+    token: String,
+    from_tx: store::BridgeTx,
+    from_log_index: Option<u64>,
+) -> Result<String, String> {
+    store::state::with(|s| {
+        let task = store::state::find_task(s, &token, &from_tx, from_log_index)?;
+        store::state::check_release_task(task)
+    })?;
+    pretty_format(&(token, from_tx, from_log_index))
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+async fn admin_mark_finalized(
+    token: String,
+    from_tx: store::BridgeTx,
+    to_tx: store::BridgeTx,
+    from_log_index: Option<u64>,
+) -> Result<(), String> {
+    let now_ms = ic_cdk::api::time() / 1_000_000;
+    store::state::mark_task_finalized(&token, &from_tx, from_log_index, to_tx, now_ms).await
+}
+
+#[ic_cdk::update]
+async fn validate_admin_mark_finalized(
+    token: String,
+    from_tx: store::BridgeTx,
+    to_tx: store::BridgeTx,
+    from_log_index: Option<u64>,
+) -> Result<String, String> {
+    let now_ms = ic_cdk::api::time() / 1_000_000;
+    let task = store::state::with(|s| {
+        store::state::find_task(s, &token, &from_tx, from_log_index).cloned()
+    })?;
+    store::state::verify_mark_finalized(&task, &to_tx, now_ms).await?;
+    pretty_format(&(token, from_tx, to_tx, from_log_index))
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_reset_error_rounds() -> Result<(), String> {
+    store::state::reset_error_rounds();
+    Ok(())
+}
+
+#[ic_cdk::update]
+fn validate_admin_reset_error_rounds() -> Result<String, String> {
+    Ok("reset the error counters of all pending tasks".to_string())
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_rate_limit(token: String, limit: Option<store::RateLimit>) -> Result<(), String> {
+    check_admin_set_rate_limit(&limit)?;
+    store::state::with_mut(|s| {
+        s.token_mut(&token)?.rate_limit = limit;
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_rate_limit(
+    token: String,
+    limit: Option<store::RateLimit>,
+) -> Result<String, String> {
+    check_admin_set_rate_limit(&limit)?;
+    store::state::with(|s| s.token(&token).map(|_| ()))?;
+    pretty_format(&(token, limit))
+}
+
+fn check_admin_set_rate_limit(limit: &Option<store::RateLimit>) -> Result<(), String> {
+    if let Some(limit) = limit
+        && (limit.window_secs < 60 || limit.window_secs > 30 * 24 * 3600)
+    {
+        return Err("window_secs must be between 1 minute and 30 days".to_string());
+    }
+    Ok(())
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_fee_schedule(
+    token: String,
+    to_chain: String,
+    schedule: Option<store::FeeSchedule>,
+) -> Result<(), String> {
+    store::state::with(|s| check_admin_set_fee_schedule(s, &token, &to_chain, &schedule))?;
+    store::state::with_mut(|s| {
+        let t = s.token_mut(&token)?;
+        match schedule {
+            Some(schedule) => t.fee_schedules.insert(to_chain, schedule),
+            None => t.fee_schedules.remove(&to_chain),
+        };
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_fee_schedule(
+    token: String,
+    to_chain: String,
+    schedule: Option<store::FeeSchedule>,
+) -> Result<String, String> {
+    store::state::with(|s| check_admin_set_fee_schedule(s, &token, &to_chain, &schedule))?;
+    pretty_format(&(token, to_chain, schedule))
+}
+
+fn check_admin_set_fee_schedule(
+    s: &store::State,
+    token: &str,
+    to_chain: &str,
+    schedule: &Option<store::FeeSchedule>,
+) -> Result<(), String> {
+    let t = s.token(token)?;
+    if let Some(fs) = schedule {
+        let configured = match to_chain {
+            "ICP" => true,
+            "SOL" => t.svm_contract.is_some(),
+            chain => t.evm_contracts.contains_key(chain),
+        };
+        if !configured {
+            return Err(format!(
+                "to_chain {to_chain} not found or not supported for token {token}"
+            ));
+        }
+        if fs.bps > 10_000 {
+            return Err("bps must be at most 10000".to_string());
+        }
+        if fs.max > 0 && fs.min > fs.max {
+            return Err("min must not exceed max".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_timelock(token: String, threshold: u128, delay_secs: u64) -> Result<(), String> {
+    check_admin_set_timelock(delay_secs)?;
+    store::state::with_mut(|s| {
+        let t = s.token_mut(&token)?;
+        t.timelock_threshold = threshold;
+        t.timelock_secs = delay_secs;
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_timelock(
+    token: String,
+    threshold: u128,
+    delay_secs: u64,
+) -> Result<String, String> {
+    check_admin_set_timelock(delay_secs)?;
+    store::state::with(|s| s.token(&token).map(|_| ()))?;
+    pretty_format(&(token, threshold, delay_secs))
+}
+
+fn check_admin_set_timelock(delay_secs: u64) -> Result<(), String> {
+    if delay_secs > 7 * 24 * 3600 {
+        return Err("delay_secs must be at most 7 days".to_string());
+    }
+    Ok(())
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_token_paused(token: String, paused: bool) -> Result<(), String> {
+    store::state::with_mut(|s| {
+        let t = s.token_mut(&token)?;
+        t.paused = paused;
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_token_paused(token: String, paused: bool) -> Result<String, String> {
+    store::state::with(|s| s.token(&token).map(|_| ()))?;
+    pretty_format(&(token, paused))
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_reserve_tolerance(token: String, tolerance: u128) -> Result<(), String> {
+    store::state::with_mut(|s| {
+        let t = s.token_mut(&token)?;
+        t.reserve_tolerance = tolerance;
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_reserve_tolerance(token: String, tolerance: u128) -> Result<String, String> {
+    store::state::with(|s| s.token(&token).map(|_| ()))?;
+    pretty_format(&(token, tolerance))
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+async fn admin_reconcile_reserves() -> Result<(), String> {
+    store::state::reconcile_reserves().await;
+    Ok(())
+}
+
+#[ic_cdk::update]
+fn validate_admin_reconcile_reserves() -> Result<String, String> {
+    Ok("reconcile the reserves of all tokens".to_string())
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_gas_minimum(chain: String, minimum: u128) -> Result<(), String> {
+    check_admin_set_gas_minimum(&chain)?;
+    store::state::with_mut(|s| {
+        if minimum == 0 {
+            s.gas_minimums.remove(&chain);
+        } else {
+            s.gas_minimums.insert(chain, minimum);
+        }
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_gas_minimum(chain: String, minimum: u128) -> Result<String, String> {
+    check_admin_set_gas_minimum(&chain)?;
+    pretty_format(&(chain, minimum))
+}
+
+fn check_admin_set_gas_minimum(chain: &str) -> Result<(), String> {
+    if chain == "ICP" {
+        return Err("chain ICP does not need gas".to_string());
+    }
+    check_chain_exists(chain)
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+async fn admin_check_gas_balances() -> Result<(), String> {
+    store::state::check_gas_balances().await;
+    Ok(())
+}
+
+#[ic_cdk::update]
+fn validate_admin_check_gas_balances() -> Result<String, String> {
+    Ok("check the gas balances of the canister's addresses on all chains".to_string())
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_svm_priority_fee(config: store::SvmPriorityFeeConfig) -> Result<(), String> {
+    check_admin_set_svm_priority_fee(&config)?;
+    store::state::with_mut(|s| {
+        s.svm_priority_fee = config;
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_svm_priority_fee(
+    config: store::SvmPriorityFeeConfig,
+) -> Result<String, String> {
+    check_admin_set_svm_priority_fee(&config)?;
+    pretty_format(&config)
+}
+
+fn check_admin_set_svm_priority_fee(config: &store::SvmPriorityFeeConfig) -> Result<(), String> {
+    if !(10_000..=1_400_000).contains(&config.compute_unit_limit) {
+        return Err("compute_unit_limit must be between 10000 and 1400000".to_string());
+    }
+    if config.percentile > 100 {
+        return Err("percentile must be at most 100".to_string());
+    }
+    Ok(())
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+async fn admin_add_svm_nonce_account() -> Result<String, String> {
+    check_admin_add_svm_nonce_account()?;
+    let now_ms = ic_cdk::api::time() / 1_000_000;
+    let nonce_account = store::state::create_svm_nonce_account(now_ms).await?;
+    Ok(nonce_account.to_string())
+}
+
+#[ic_cdk::update]
+fn validate_admin_add_svm_nonce_account() -> Result<String, String> {
+    check_admin_add_svm_nonce_account()?;
+    Ok("create a durable nonce account for the canister's SOL address".to_string())
+}
+
+fn check_admin_add_svm_nonce_account() -> Result<(), String> {
+    store::state::with(|s| {
+        if s.svm_providers.is_empty() {
+            return Err("SOL providers are not set".to_string());
+        }
+        Ok(())
+    })
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_remove_svm_nonce_account(nonce_account: String) -> Result<(), String> {
+    check_admin_remove_svm_nonce_account(&nonce_account)?;
+    store::state::with_mut(|s| {
+        s.svm_nonce_accounts
+            .retain(|a| a.to_string() != nonce_account);
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_remove_svm_nonce_account(nonce_account: String) -> Result<String, String> {
+    check_admin_remove_svm_nonce_account(&nonce_account)?;
+    pretty_format(&nonce_account)
+}
+
+fn check_admin_remove_svm_nonce_account(nonce_account: &str) -> Result<(), String> {
+    store::state::with(|s| {
+        if !s
+            .svm_nonce_accounts
+            .iter()
+            .any(|a| a.to_string() == nonce_account)
+        {
+            return Err(format!("nonce account {nonce_account} not found"));
+        }
+        Ok(())
+    })
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+fn admin_set_refund_after_retries(retries: u32) -> Result<(), String> {
+    store::state::with_mut(|s| {
+        s.refund_after_retries = retries;
+        Ok(())
+    })
+}
+
+#[ic_cdk::update]
+fn validate_admin_set_refund_after_retries(retries: u32) -> Result<String, String> {
+    pretty_format(&(retries,))
+}
+
+#[ic_cdk::update(guard = "is_controller")]
+async fn admin_collect_fees(
+    token: String,
+    to: Principal,
+    icp_amount: u128,
+) -> Result<store::BridgeTx, String> {
+    store::state::with(|s| {
+        let t = s.token(&token)?;
         if icp_amount == 0 {
             return Err("amount must be greater than 0".to_string());
         }
-        if icp_amount + s.total_withdrawn_fees > s.total_collected_fees {
+        if icp_amount > t.available_fees() {
             return Err(format!(
                 "amount {} exceeds available fees {}",
                 icp_amount,
-                s.total_collected_fees - s.total_withdrawn_fees
+                t.available_fees()
             ));
         }
-
-        Ok(s.token_ledger)
+        Ok(())
     })?;
 
-    let tx = store::state::to_icp(ledger, to, icp_amount).await?;
+    let tx = store::state::to_icp(&token, to, icp_amount).await?;
     store::state::with_mut(|s| {
-        s.total_withdrawn_fees += icp_amount;
+        if let Some(t) = s.tokens.get_mut(&token) {
+            t.total_withdrawn_fees += icp_amount;
+        }
     });
     Ok(tx)
 }
 
 #[ic_cdk::update]
-async fn validate_admin_collect_fees(to: Principal, icp_amount: u128) -> Result<String, String> {
+async fn validate_admin_collect_fees(
+    token: String,
+    to: Principal,
+    icp_amount: u128,
+) -> Result<String, String> {
     store::state::with(|s| {
+        let t = s.token(&token)?;
         if icp_amount == 0 {
             return Err("icp_amount must be greater than 0".to_string());
         }
-        if icp_amount > s.total_collected_fees - s.total_withdrawn_fees {
+        if icp_amount > t.available_fees() {
             return Err(format!(
                 "icp_amount {} exceeds available fees {}",
                 icp_amount,
-                s.total_collected_fees - s.total_withdrawn_fees
+                t.available_fees()
             ));
         }
         Ok(())
     })?;
-    pretty_format(&(to, icp_amount))
+    pretty_format(&(token, to, icp_amount))
 }
 
 fn is_controller() -> Result<(), String> {
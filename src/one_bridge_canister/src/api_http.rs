@@ -22,6 +22,13 @@ static IC_CERTIFICATE_EXPRESSION_HEADER: &str = "ic-certificateexpression";
 
 #[ic_cdk::query(hidden = true)]
 async fn http_request(request: HttpRequest<'static>) -> HttpResponse {
+    if request.method().as_str() == "GET"
+        && parse_url(request.url()).is_ok_and(|url| url.path() == "/reserves")
+        && let Some(res) = reserves_response(request.url())
+    {
+        return res;
+    }
+
     let witness = store::state::http_tree_with(|t| {
         t.witness(&store::state::DEFAULT_CERT_ENTRY, request.url())
             .expect("get witness failed")
@@ -106,6 +113,37 @@ async fn http_request(request: HttpRequest<'static>) -> HttpResponse {
     }
 }
 
+// Serves the certified proof of reserves, always in JSON.
+fn reserves_response(url: &str) -> Option<HttpResponse> {
+    let (entry, body) = store::state::reserves_response()?;
+    let witness = store::state::http_tree_with(|t| t.witness(&entry, url).ok())?;
+    let certified_data = ic_cdk::api::data_certificate()?;
+
+    let mut headers = vec![
+        ("x-content-type-options".to_string(), "nosniff".to_string()),
+        (
+            IC_CERTIFICATE_HEADER.to_string(),
+            format!(
+                "certificate=:{}:, tree=:{}:, expr_path=:{}:, version=2",
+                BASE64.encode(certified_data),
+                BASE64.encode(cbor_into_vec(&witness).expect("failed to serialize witness")),
+                BASE64.encode(
+                    cbor_into_vec(&store::state::RESERVES_EXPR_PATH.to_expr_path())
+                        .expect("failed to serialize expr path")
+                )
+            ),
+        ),
+    ];
+    headers.extend(store::state::reserves_headers());
+    headers.push(("content-length".to_string(), body.len().to_string()));
+    Some(HttpResponse {
+        status_code: 200,
+        headers,
+        body: body.into(),
+        upgrade: None,
+    })
+}
+
 fn parse_url(s: &str) -> Result<Url, String> {
     let url = if s.starts_with('/') {
         Url::parse(format!("http://localhost{}", s).as_str())
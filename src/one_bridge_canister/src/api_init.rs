@@ -13,39 +13,65 @@ pub enum CanisterArgs {
 #[derive(Clone, Debug, CandidType, Deserialize)]
 pub struct InitArgs {
     pub key_name: String,
-    pub token_name: String,
-    pub token_symbol: String,
-    pub token_decimals: u8,
-    pub token_logo: String,
-    pub token_ledger: Principal,
-    pub token_bridge_fee: u128,
-    pub min_threshold_to_bridge: u128,
+    pub tokens: Vec<TokenArgs>,
     pub governance_canister: Option<Principal>,
 }
 
+#[derive(Clone, Debug, CandidType, Deserialize)]
+pub struct TokenArgs {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub logo: String,
+    pub ledger: Principal,
+    pub bridge_fee: u128,
+    pub min_threshold_to_bridge: u128,
+}
+
 #[derive(Clone, Debug, CandidType, Deserialize)]
 pub struct UpgradeArgs {
-    pub token_name: Option<String>,
-    pub token_symbol: Option<String>,
-    pub token_logo: Option<String>,
-    pub token_ledger: Option<Principal>,
-    pub token_bridge_fee: Option<u128>,
-    pub min_threshold_to_bridge: Option<u128>,
     pub governance_canister: Option<Principal>,
 }
 
+impl From<TokenArgs> for store::Token {
+    fn from(args: TokenArgs) -> Self {
+        store::Token::new(
+            args.name,
+            args.symbol,
+            args.decimals,
+            args.logo,
+            args.ledger,
+            args.bridge_fee,
+            args.min_threshold_to_bridge,
+        )
+    }
+}
+
+impl TokenArgs {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.symbol.trim() != self.symbol || self.symbol.is_empty() || self.symbol.len() > 16 {
+            return Err("token symbol must be non-empty, up to 16 chars, and trimmed".to_string());
+        }
+        if self.name.trim().is_empty() {
+            return Err("token name cannot be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
 #[ic_cdk::init]
 fn init(args: Option<CanisterArgs>) {
     if let Some(CanisterArgs::Init(args)) = args {
+        for token in &args.tokens {
+            if let Err(err) = token.validate() {
+                ic_cdk::trap(&err);
+            }
+        }
         store::state::with_mut(|s| {
             s.key_name = args.key_name;
-            s.token_name = args.token_name;
-            s.token_symbol = args.token_symbol;
-            s.token_decimals = args.token_decimals;
-            s.token_logo = args.token_logo;
-            s.token_ledger = args.token_ledger;
-            s.token_bridge_fee = args.token_bridge_fee;
-            s.min_threshold_to_bridge = args.min_threshold_to_bridge;
+            for token in args.tokens {
+                s.tokens.insert(token.symbol.clone(), token.into());
+            }
             s.governance_canister = args.governance_canister;
         });
     } else if let Some(CanisterArgs::Upgrade(_)) = args {
@@ -54,6 +80,9 @@ fn init(args: Option<CanisterArgs>) {
 
     store::state::init_http_certified_data();
     ic_cdk_timers::set_timer(Duration::from_secs(0), store::state::init_public_key());
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(60), store::state::scan_evm_deposits);
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(3600), store::state::reconcile_reserves);
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(600), store::state::check_gas_balances);
 }
 
 #[ic_cdk::pre_upgrade]
@@ -67,24 +96,6 @@ fn post_upgrade(args: Option<CanisterArgs>) {
 
     match args {
         Some(CanisterArgs::Upgrade(args)) => store::state::with_mut(|s| {
-            if let Some(token_name) = args.token_name {
-                s.token_name = token_name;
-            }
-            if let Some(token_symbol) = args.token_symbol {
-                s.token_symbol = token_symbol;
-            }
-            if let Some(token_logo) = args.token_logo {
-                s.token_logo = token_logo;
-            }
-            if let Some(token_ledger) = args.token_ledger {
-                s.token_ledger = token_ledger;
-            }
-            if let Some(token_bridge_fee) = args.token_bridge_fee {
-                s.token_bridge_fee = token_bridge_fee;
-            }
-            if let Some(min_threshold_to_bridge) = args.min_threshold_to_bridge {
-                s.min_threshold_to_bridge = min_threshold_to_bridge;
-            }
             if let Some(governance_canister) = args.governance_canister {
                 s.governance_canister = Some(governance_canister);
             }
@@ -110,4 +121,11 @@ fn post_upgrade(args: Option<CanisterArgs>) {
         Duration::from_secs(3),
         store::state::finalize_bridging(round),
     );
+    ic_cdk_timers::set_timer(
+        Duration::from_secs(30),
+        store::state::check_svm_nonce_accounts(),
+    );
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(60), store::state::scan_evm_deposits);
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(3600), store::state::reconcile_reserves);
+    ic_cdk_timers::set_timer_interval(Duration::from_secs(600), store::state::check_gas_balances);
 }
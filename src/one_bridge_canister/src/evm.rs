@@ -1,21 +1,38 @@
-use alloy_primitives::{U256, hex::FromHex};
-use alloy_rpc_types_eth::TransactionReceipt;
+use alloy_primitives::{B256, U256, hex, hex::FromHex};
+use alloy_rpc_types_eth::{Log, TransactionReceipt};
 use ic_cdk::management_canister::{HttpHeader, HttpMethod, HttpRequestArgs};
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 
 use crate::{
     helper::APP_AGENT,
-    outcall::HttpOutcall,
+    outcall::{HttpOutcall, MultiCallResult, quorum_agree, quorum_reached},
     types::{RPCRequest, RPCResponse},
 };
 
 pub use alloy_primitives::{Address, TxHash};
 
+// keccak256("Transfer(address,address,uint256)")
+pub const TRANSFER_EVENT_TOPIC: [u8; 32] = [
+    0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d, 0xaa,
+    0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23, 0xb3, 0xef,
+];
+
+/// A decoded ERC20 `Transfer(address indexed from, address indexed to, uint256 value)` event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Erc20TransferLog {
+    pub contract: Address,
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+}
+
 pub struct EvmClient<T: HttpOutcall> {
     pub providers: Vec<String>,
     pub max_confirmations: u64,
     pub api_token: Option<String>,
+    // the number of providers that must agree on safety-critical reads, 1 means no quorum
+    pub quorum: usize,
     outcall: T,
 }
 
@@ -31,12 +48,24 @@ impl<H: HttpOutcall> EvmClient<H> {
             providers,
             max_confirmations,
             api_token,
+            quorum: 1,
             outcall,
         }
     }
+
+    pub fn with_quorum(mut self, quorum: usize) -> Self {
+        self.quorum = quorum;
+        self
+    }
+
     pub async fn chain_id(&self, now_ms: u64) -> Result<u64, String> {
         let res: String = self
-            .call(format!("eth_chainId-{}", now_ms), "eth_chainId", &[])
+            .call_quorum(
+                format!("eth_chainId-{}", now_ms),
+                "eth_chainId",
+                &[],
+                |v: &String| hex_to_u64(v).ok(),
+            )
             .await?;
         hex_to_u64(&res)
     }
@@ -60,6 +89,34 @@ impl<H: HttpOutcall> EvmClient<H> {
     }
 
     pub async fn block_number(&self, now_ms: u64) -> Result<u64, String> {
+        if self.quorum > 1 {
+            let idempotency_key = format!("eth_blockNumber-{}", now_ms);
+            if let Some(res) = self
+                .multi_call::<String>(&idempotency_key, "eth_blockNumber", &[], self.quorum)
+                .await
+            {
+                // the providers are rarely at the same height
+                return res
+                    .and_then(|v| hex_to_u64(&v))
+                    .resolve(|results| quorum_reached(results, self.quorum))
+                    .map_err(|err| format!("eth_blockNumber: {err}"));
+            }
+
+            let results = self
+                .call_all::<String>(
+                    format!("eth_blockNumber-{}", now_ms),
+                    "eth_blockNumber",
+                    &[],
+                )
+                .await?;
+            let results = results
+                .into_iter()
+                .map(|r| r.and_then(|v| hex_to_u64(&v)))
+                .collect();
+            return quorum_reached(results, self.quorum)
+                .map_err(|err| format!("eth_blockNumber: {err}"));
+        }
+
         let res: String = self
             .call(
                 format!("eth_blockNumber-{}", now_ms),
@@ -70,41 +127,86 @@ impl<H: HttpOutcall> EvmClient<H> {
         hex_to_u64(&res)
     }
 
+    // The nonce of the address at the block tag, "pending" counts its transactions in the
+    // mempool too.
     pub async fn get_transaction_count(
         &self,
         now_ms: u64,
         address: &Address,
+        block_tag: &str,
     ) -> Result<u64, String> {
         let res: String = self
             .call(
-                format!("eth_getTransactionCount-{}", now_ms),
+                format!("eth_getTransactionCount-{}-{}", block_tag, now_ms),
                 "eth_getTransactionCount",
-                &[address.to_string().into(), "latest".into()],
+                &[address.to_string().into(), block_tag.into()],
             )
             .await?;
         hex_to_u64(&res)
     }
 
-    // pub async fn get_balance(&self, now_ms: u64, address: &Address) -> Result<u128, String> {
-    //     let res: String = self
-    //         .call(
-    //             format!("eth_getBalance-{}", now_ms),
-    //             "eth_getBalance",
-    //             &[address.to_string().into(), "finalized".into()],
-    //         )
-    //         .await?;
-    //     hex_to_u128(&res)
-    // }
+    // The nonce of the address at the finalized block, agreed by the quorum of providers.
+    pub async fn get_finalized_transaction_count(
+        &self,
+        now_ms: u64,
+        address: &Address,
+    ) -> Result<u64, String> {
+        let res: String = self
+            .call_quorum(
+                format!("eth_getTransactionCount-finalized-{}", now_ms),
+                "eth_getTransactionCount",
+                &[address.to_string().into(), "finalized".into()],
+                |v: &String| hex_to_u64(v).ok(),
+            )
+            .await?;
+        hex_to_u64(&res)
+    }
+
+    pub async fn get_balance(&self, now_ms: u64, address: &Address) -> Result<u128, String> {
+        let res: String = self
+            .call(
+                format!("eth_getBalance-{}", now_ms),
+                "eth_getBalance",
+                &[address.to_string().into(), "latest".into()],
+            )
+            .await?;
+        hex_to_u128(&res)
+    }
 
     pub async fn get_transaction_receipt(
         &self,
         now_ms: u64,
         tx_hash: &TxHash,
     ) -> Result<Option<TransactionReceipt>, String> {
-        self.call(
+        self.call_quorum(
             format!("eth_getTransactionReceipt-{}", now_ms),
             "eth_getTransactionReceipt",
             &[tx_hash.to_string().into()],
+            |v: &Option<TransactionReceipt>| v.clone(),
+        )
+        .await
+    }
+
+    pub async fn get_logs(
+        &self,
+        now_ms: u64,
+        from_block: u64,
+        to_block: u64,
+        addresses: &[Address],
+        topics: &[Option<B256>],
+    ) -> Result<Vec<Log>, String> {
+        let filter = serde_json::json!({
+            "fromBlock": format!("0x{:x}", from_block),
+            "toBlock": format!("0x{:x}", to_block),
+            "address": addresses.iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+            "topics": topics.iter().map(|t| t.map(|t| t.to_string())).collect::<Vec<_>>(),
+        });
+
+        self.call_quorum(
+            format!("eth_getLogs-{}-{}-{}", from_block, to_block, now_ms),
+            "eth_getLogs",
+            &[filter],
+            |v: &Vec<Log>| v.clone(),
         )
         .await
     }
@@ -144,6 +246,33 @@ impl<H: HttpOutcall> EvmClient<H> {
         <Vec<u8>>::from_hex(res).map_err(|err| err.to_string())
     }
 
+    pub async fn estimate_gas(
+        &self,
+        now_ms: u64,
+        from: &Address,
+        to: &Address,
+        data: String,
+        value: u128,
+    ) -> Result<u64, String> {
+        let mut call_object = serde_json::json!({
+            "from": from.to_string(),
+            "to": to.to_string(),
+            "data": data,
+        });
+        if value > 0 {
+            call_object["value"] = format!("{value:#x}").into();
+        }
+
+        let res: String = self
+            .call(
+                format!("eth_estimateGas-{}", now_ms),
+                "eth_estimateGas",
+                &[call_object],
+            )
+            .await?;
+        hex_to_u64(&res)
+    }
+
     // pub async fn erc20_name(&self, now_ms: u64, contract: &Address) -> Result<String, String> {
     //     let res = self
     //         .call_contract(now_ms, contract, "0x06fdde03".to_string())
@@ -167,6 +296,30 @@ impl<H: HttpOutcall> EvmClient<H> {
         u8::try_from(v).map_err(|_| "decimals overflow u8".to_string())
     }
 
+    pub async fn erc20_total_supply(
+        &self,
+        now_ms: u64,
+        contract: &Address,
+    ) -> Result<u128, String> {
+        let res = self
+            .call_contract(now_ms, contract, "0x18160ddd".to_string())
+            .await?;
+        let v = decode_abi_uint(&res)?;
+        u128::try_from(v).map_err(|_| "total supply overflow u128".to_string())
+    }
+
+    pub async fn erc20_balance_of(
+        &self,
+        now_ms: u64,
+        contract: &Address,
+        owner: &Address,
+    ) -> Result<u128, String> {
+        let call_data = format!("0x70a08231{:0>64}", hex::encode(owner));
+        let res = self.call_contract(now_ms, contract, call_data).await?;
+        let v = decode_abi_uint(&res)?;
+        u128::try_from(v).map_err(|_| "balance overflow u128".to_string())
+    }
+
     pub async fn call<T: DeserializeOwned>(
         &self,
         idempotency_key: String,
@@ -177,6 +330,10 @@ impl<H: HttpOutcall> EvmClient<H> {
             return Err("no available provider".to_string());
         }
 
+        if let Some(res) = self.multi_call(&idempotency_key, method, params, 1).await {
+            return res.resolve(|results| quorum_agree(results, 1, |_| ()));
+        }
+
         let input = RPCRequest {
             jsonrpc: "2.0",
             method,
@@ -185,18 +342,97 @@ impl<H: HttpOutcall> EvmClient<H> {
         };
         let input = serde_json::to_vec(&input).map_err(|err| err.to_string())?;
         let data = self.http_request(idempotency_key, input).await?;
+        RPCResponse::<T>::decode(&data)
+    }
 
-        let output: RPCResponse<T> =
-            serde_json::from_slice(&data).map_err(|err| err.to_string())?;
+    /// Calls all providers concurrently if the quorum is more than 1, and returns
+    /// the result that at least `quorum` providers agree on by `key`.
+    pub async fn call_quorum<T: DeserializeOwned, K: PartialEq>(
+        &self,
+        idempotency_key: String,
+        method: &str,
+        params: &[Value],
+        key: impl Fn(&T) -> K,
+    ) -> Result<T, String> {
+        if self.quorum <= 1 {
+            return self.call(idempotency_key, method, params).await;
+        }
 
-        if let Some(error) = output.error {
-            return Err(serde_json::to_string(&error).map_err(|err| err.to_string())?);
+        if let Some(res) = self
+            .multi_call(&idempotency_key, method, params, self.quorum)
+            .await
+        {
+            return res
+                .resolve(|results| quorum_agree(results, self.quorum, key))
+                .map_err(|err| format!("{method}: {err}"));
         }
 
-        match output.result {
-            Some(result) => Ok(result),
-            None => serde_json::from_value(Value::Null).map_err(|_| "missing result".to_string()),
+        let results = self.call_all(idempotency_key, method, params).await?;
+        quorum_agree(results, self.quorum, key).map_err(|err| format!("{method}: {err}"))
+    }
+
+    async fn call_all<T: DeserializeOwned>(
+        &self,
+        idempotency_key: String,
+        method: &str,
+        params: &[Value],
+    ) -> Result<Vec<Result<T, String>>, String> {
+        if self.providers.is_empty() {
+            return Err("no available provider".to_string());
         }
+
+        let input = RPCRequest {
+            jsonrpc: "2.0",
+            method,
+            params,
+            id: 1,
+        };
+        let input = serde_json::to_vec(&input).map_err(|err| err.to_string())?;
+        let args = self.request_args(&idempotency_key, input);
+        let results = futures::future::join_all(self.providers.iter().map(|p| {
+            let mut args = args.clone();
+            args.url = p.clone();
+            let idempotency_key = &idempotency_key;
+            async move {
+                let data = self.request_provider(&args, idempotency_key).await?;
+                RPCResponse::<T>::decode(&data)
+            }
+        }))
+        .await;
+        Ok(results)
+    }
+
+    /// Calls all providers at once through the outcall backend's multi-provider method,
+    /// returns None if the backend has none for the method.
+    async fn multi_call<T: DeserializeOwned>(
+        &self,
+        idempotency_key: &str,
+        method: &str,
+        params: &[Value],
+        quorum: usize,
+    ) -> Option<MultiCallResult<T>> {
+        let input = RPCRequest {
+            jsonrpc: "2.0",
+            method,
+            params,
+            id: 1,
+        };
+        let input = serde_json::to_vec(&input).ok()?;
+        let args = self.request_args(idempotency_key, input);
+        let args: Vec<HttpRequestArgs> = self
+            .providers
+            .iter()
+            .map(|p| {
+                let mut args = args.clone();
+                args.url = p.clone();
+                args
+            })
+            .collect();
+        let res = self
+            .outcall
+            .multi_request(&args, method, params, quorum)
+            .await?;
+        Some(res.and_then(|v| serde_json::from_value(v).map_err(|err| err.to_string())))
     }
 
     async fn http_request(
@@ -204,6 +440,20 @@ impl<H: HttpOutcall> EvmClient<H> {
         idempotency_key: String,
         body: Vec<u8>,
     ) -> Result<Vec<u8>, String> {
+        let mut args = self.request_args(&idempotency_key, body);
+        let mut last_err = "No provider succeeded".to_string();
+        for p in &self.providers {
+            args.url = p.clone();
+            match self.request_provider(&args, &idempotency_key).await {
+                Ok(body) => return Ok(body),
+                Err(err) => last_err = err,
+            }
+        }
+
+        Err(last_err)
+    }
+
+    fn request_args(&self, idempotency_key: &str, body: Vec<u8>) -> HttpRequestArgs {
         let mut request_headers = vec![
             HttpHeader {
                 name: "content-type".to_string(),
@@ -215,7 +465,7 @@ impl<H: HttpOutcall> EvmClient<H> {
             },
             HttpHeader {
                 name: "idempotency-key".to_string(),
-                value: idempotency_key.clone(),
+                value: idempotency_key.to_string(),
             },
         ];
 
@@ -226,7 +476,7 @@ impl<H: HttpOutcall> EvmClient<H> {
             });
         }
 
-        let mut args = HttpRequestArgs {
+        HttpRequestArgs {
             url: "".to_string(),
             max_response_bytes: None, //optional for request
             method: HttpMethod::POST,
@@ -234,32 +484,31 @@ impl<H: HttpOutcall> EvmClient<H> {
             body: Some(body),
             transform: self.outcall.transform_context(),
             is_replicated: Some(false),
-        };
+        }
+    }
 
-        let mut last_err = "No provider succeeded".to_string();
-        for p in &self.providers {
-            args.url = p.clone();
-            match self.outcall.request(&args).await {
-                Ok(res) => {
-                    if res.status >= 200u64 && res.status < 300u64 {
-                        return Ok(res.body);
-                    } else {
-                        last_err = format!(
-                            "request provider: {}, idempotency-key: {}, status: {}, body: {}",
-                            p,
-                            idempotency_key,
-                            res.status,
-                            String::from_utf8(res.body.clone()).unwrap_or_default(),
-                        );
-                    }
-                }
-                Err(err) => {
-                    last_err = format!("failed to request provider: {p}, error: {err}");
+    async fn request_provider(
+        &self,
+        args: &HttpRequestArgs,
+        idempotency_key: &str,
+    ) -> Result<Vec<u8>, String> {
+        let p = &args.url;
+        match self.outcall.request(args).await {
+            Ok(res) => {
+                if res.status >= 200u64 && res.status < 300u64 {
+                    Ok(res.body)
+                } else {
+                    Err(format!(
+                        "request provider: {}, idempotency-key: {}, status: {}, body: {}",
+                        p,
+                        idempotency_key,
+                        res.status,
+                        String::from_utf8(res.body.clone()).unwrap_or_default(),
+                    ))
                 }
             }
+            Err(err) => Err(format!("failed to request provider: {p}, error: {err}")),
         }
-
-        Err(last_err)
     }
 }
 
@@ -279,6 +528,54 @@ pub fn encode_erc20_transfer(to: &Address, value: u128) -> Vec<u8> {
     call_data
 }
 
+pub fn encode_erc20_mint(to: &Address, value: u128) -> Vec<u8> {
+    const MINT_SELECTOR: [u8; 4] = [0x40, 0xc1, 0x0f, 0x19]; // keccak256("mint(address,uint256)")[:4]
+
+    let mut call_data = Vec::with_capacity(4 + 32 + 32);
+    call_data.extend_from_slice(&MINT_SELECTOR);
+
+    let mut padded_to = [0u8; 32];
+    padded_to[12..].copy_from_slice(to.as_slice());
+    call_data.extend_from_slice(&padded_to);
+
+    let value_bytes = U256::from(value).to_be_bytes::<32>();
+    call_data.extend_from_slice(&value_bytes);
+
+    call_data
+}
+
+pub fn encode_erc20_burn(value: u128) -> Vec<u8> {
+    const BURN_SELECTOR: [u8; 4] = [0x42, 0x96, 0x6c, 0x68]; // keccak256("burn(uint256)")[:4]
+
+    let mut call_data = Vec::with_capacity(4 + 32);
+    call_data.extend_from_slice(&BURN_SELECTOR);
+
+    let value_bytes = U256::from(value).to_be_bytes::<32>();
+    call_data.extend_from_slice(&value_bytes);
+
+    call_data
+}
+
+pub fn address_to_topic(addr: &Address) -> B256 {
+    let mut topic = [0u8; 32];
+    topic[12..].copy_from_slice(addr.as_slice());
+    topic.into()
+}
+
+pub fn decode_erc20_transfer_log(log: &Log) -> Option<Erc20TransferLog> {
+    let topics = log.topics();
+    if topics.len() != 3 || topics[0].0 != TRANSFER_EVENT_TOPIC || log.data().data.len() != 32 {
+        return None;
+    }
+
+    Some(Erc20TransferLog {
+        contract: log.address(),
+        from: Address::from_slice(&topics[1][12..]),
+        to: Address::from_slice(&topics[2][12..]),
+        value: U256::from_be_slice(&log.data().data),
+    })
+}
+
 fn hex_to_u64(s: &str) -> Result<u64, String> {
     let s = s.strip_prefix("0x").unwrap_or(s);
     u64::from_str_radix(s, 16).map_err(|err| err.to_string())
@@ -335,6 +632,8 @@ mod tests {
 
     use ic_cdk::management_canister::{HttpRequestResult, TransformContext};
 
+    use crate::outcall::QUORUM_DISAGREEMENT;
+
     #[test]
     fn test_encode_erc20_transfer() {
         let addr = Address::from_hex("0x00112233445566778899aabbccddeeff00112233").unwrap();
@@ -348,6 +647,62 @@ mod tests {
         assert_eq!(encoded, expected);
     }
 
+    #[test]
+    fn test_encode_erc20_mint_and_burn() {
+        let addr = Address::from_hex("0x00112233445566778899aabbccddeeff00112233").unwrap();
+        let encoded = encode_erc20_mint(&addr, 12345);
+
+        let mut expected = alloy_primitives::keccak256("mint(address,uint256)")[..4].to_vec();
+        expected.extend(vec![0u8; 12]);
+        expected.extend_from_slice(addr.as_ref());
+        expected.extend(U256::from(12345u128).to_be_bytes::<32>());
+        assert_eq!(encoded, expected);
+
+        let encoded = encode_erc20_burn(12345);
+        let mut expected = alloy_primitives::keccak256("burn(uint256)")[..4].to_vec();
+        expected.extend(U256::from(12345u128).to_be_bytes::<32>());
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn test_decode_erc20_transfer_log() {
+        assert_eq!(
+            alloy_primitives::keccak256("Transfer(address,address,uint256)").0,
+            TRANSFER_EVENT_TOPIC
+        );
+
+        let log: Log = serde_json::from_value(serde_json::json!({
+            "address": "0xe74583edaff618d88463554b84bc675196b36990",
+            "topics": [
+                "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+                "0x00000000000000000000000000112233445566778899aabbccddeeff00112233",
+                "0x000000000000000000000000ffeeddccbbaa99887766554433221100ffeeddcc"
+            ],
+            "data": "0x0000000000000000000000000000000000000000000000000000000000003039",
+            "blockNumber": "0x10",
+            "blockHash": "0x5a1b0f3a9e6b2bd02ba3ee9a9e2cf6a4e69b6bc8a1e4a6e4c1cc2a2c9f3d0c0f",
+            "transactionHash": "0x6b2bd02ba3ee9a9e2cf6a4e69b6bc8a1e4a6e4c1cc2a2c9f3d0c0f3d1b0f3a9e",
+            "transactionIndex": "0x0",
+            "logIndex": "0x1",
+            "removed": false
+        }))
+        .unwrap();
+
+        let transfer = decode_erc20_transfer_log(&log).unwrap();
+        assert_eq!(
+            transfer.contract,
+            Address::from_hex("0xe74583edaff618d88463554b84bc675196b36990").unwrap()
+        );
+        let from = Address::from_hex("0x00112233445566778899aabbccddeeff00112233").unwrap();
+        assert_eq!(transfer.from, from);
+        assert_eq!(
+            transfer.to,
+            Address::from_hex("0xffeeddccbbaa99887766554433221100ffeeddcc").unwrap()
+        );
+        assert_eq!(transfer.value, U256::from(12345u64));
+        assert_eq!(address_to_topic(&from), log.topics()[1]);
+    }
+
     #[test]
     fn test_hex_to_u64_and_u128() {
         assert_eq!(hex_to_u64("0x2a").unwrap(), 42);
@@ -436,6 +791,61 @@ mod tests {
         assert_eq!(mock.urls(), vec!["https://rpc.one".to_string()]);
     }
 
+    #[test]
+    fn test_estimate_gas() {
+        let mock = MockHttpOutcall::new(vec![success_response(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": "0xd1c0"
+        }))]);
+
+        let client = EvmClient::new(vec!["https://rpc.one".to_string()], 5, None, mock);
+        let from = Address::from_hex("0x00112233445566778899aabbccddeeff00112233").unwrap();
+        let to = Address::from_hex("0xffeeddccbbaa99887766554433221100ffeeddcc").unwrap();
+        let gas = futures::executor::block_on(client.estimate_gas(
+            1_000,
+            &from,
+            &to,
+            "0xa9059cbb".to_string(),
+            0,
+        ))
+        .unwrap();
+
+        assert_eq!(gas, 53_696);
+    }
+
+    #[test]
+    fn test_get_balance() {
+        let mock = MockHttpOutcall::new(vec![success_response(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": "0xde0b6b3a7640000"
+        }))]);
+
+        let client = EvmClient::new(vec!["https://rpc.one".to_string()], 5, None, mock);
+        let address = Address::from_hex("0x00112233445566778899aabbccddeeff00112233").unwrap();
+        let balance = futures::executor::block_on(client.get_balance(1_000, &address)).unwrap();
+
+        assert_eq!(balance, 1_000_000_000_000_000_000);
+    }
+
+    #[test]
+    fn test_erc20_balance_of() {
+        let mock = MockHttpOutcall::new(vec![success_response(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": format!("0x{}", hex::encode(U256::from(12345u128).to_be_bytes::<32>()))
+        }))]);
+
+        let client = EvmClient::new(vec!["https://rpc.one".to_string()], 5, None, mock);
+        let contract = Address::from_hex("0x00112233445566778899aabbccddeeff00112233").unwrap();
+        let owner = Address::from_hex("0xffeeddccbbaa99887766554433221100ffeeddcc").unwrap();
+        let balance =
+            futures::executor::block_on(client.erc20_balance_of(1_000, &contract, &owner)).unwrap();
+
+        assert_eq!(balance, 12345);
+    }
+
     #[test]
     fn test_http_request_fallbacks_between_providers() {
         let mock = MockHttpOutcall::new(vec![
@@ -463,6 +873,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_quorum_reads() {
+        let rpc = |result: &str| {
+            success_response(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": result
+            }))
+        };
+        let providers = vec![
+            "https://first".to_string(),
+            "https://second".to_string(),
+            "https://third".to_string(),
+        ];
+
+        let mock = MockHttpOutcall::new(vec![rpc("0x38"), Err("timeout".to_string()), rpc("0x38")]);
+        let client = EvmClient::new(providers.clone(), 5, None, mock.clone()).with_quorum(2);
+        let value = futures::executor::block_on(client.chain_id(1_000)).unwrap();
+        assert_eq!(value, 56);
+        assert_eq!(mock.urls(), providers);
+
+        let mock = MockHttpOutcall::new(vec![rpc("0x38"), rpc("0x1"), rpc("0x89")]);
+        let client = EvmClient::new(providers.clone(), 5, None, mock).with_quorum(2);
+        let err = futures::executor::block_on(client.chain_id(1_000)).unwrap_err();
+        assert!(err.contains(QUORUM_DISAGREEMENT));
+
+        let mock = MockHttpOutcall::new(vec![rpc("0x64"), rpc("0x66"), rpc("0x65")]);
+        let client = EvmClient::new(providers.clone(), 5, None, mock).with_quorum(2);
+        let block = futures::executor::block_on(client.block_number(1_000)).unwrap();
+        assert_eq!(block, 0x65);
+
+        // providers agree on the nonce, not on its encoding
+        let mock = MockHttpOutcall::new(vec![rpc("0x7"), rpc("0x8"), rpc("0x07")]);
+        let client = EvmClient::new(providers.clone(), 5, None, mock).with_quorum(2);
+        let addr = Address::from_hex("0x00112233445566778899aabbccddeeff00112233").unwrap();
+        let nonce =
+            futures::executor::block_on(client.get_finalized_transaction_count(1_000, &addr))
+                .unwrap();
+        assert_eq!(nonce, 7);
+
+        let mock = MockHttpOutcall::new(vec![rpc("0x64"), Err("timeout".to_string())]);
+        let client = EvmClient::new(providers, 5, None, mock).with_quorum(2);
+        let err = futures::executor::block_on(client.block_number(1_000)).unwrap_err();
+        assert!(err.contains("quorum 2 not reached"));
+    }
+
     #[test]
     fn test_call_handles_error_payload() {
         let error_body = serde_json::json!({
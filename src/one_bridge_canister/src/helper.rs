@@ -97,3 +97,30 @@ where
 
     Ok(format!("{}", doc.pretty(120)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_amount() {
+        // exact
+        assert_eq!(convert_amount(123_456_789, 8, 8), Ok(123_456_789));
+        // up-scale
+        assert_eq!(
+            convert_amount(123_456_789, 8, 18),
+            Ok(1_234_567_890_000_000_000)
+        );
+        assert_eq!(convert_amount(0, 6, 18), Ok(0));
+        assert!(convert_amount(u128::MAX, 8, 18).is_err());
+        assert!(convert_amount(1, 0, 39).is_err());
+        // down-scale truncates
+        assert_eq!(
+            convert_amount(1_234_567_899_999_999_999, 18, 8),
+            Ok(123_456_789)
+        );
+        assert_eq!(convert_amount(123_456_789, 8, 6), Ok(1_234_567));
+        assert_eq!(convert_amount(99, 8, 6), Ok(0));
+        assert!(convert_amount(1, 39, 0).is_err());
+    }
+}
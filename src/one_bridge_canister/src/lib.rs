@@ -15,6 +15,7 @@ mod store;
 mod svm;
 mod types;
 
-use api_init::CanisterArgs;
+use api_admin::UpdateTokenArgs;
+use api_init::{CanisterArgs, TokenArgs};
 
 ic_cdk::export_candid!();
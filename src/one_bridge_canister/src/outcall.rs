@@ -1,12 +1,81 @@
-use candid::Principal;
+use candid::{CandidType, Nat, Principal, utils::ArgumentEncoder};
 use ic_cdk::management_canister::{
-    HttpRequestArgs, HttpRequestResult, TransformArgs, TransformContext, TransformFunc,
+    HttpHeader, HttpRequestArgs, HttpRequestResult, TransformArgs, TransformContext, TransformFunc,
     http_request,
 };
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use std::{cell::RefCell, collections::HashMap};
+
+const EVM_RPC_MAX_RESPONSE_BYTES: u64 = 256 * 1024;
+// margin on the cycles quoted by `requestCost`, the unused cycles are refunded
+const EVM_RPC_CYCLES_MARGIN_PCT: u128 = 10;
+
+thread_local! {
+    // the `requestCost` quotes of the EVM RPC canisters by (canister, chain id, method)
+    static REQUEST_COSTS: RefCell<HashMap<(Principal, u64, String), RequestCost>> =
+        RefCell::new(HashMap::new());
+}
+
+// a provider's cycles quoted for a request up to the sizes
+#[derive(Clone, Copy)]
+struct RequestCost {
+    request_bytes: usize,
+    max_response_bytes: u64,
+    cycles: u128,
+}
+
+// the error prefix when the providers respond but do not agree on a result
+pub const QUORUM_DISAGREEMENT: &str = "providers disagreement";
 
 pub trait HttpOutcall {
     fn transform_context(&self) -> Option<TransformContext>;
     async fn request(&self, args: &HttpRequestArgs) -> Result<HttpRequestResult, String>;
+
+    /// Makes a JSON-RPC call to all providers (one request args each) at once with the
+    /// backend's own multi-provider consensus, at least `quorum` providers must agree.
+    /// Returns None if the backend has no such method for the call.
+    async fn multi_request(
+        &self,
+        _args: &[HttpRequestArgs],
+        _method: &str,
+        _params: &[Value],
+        _quorum: usize,
+    ) -> Option<MultiCallResult<Value>> {
+        None
+    }
+}
+
+/// The JSON-RPC results of a call made to all providers at once.
+#[derive(Debug)]
+pub enum MultiCallResult<T> {
+    // the providers agreed on the result, or the call failed as a whole
+    Consistent(Result<T, String>),
+    // the providers did not agree, the result of each provider
+    Inconsistent(Vec<Result<T, String>>),
+}
+
+impl<T> MultiCallResult<T> {
+    pub fn and_then<R>(self, f: impl Fn(T) -> Result<R, String>) -> MultiCallResult<R> {
+        match self {
+            MultiCallResult::Consistent(res) => MultiCallResult::Consistent(res.and_then(f)),
+            MultiCallResult::Inconsistent(results) => {
+                MultiCallResult::Inconsistent(results.into_iter().map(|r| r.and_then(&f)).collect())
+            }
+        }
+    }
+
+    /// Returns the consistent result, or the one that `resolve` picks from the results of
+    /// the providers.
+    pub fn resolve(
+        self,
+        resolve: impl FnOnce(Vec<Result<T, String>>) -> Result<T, String>,
+    ) -> Result<T, String> {
+        match self {
+            MultiCallResult::Consistent(res) => res,
+            MultiCallResult::Inconsistent(results) => resolve(results),
+        }
+    }
 }
 
 pub struct DefaultHttpOutcall(Principal);
@@ -30,6 +99,771 @@ impl HttpOutcall for DefaultHttpOutcall {
     }
 }
 
+/// Makes the inter-canister calls of `EvmRpcOutcall` with Candid encoded arguments and results.
+pub trait CanisterCall {
+    async fn call(
+        &self,
+        canister: Principal,
+        method: &str,
+        args: Vec<u8>,
+        cycles: u128,
+    ) -> Result<Vec<u8>, String>;
+}
+
+pub struct IcCanisterCall;
+
+impl CanisterCall for IcCanisterCall {
+    async fn call(
+        &self,
+        canister: Principal,
+        method: &str,
+        args: Vec<u8>,
+        cycles: u128,
+    ) -> Result<Vec<u8>, String> {
+        let res = ic_cdk::call::Call::bounded_wait(canister, method)
+            .with_raw_args(&args)
+            .with_cycles(cycles)
+            .await
+            .map_err(|err| format!("failed to call {method} on {canister:?}, error: {err:?}"))?;
+        Ok(res.into_bytes())
+    }
+}
+
+/// Routes the JSON-RPC requests through the EVM RPC canister
+/// (https://github.com/dfinity/evm-rpc-canister) or a stand-in canister with the same interface.
+/// The reads and sends it has typed `eth_*` methods for are made to all providers at once with
+/// its consensus, the others through its single-provider `request` method. At quorum 1 the
+/// typed methods are called with one provider at a time, failing over to the next one.
+/// The cycles attached to each call are quoted by its `requestCost` method once per method and
+/// chain, and quoted again for a larger request or after a `TooFewCycles` error.
+pub struct EvmRpcOutcall<C: CanisterCall = IcCanisterCall> {
+    canister: Principal,
+    chain_id: u64,
+    caller: C,
+}
+
+impl EvmRpcOutcall {
+    pub fn new(canister: Principal, chain_id: u64) -> Self {
+        Self {
+            canister,
+            chain_id,
+            caller: IcCanisterCall,
+        }
+    }
+}
+
+impl<C: CanisterCall> EvmRpcOutcall<C> {
+    async fn call<In, Out>(&self, method: &str, args: In, cycles: u128) -> Result<Out, String>
+    where
+        In: ArgumentEncoder,
+        Out: CandidType + DeserializeOwned,
+    {
+        let args = candid::encode_args(args).map_err(|err| format!("{method}: {err}"))?;
+        let res = self
+            .caller
+            .call(self.canister, method, args, cycles)
+            .await?;
+        candid::decode_one(&res).map_err(|err| {
+            format!(
+                "failed to decode response from {method} on {:?}, error: {err}",
+                self.canister
+            )
+        })
+    }
+
+    fn cost_key(&self, method: &str) -> (Principal, u64, String) {
+        (self.canister, self.chain_id, method.to_string())
+    }
+
+    // the cycles of the JSON-RPC request to a provider, with a margin.
+    // The largest request is quoted and the quote is reused for the method until a larger
+    // request comes, the unused cycles are refunded by the EVM RPC canister.
+    async fn request_cost(
+        &self,
+        method: &str,
+        requests: &[(RpcService, String, u64)],
+    ) -> Result<u128, String> {
+        let Some((largest, request_bytes)) = requests
+            .iter()
+            .map(|r| (r, request_bytes(r)))
+            .max_by_key(|(_, bytes)| *bytes)
+        else {
+            return Ok(0);
+        };
+        let max_response_bytes = requests.iter().map(|(_, _, m)| *m).max().unwrap_or(0);
+        let key = self.cost_key(method);
+        let cached = REQUEST_COSTS.with_borrow(|costs| {
+            costs.get(&key).and_then(|c| {
+                (c.request_bytes >= request_bytes && c.max_response_bytes >= max_response_bytes)
+                    .then_some(c.cycles)
+            })
+        });
+        let cost = match cached {
+            Some(cost) => cost,
+            None => {
+                let (service, json, _) = largest;
+                let res: Result<Nat, RpcError> = self
+                    .call("requestCost", (service, json, max_response_bytes), 0)
+                    .await?;
+                let cost = res.map_err(|err| format!("EVM RPC canister error: {err:?}"))?;
+                let cost: u128 = cost
+                    .0
+                    .clone()
+                    .try_into()
+                    .map_err(|_| format!("request cost {cost} overflows u128"))?;
+                REQUEST_COSTS.with_borrow_mut(|costs| {
+                    costs.insert(
+                        key,
+                        RequestCost {
+                            request_bytes,
+                            max_response_bytes,
+                            cycles: cost,
+                        },
+                    )
+                });
+                cost
+            }
+        };
+        Ok(cost.saturating_add(cost * EVM_RPC_CYCLES_MARGIN_PCT / 100))
+    }
+
+    // drops the quote of the method if the call was short of cycles, to quote it again
+    fn check_request_cost<T>(&self, method: &str, res: &Result<MultiCallResult<T>, String>) {
+        let too_few = |err: &String| err.contains("TooFewCycles");
+        let short = match res {
+            Err(err) | Ok(MultiCallResult::Consistent(Err(err))) => too_few(err),
+            Ok(MultiCallResult::Inconsistent(results)) => {
+                results.iter().any(|r| r.as_ref().is_err_and(too_few))
+            }
+            Ok(MultiCallResult::Consistent(Ok(_))) => false,
+        };
+        if short {
+            REQUEST_COSTS.with_borrow_mut(|costs| costs.remove(&self.cost_key(method)));
+        }
+    }
+
+    async fn multi_call<In, Out>(
+        &self,
+        method: &str,
+        args: In,
+        cycles: u128,
+        to_json: impl Fn(Out) -> Result<Value, String>,
+    ) -> Result<MultiCallResult<Value>, String>
+    where
+        In: ArgumentEncoder,
+        Out: CandidType + DeserializeOwned,
+    {
+        let res: MultiRpcResult<Out> = self.call(method, args, cycles).await?;
+        let rpc_result = |res: Result<Out, RpcError>| {
+            res.map_err(|err| format!("EVM RPC canister error: {err:?}"))
+                .and_then(&to_json)
+        };
+        Ok(match res {
+            MultiRpcResult::Consistent(res) => MultiCallResult::Consistent(rpc_result(res)),
+            MultiRpcResult::Inconsistent(results) => MultiCallResult::Inconsistent(
+                results
+                    .into_iter()
+                    .map(|(_, res)| rpc_result(res))
+                    .collect(),
+            ),
+        })
+    }
+
+    async fn typed_request(
+        &self,
+        args: &[HttpRequestArgs],
+        method: &str,
+        params: &[Value],
+        quorum: usize,
+    ) -> Result<MultiCallResult<Value>, String> {
+        let requests = args
+            .iter()
+            .map(evm_rpc_request_args)
+            .collect::<Result<Vec<_>, String>>()?;
+        let cycles = self.request_cost(method, &requests).await?;
+        if quorum > 1 {
+            let cycles = cycles.saturating_mul(requests.len() as u128);
+            let res = self
+                .typed_call(requests, method, params, quorum, cycles)
+                .await;
+            self.check_request_cost(method, &res);
+            return res;
+        }
+
+        // one provider is enough, fails over to the next one on error
+        let mut last_err = "No provider succeeded".to_string();
+        for request in requests {
+            let res = self
+                .typed_call(vec![request], method, params, 1, cycles)
+                .await;
+            self.check_request_cost(method, &res);
+            match res {
+                Ok(MultiCallResult::Consistent(Ok(v))) => {
+                    return Ok(MultiCallResult::Consistent(Ok(v)));
+                }
+                Ok(MultiCallResult::Consistent(Err(err))) | Err(err) => last_err = err,
+                Ok(MultiCallResult::Inconsistent(results)) => {
+                    if let Some(Err(err)) = results.into_iter().find(|r| r.is_err()) {
+                        last_err = err;
+                    }
+                }
+            }
+        }
+        Err(last_err)
+    }
+
+    async fn typed_call(
+        &self,
+        requests: Vec<(RpcService, String, u64)>,
+        method: &str,
+        params: &[Value],
+        quorum: usize,
+        cycles: u128,
+    ) -> Result<MultiCallResult<Value>, String> {
+        let response_size_estimate = requests
+            .first()
+            .map(|(_, _, m)| *m)
+            .unwrap_or(EVM_RPC_MAX_RESPONSE_BYTES);
+        let total = requests.len().min(u8::MAX as usize) as u8;
+        let services = RpcServices::Custom {
+            chain_id: self.chain_id,
+            services: requests
+                .into_iter()
+                .map(|(service, _, _)| match service {
+                    RpcService::Custom(api) => api,
+                })
+                .collect(),
+        };
+        let config = RpcConfig {
+            response_size_estimate: Some(response_size_estimate),
+            response_consensus: Some(ConsensusStrategy::Threshold {
+                total: Some(total),
+                min: (quorum.max(1) as u8).min(total),
+            }),
+        };
+
+        match method {
+            "eth_blockNumber" => {
+                self.multi_call(
+                    "eth_getBlockByNumber",
+                    (services, Some(config), BlockTag::Latest),
+                    cycles,
+                    |block: Block| Ok(nat_to_hex(&block.number).into()),
+                )
+                .await
+            }
+            "eth_getTransactionCount" => {
+                let args = GetTransactionCountArgs {
+                    address: param_str(params, 0)?,
+                    block: block_tag(&param_str(params, 1)?)?,
+                };
+                self.multi_call(
+                    "eth_getTransactionCount",
+                    (services, Some(config), args),
+                    cycles,
+                    |count: Nat| Ok(nat_to_hex(&count).into()),
+                )
+                .await
+            }
+            "eth_getTransactionReceipt" => {
+                self.multi_call(
+                    "eth_getTransactionReceipt",
+                    (services, Some(config), param_str(params, 0)?),
+                    cycles,
+                    |receipt: Option<TransactionReceipt>| {
+                        Ok(receipt.map(|r| r.to_json()).unwrap_or(Value::Null))
+                    },
+                )
+                .await
+            }
+            "eth_getLogs" => {
+                let filter = params.first().ok_or("missing eth_getLogs filter")?;
+                let block = |key: &str| -> Result<Option<BlockTag>, String> {
+                    filter[key].as_str().map(block_tag).transpose()
+                };
+                let args = GetLogsArgs {
+                    from_block: block("fromBlock")?,
+                    to_block: block("toBlock")?,
+                    addresses: serde_json::from_value(filter["address"].clone())
+                        .map_err(|err| format!("invalid eth_getLogs addresses: {err}"))?,
+                    // a null topic matches any, as an empty list of alternatives
+                    topics: Some(
+                        filter["topics"]
+                            .as_array()
+                            .map(|topics| {
+                                topics
+                                    .iter()
+                                    .map(|t| {
+                                        t.as_str().map(|t| vec![t.to_string()]).unwrap_or_default()
+                                    })
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                    ),
+                };
+                let config = GetLogsRpcConfig {
+                    response_size_estimate: config.response_size_estimate,
+                    response_consensus: config.response_consensus,
+                    max_block_range: None,
+                };
+                self.multi_call(
+                    "eth_getLogs",
+                    (services, Some(config), args),
+                    cycles,
+                    |logs: Vec<LogEntry>| Ok(logs.into_iter().map(|l| l.to_json()).collect()),
+                )
+                .await
+            }
+            "eth_sendRawTransaction" => {
+                self.multi_call(
+                    "eth_sendRawTransaction",
+                    (services, Some(config), param_str(params, 0)?),
+                    cycles,
+                    |status: SendRawTransactionStatus| match status {
+                        SendRawTransactionStatus::Ok(tx_hash) => Ok(tx_hash.into()),
+                        SendRawTransactionStatus::NonceTooLow => Err("nonce too low".to_string()),
+                        SendRawTransactionStatus::NonceTooHigh => Err("nonce too high".to_string()),
+                        SendRawTransactionStatus::InsufficientFunds => {
+                            Err("insufficient funds".to_string())
+                        }
+                    },
+                )
+                .await
+            }
+            "eth_call" => {
+                let call = params.first().ok_or("missing eth_call object")?;
+                let args = CallArgs {
+                    transaction: TransactionRequest {
+                        to: call["to"].as_str().map(|v| v.to_string()),
+                        input: call["data"].as_str().map(|v| v.to_string()),
+                    },
+                    block: Some(block_tag(&param_str(params, 1)?)?),
+                };
+                self.multi_call(
+                    "eth_call",
+                    (services, Some(config), args),
+                    cycles,
+                    |data: String| Ok(data.into()),
+                )
+                .await
+            }
+            _ => Err(format!("{method} is not a typed EVM RPC canister method")),
+        }
+    }
+}
+
+// the JSON-RPC methods that the EVM RPC canister has typed multi-provider methods for
+const EVM_RPC_TYPED_METHODS: [&str; 6] = [
+    "eth_blockNumber",
+    "eth_getTransactionCount",
+    "eth_getTransactionReceipt",
+    "eth_getLogs",
+    "eth_sendRawTransaction",
+    "eth_call",
+];
+
+impl<C: CanisterCall> HttpOutcall for EvmRpcOutcall<C> {
+    async fn request(&self, args: &HttpRequestArgs) -> Result<HttpRequestResult, String> {
+        let request = evm_rpc_request_args(args)?;
+        let cycles = self
+            .request_cost("request", std::slice::from_ref(&request))
+            .await?;
+        let res: Result<String, RpcError> = self.call("request", request, cycles).await?;
+        let body = res.map_err(|err| {
+            if matches!(
+                err,
+                RpcError::ProviderError(ProviderError::TooFewCycles { .. })
+            ) {
+                REQUEST_COSTS.with_borrow_mut(|costs| costs.remove(&self.cost_key("request")));
+            }
+            format!("EVM RPC canister error: {err:?}")
+        })?;
+        Ok(HttpRequestResult {
+            status: 200u64.into(),
+            headers: vec![],
+            body: body.into_bytes(),
+        })
+    }
+
+    fn transform_context(&self) -> Option<TransformContext> {
+        None
+    }
+
+    async fn multi_request(
+        &self,
+        args: &[HttpRequestArgs],
+        method: &str,
+        params: &[Value],
+        quorum: usize,
+    ) -> Option<MultiCallResult<Value>> {
+        if args.is_empty() || !EVM_RPC_TYPED_METHODS.contains(&method) {
+            return None;
+        }
+
+        Some(
+            self.typed_request(args, method, params, quorum)
+                .await
+                .map_err(|err| format!("{method}: {err}"))
+                .unwrap_or_else(|err| MultiCallResult::Consistent(Err(err))),
+        )
+    }
+}
+
+/// The outcall backend of a chain's RPC client.
+pub enum RpcOutcall {
+    Http(DefaultHttpOutcall),
+    EvmRpc(EvmRpcOutcall),
+}
+
+impl HttpOutcall for RpcOutcall {
+    async fn request(&self, args: &HttpRequestArgs) -> Result<HttpRequestResult, String> {
+        match self {
+            RpcOutcall::Http(outcall) => outcall.request(args).await,
+            RpcOutcall::EvmRpc(outcall) => outcall.request(args).await,
+        }
+    }
+
+    fn transform_context(&self) -> Option<TransformContext> {
+        match self {
+            RpcOutcall::Http(outcall) => outcall.transform_context(),
+            RpcOutcall::EvmRpc(outcall) => outcall.transform_context(),
+        }
+    }
+
+    async fn multi_request(
+        &self,
+        args: &[HttpRequestArgs],
+        method: &str,
+        params: &[Value],
+        quorum: usize,
+    ) -> Option<MultiCallResult<Value>> {
+        match self {
+            RpcOutcall::Http(outcall) => outcall.multi_request(args, method, params, quorum).await,
+            RpcOutcall::EvmRpc(outcall) => {
+                outcall.multi_request(args, method, params, quorum).await
+            }
+        }
+    }
+}
+
+// Types of the EVM RPC canister's methods (evm_rpc.did), with the fields this canister uses.
+#[derive(Clone, Debug, CandidType, Deserialize, PartialEq, Eq)]
+pub struct RpcApi {
+    pub url: String,
+    pub headers: Option<Vec<HttpHeader>>,
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize, PartialEq, Eq)]
+pub enum RpcService {
+    Custom(RpcApi),
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize, PartialEq, Eq)]
+pub enum RpcServices {
+    Custom {
+        #[serde(rename = "chainId")]
+        chain_id: u64,
+        services: Vec<RpcApi>,
+    },
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize, PartialEq, Eq)]
+pub enum ConsensusStrategy {
+    Equality,
+    Threshold { total: Option<u8>, min: u8 },
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize, PartialEq, Eq)]
+pub struct RpcConfig {
+    #[serde(rename = "responseSizeEstimate")]
+    pub response_size_estimate: Option<u64>,
+    #[serde(rename = "responseConsensus")]
+    pub response_consensus: Option<ConsensusStrategy>,
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize, PartialEq, Eq)]
+pub struct GetLogsRpcConfig {
+    #[serde(rename = "responseSizeEstimate")]
+    pub response_size_estimate: Option<u64>,
+    #[serde(rename = "responseConsensus")]
+    pub response_consensus: Option<ConsensusStrategy>,
+    #[serde(rename = "maxBlockRange")]
+    pub max_block_range: Option<u32>,
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize, PartialEq, Eq)]
+pub enum BlockTag {
+    Latest,
+    Finalized,
+    Safe,
+    Earliest,
+    Pending,
+    Number(Nat),
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize, PartialEq, Eq)]
+pub struct GetTransactionCountArgs {
+    pub address: String,
+    pub block: BlockTag,
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize, PartialEq, Eq)]
+pub struct GetLogsArgs {
+    #[serde(rename = "fromBlock")]
+    pub from_block: Option<BlockTag>,
+    #[serde(rename = "toBlock")]
+    pub to_block: Option<BlockTag>,
+    pub addresses: Vec<String>,
+    pub topics: Option<Vec<Vec<String>>>,
+}
+
+// the other (optional) fields of the canister's TransactionRequest are null
+#[derive(Clone, Debug, CandidType, Deserialize, PartialEq, Eq)]
+pub struct TransactionRequest {
+    pub to: Option<String>,
+    pub input: Option<String>,
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize, PartialEq, Eq)]
+pub struct CallArgs {
+    pub transaction: TransactionRequest,
+    pub block: Option<BlockTag>,
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize)]
+pub struct Block {
+    pub number: Nat,
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize)]
+pub struct LogEntry {
+    pub address: String,
+    pub topics: Vec<String>,
+    pub data: String,
+    #[serde(rename = "blockNumber")]
+    pub block_number: Option<Nat>,
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: Option<String>,
+    #[serde(rename = "transactionIndex")]
+    pub transaction_index: Option<Nat>,
+    #[serde(rename = "blockHash")]
+    pub block_hash: Option<String>,
+    #[serde(rename = "logIndex")]
+    pub log_index: Option<Nat>,
+    pub removed: bool,
+}
+
+impl LogEntry {
+    fn to_json(&self) -> Value {
+        json!({
+            "address": self.address,
+            "topics": self.topics,
+            "data": self.data,
+            "blockNumber": self.block_number.as_ref().map(nat_to_hex),
+            "transactionHash": self.transaction_hash,
+            "transactionIndex": self.transaction_index.as_ref().map(nat_to_hex),
+            "blockHash": self.block_hash,
+            "logIndex": self.log_index.as_ref().map(nat_to_hex),
+            "removed": self.removed,
+        })
+    }
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize)]
+pub struct TransactionReceipt {
+    #[serde(rename = "blockHash")]
+    pub block_hash: String,
+    #[serde(rename = "blockNumber")]
+    pub block_number: Nat,
+    #[serde(rename = "effectiveGasPrice")]
+    pub effective_gas_price: Nat,
+    #[serde(rename = "gasUsed")]
+    pub gas_used: Nat,
+    #[serde(rename = "cumulativeGasUsed")]
+    pub cumulative_gas_used: Nat,
+    pub status: Option<Nat>,
+    pub root: Option<String>,
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: String,
+    #[serde(rename = "contractAddress")]
+    pub contract_address: Option<String>,
+    pub from: String,
+    pub logs: Vec<LogEntry>,
+    #[serde(rename = "logsBloom")]
+    pub logs_bloom: String,
+    pub to: Option<String>,
+    #[serde(rename = "transactionIndex")]
+    pub transaction_index: Nat,
+    #[serde(rename = "type")]
+    pub tx_type: String,
+}
+
+impl TransactionReceipt {
+    // the JSON-RPC form of the receipt
+    fn to_json(&self) -> Value {
+        let mut receipt = json!({
+            "type": self.tx_type,
+            "blockHash": self.block_hash,
+            "blockNumber": nat_to_hex(&self.block_number),
+            "effectiveGasPrice": nat_to_hex(&self.effective_gas_price),
+            "gasUsed": nat_to_hex(&self.gas_used),
+            "cumulativeGasUsed": nat_to_hex(&self.cumulative_gas_used),
+            "transactionHash": self.transaction_hash,
+            "contractAddress": self.contract_address,
+            "from": self.from,
+            "logs": self.logs.iter().map(|l| l.to_json()).collect::<Vec<_>>(),
+            "logsBloom": self.logs_bloom,
+            "to": self.to,
+            "transactionIndex": nat_to_hex(&self.transaction_index),
+        });
+        if let Some(status) = &self.status {
+            receipt["status"] = nat_to_hex(status).into();
+        }
+        if let Some(root) = &self.root {
+            receipt["root"] = root.clone().into();
+        }
+        receipt
+    }
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize)]
+pub enum SendRawTransactionStatus {
+    Ok(Option<String>),
+    InsufficientFunds,
+    NonceTooLow,
+    NonceTooHigh,
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize)]
+pub enum MultiRpcResult<T> {
+    Consistent(Result<T, RpcError>),
+    Inconsistent(Vec<(RpcService, Result<T, RpcError>)>),
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize)]
+#[allow(clippy::enum_variant_names)]
+pub enum RpcError {
+    JsonRpcError(JsonRpcError),
+    ProviderError(ProviderError),
+    ValidationError(ValidationError),
+    HttpOutcallError(HttpOutcallError),
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize)]
+pub enum ProviderError {
+    TooFewCycles {
+        expected: candid::Nat,
+        received: candid::Nat,
+    },
+    MissingRequiredProvider,
+    ProviderNotFound,
+    NoPermission,
+    InvalidRpcConfig(String),
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize)]
+pub enum ValidationError {
+    Custom(String),
+    InvalidHex(String),
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize)]
+pub enum HttpOutcallError {
+    IcError {
+        code: RejectionCode,
+        message: String,
+    },
+    InvalidHttpJsonRpcResponse {
+        status: u16,
+        body: String,
+        #[serde(rename = "parsingError")]
+        parsing_error: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, CandidType, Deserialize)]
+pub enum RejectionCode {
+    NoError,
+    CanisterError,
+    SysTransient,
+    DestinationInvalid,
+    Unknown,
+    SysFatal,
+    CanisterReject,
+}
+
+fn nat_to_hex(n: &Nat) -> String {
+    format!("{:#x}", n.0)
+}
+
+fn param_str(params: &[Value], i: usize) -> Result<String, String> {
+    params
+        .get(i)
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_string())
+        .ok_or_else(|| format!("missing string param {i}"))
+}
+
+fn block_tag(tag: &str) -> Result<BlockTag, String> {
+    Ok(match tag {
+        "latest" => BlockTag::Latest,
+        "finalized" => BlockTag::Finalized,
+        "safe" => BlockTag::Safe,
+        "earliest" => BlockTag::Earliest,
+        "pending" => BlockTag::Pending,
+        _ => {
+            let n = u64::from_str_radix(tag.trim_start_matches("0x"), 16)
+                .map_err(|err| format!("invalid block tag {tag}: {err}"))?;
+            BlockTag::Number(n.into())
+        }
+    })
+}
+
+fn evm_rpc_request_args(args: &HttpRequestArgs) -> Result<(RpcService, String, u64), String> {
+    let json = String::from_utf8(args.body.clone().unwrap_or_default())
+        .map_err(|err| format!("invalid JSON-RPC request body: {err}"))?;
+    // the EVM RPC canister sets the content-type itself
+    let headers: Vec<HttpHeader> = args
+        .headers
+        .iter()
+        .filter(|h| !h.name.eq_ignore_ascii_case("content-type"))
+        .cloned()
+        .collect();
+    let service = RpcService::Custom(RpcApi {
+        url: args.url.clone(),
+        headers: if headers.is_empty() {
+            None
+        } else {
+            Some(headers)
+        },
+    });
+    Ok((
+        service,
+        json,
+        args.max_response_bytes
+            .unwrap_or(EVM_RPC_MAX_RESPONSE_BYTES),
+    ))
+}
+
+// the bytes of a request that its cost grows with
+fn request_bytes((service, json, _): &(RpcService, String, u64)) -> usize {
+    let RpcService::Custom(api) = service;
+    json.len()
+        + api.url.len()
+        + api
+            .headers
+            .iter()
+            .flatten()
+            .map(|h| h.name.len() + h.value.len())
+            .sum::<usize>()
+}
+
 #[ic_cdk::query(hidden = true)]
 fn inner_transform_response(args: TransformArgs) -> HttpRequestResult {
     HttpRequestResult {
@@ -39,3 +873,365 @@ fn inner_transform_response(args: TransformArgs) -> HttpRequestResult {
         headers: vec![],
     }
 }
+
+/// Returns the first result that at least `quorum` of the results agree on by `key`.
+pub fn quorum_agree<T, K: PartialEq>(
+    results: Vec<Result<T, String>>,
+    quorum: usize,
+    key: impl Fn(&T) -> K,
+) -> Result<T, String> {
+    let total = results.len();
+    let mut values: Vec<T> = Vec::with_capacity(total);
+    let mut errors: Vec<String> = Vec::new();
+    for res in results {
+        match res {
+            Ok(v) => values.push(v),
+            Err(err) => errors.push(err),
+        }
+    }
+    if values.len() < quorum {
+        return Err(format!(
+            "quorum {quorum} not reached, {} of {total} providers succeeded, errors: {}",
+            values.len(),
+            errors.join("; ")
+        ));
+    }
+
+    let keys: Vec<K> = values.iter().map(key).collect();
+    match (0..keys.len()).find(|&i| keys.iter().filter(|k| **k == keys[i]).count() >= quorum) {
+        Some(i) => Ok(values.swap_remove(i)),
+        None => Err(format!(
+            "{QUORUM_DISAGREEMENT}: no result agreed by {quorum} of {total} providers"
+        )),
+    }
+}
+
+/// Returns the highest number that at least `quorum` of the results have reached,
+/// e.g. the block height that `quorum` providers have seen.
+pub fn quorum_reached(results: Vec<Result<u64, String>>, quorum: usize) -> Result<u64, String> {
+    let total = results.len();
+    let mut errors: Vec<String> = Vec::new();
+    let mut values: Vec<u64> = Vec::with_capacity(total);
+    for res in results {
+        match res {
+            Ok(v) => values.push(v),
+            Err(err) => errors.push(err),
+        }
+    }
+    if values.len() < quorum.max(1) {
+        return Err(format!(
+            "quorum {quorum} not reached, {} of {total} providers succeeded, errors: {}",
+            values.len(),
+            errors.join("; ")
+        ));
+    }
+
+    values.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(values[quorum.max(1) - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_cdk::management_canister::HttpMethod;
+
+    #[test]
+    fn test_evm_rpc_request_args() {
+        let args = HttpRequestArgs {
+            url: "https://rpc.ankr.com/eth".to_string(),
+            max_response_bytes: None,
+            method: HttpMethod::POST,
+            headers: vec![
+                HttpHeader {
+                    name: "content-type".to_string(),
+                    value: "application/json".to_string(),
+                },
+                HttpHeader {
+                    name: "idempotency-key".to_string(),
+                    value: "eth_chainId-1".to_string(),
+                },
+            ],
+            body: Some(br#"{"jsonrpc":"2.0","method":"eth_chainId","params":[],"id":1}"#.to_vec()),
+            transform: None,
+            is_replicated: Some(false),
+        };
+
+        let (service, json, max_response_bytes) = evm_rpc_request_args(&args).unwrap();
+        assert_eq!(
+            service,
+            RpcService::Custom(RpcApi {
+                url: "https://rpc.ankr.com/eth".to_string(),
+                headers: Some(vec![HttpHeader {
+                    name: "idempotency-key".to_string(),
+                    value: "eth_chainId-1".to_string(),
+                }]),
+            })
+        );
+        assert_eq!(
+            json,
+            r#"{"jsonrpc":"2.0","method":"eth_chainId","params":[],"id":1}"#
+        );
+        assert_eq!(max_response_bytes, EVM_RPC_MAX_RESPONSE_BYTES);
+    }
+
+    // A stand-in EVM RPC canister, it decodes the Candid arguments of each call as the canister
+    // does and answers with Candid encoded results.
+    #[derive(Clone, Default)]
+    struct StandInEvmRpc {
+        // (method, attached cycles)
+        calls: std::sync::Arc<std::sync::Mutex<Vec<(String, u128)>>>,
+    }
+
+    // the canister's block has more fields than this canister decodes
+    #[derive(CandidType)]
+    struct FullBlock {
+        number: Nat,
+        hash: String,
+        timestamp: Nat,
+    }
+
+    const REQUEST_COST: u128 = 1_000_000;
+
+    // returns the url of the first service
+    fn check_services(
+        services: &RpcServices,
+        config: &Option<RpcConfig>,
+        total: u8,
+        min: u8,
+    ) -> String {
+        let RpcServices::Custom { chain_id, services } = services;
+        assert_eq!(*chain_id, 56);
+        assert_eq!(services.len(), total as usize);
+        let config = config.as_ref().unwrap();
+        assert_eq!(
+            config.response_consensus,
+            Some(ConsensusStrategy::Threshold {
+                total: Some(total),
+                min
+            })
+        );
+        services[0].url.clone()
+    }
+
+    impl CanisterCall for StandInEvmRpc {
+        async fn call(
+            &self,
+            _canister: Principal,
+            method: &str,
+            args: Vec<u8>,
+            cycles: u128,
+        ) -> Result<Vec<u8>, String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((method.to_string(), cycles));
+            let service = |url: &str| {
+                RpcService::Custom(RpcApi {
+                    url: url.to_string(),
+                    headers: None,
+                })
+            };
+            let res = match method {
+                "requestCost" => {
+                    let (_, json, _): (RpcService, String, u64) =
+                        candid::decode_args(&args).unwrap();
+                    assert!(json.contains("\"jsonrpc\":\"2.0\""));
+                    candid::encode_one(Ok::<Nat, RpcError>(REQUEST_COST.into()))
+                }
+                "request" => {
+                    let (service, json, _): (RpcService, String, u64) =
+                        candid::decode_args(&args).unwrap();
+                    assert!(matches!(service, RpcService::Custom(_)));
+                    assert!(json.contains("eth_chainId"));
+                    candid::encode_one(Ok::<String, RpcError>(
+                        r#"{"jsonrpc":"2.0","id":1,"result":"0x38"}"#.to_string(),
+                    ))
+                }
+                "eth_getBlockByNumber" => {
+                    let (services, config, tag): (RpcServices, Option<RpcConfig>, BlockTag) =
+                        candid::decode_args(&args).unwrap();
+                    assert_eq!(check_services(&services, &config, 3, 2), "https://first");
+                    assert_eq!(tag, BlockTag::Latest);
+                    let block = |number: u64| FullBlock {
+                        number: number.into(),
+                        hash: format!("0x{number:064x}"),
+                        timestamp: 1_700_000_000u64.into(),
+                    };
+                    candid::encode_one(MultiRpcResult::Inconsistent(vec![
+                        (service("https://first"), Ok(block(100))),
+                        (service("https://second"), Ok(block(102))),
+                        (service("https://third"), Ok(block(101))),
+                    ]))
+                }
+                "eth_getTransactionCount" => {
+                    let (services, config, args): (
+                        RpcServices,
+                        Option<RpcConfig>,
+                        GetTransactionCountArgs,
+                    ) = candid::decode_args(&args).unwrap();
+                    // one provider at a time at quorum 1, the first one fails
+                    let url = check_services(&services, &config, 1, 1);
+                    assert_eq!(args.block, BlockTag::Pending);
+                    candid::encode_one(MultiRpcResult::<Nat>::Consistent(
+                        if url == "https://first" {
+                            Err(RpcError::JsonRpcError(JsonRpcError {
+                                code: -32000,
+                                message: "internal error".to_string(),
+                            }))
+                        } else {
+                            Ok(7u64.into())
+                        },
+                    ))
+                }
+                "eth_getTransactionReceipt" => {
+                    let (services, config, tx_hash): (RpcServices, Option<RpcConfig>, String) =
+                        candid::decode_args(&args).unwrap();
+                    check_services(&services, &config, 3, 2);
+                    let n = |v: u64| -> Nat { v.into() };
+                    let receipt = TransactionReceipt {
+                        block_hash: format!("0x{:064x}", 1),
+                        block_number: n(0x418f472),
+                        effective_gas_price: n(0x7270e00),
+                        gas_used: n(0xcbdb),
+                        cumulative_gas_used: n(0x3e45f),
+                        status: Some(n(1)),
+                        root: None,
+                        transaction_hash: tx_hash,
+                        contract_address: None,
+                        from: "0x9ac6b9ffbb4269fc51cf0ef7bcd322cefb3e5e14".to_string(),
+                        logs: vec![],
+                        logs_bloom: format!("0x{}", "0".repeat(512)),
+                        to: Some("0xe74583edaff618d88463554b84bc675196b36990".to_string()),
+                        transaction_index: n(3),
+                        tx_type: "0x2".to_string(),
+                    };
+                    candid::encode_one(MultiRpcResult::Consistent(Ok(Some(receipt))))
+                }
+                "eth_sendRawTransaction" => {
+                    let (_, _, tx): (RpcServices, Option<RpcConfig>, String) =
+                        candid::decode_args(&args).unwrap();
+                    assert_eq!(tx, "0x02f8");
+                    candid::encode_one(MultiRpcResult::Consistent(Ok(
+                        SendRawTransactionStatus::NonceTooLow,
+                    )))
+                }
+                _ => panic!("unexpected method {method}"),
+            };
+            Ok(res.unwrap())
+        }
+    }
+
+    #[test]
+    fn test_evm_rpc_outcall_with_stand_in() {
+        use crate::evm::{Address, EvmClient, TxHash};
+        use alloy_primitives::hex::FromHex;
+        use futures::executor::block_on;
+
+        let stand_in = StandInEvmRpc::default();
+        let outcall = EvmRpcOutcall {
+            canister: Principal::management_canister(),
+            chain_id: 56,
+            caller: stand_in.clone(),
+        };
+        let providers = vec![
+            "https://first".to_string(),
+            "https://second".to_string(),
+            "https://third".to_string(),
+        ];
+        let client = EvmClient::new(providers, 5, None, outcall).with_quorum(2);
+
+        // the block height that 2 providers have reached, one quote for all providers
+        assert_eq!(block_on(client.block_number(1_000)).unwrap(), 101);
+        let block_calls = vec![
+            ("requestCost".to_string(), 0),
+            (
+                "eth_getBlockByNumber".to_string(),
+                REQUEST_COST * 3 * 110 / 100,
+            ),
+        ];
+        assert_eq!(*stand_in.calls.lock().unwrap(), block_calls);
+
+        // the quote is cached for the method and chain
+        stand_in.calls.lock().unwrap().clear();
+        assert_eq!(block_on(client.block_number(2_000)).unwrap(), 101);
+        assert_eq!(*stand_in.calls.lock().unwrap(), block_calls[1..]);
+
+        // a single provider at quorum 1, failing over to the next one
+        stand_in.calls.lock().unwrap().clear();
+        let address = Address::from_hex("0x9ac6b9ffbb4269fc51cf0ef7bcd322cefb3e5e14").unwrap();
+        assert_eq!(
+            block_on(client.get_transaction_count(1_000, &address, "pending")).unwrap(),
+            7
+        );
+        assert_eq!(
+            *stand_in.calls.lock().unwrap(),
+            vec![
+                ("requestCost".to_string(), 0),
+                (
+                    "eth_getTransactionCount".to_string(),
+                    REQUEST_COST * 110 / 100
+                ),
+                (
+                    "eth_getTransactionCount".to_string(),
+                    REQUEST_COST * 110 / 100
+                ),
+            ]
+        );
+
+        let tx_hash =
+            TxHash::from_hex("0xbbded599a5f088cb82d9b439043ff691857ebff4f480225d5d563aed4ef11aaa")
+                .unwrap();
+        let receipt = block_on(client.get_transaction_receipt(1_000, &tx_hash))
+            .unwrap()
+            .unwrap();
+        assert!(receipt.status());
+        assert_eq!(receipt.transaction_hash, tx_hash);
+        assert_eq!(receipt.block_number, Some(0x418f472));
+
+        let err = block_on(client.send_raw_transaction(1_000, "0x02f8".to_string())).unwrap_err();
+        assert!(err.contains("nonce too low"));
+
+        // no typed method, each provider is requested through `request`
+        stand_in.calls.lock().unwrap().clear();
+        assert_eq!(block_on(client.chain_id(1_000)).unwrap(), 56);
+        {
+            let calls = stand_in.calls.lock().unwrap();
+            assert_eq!(
+                calls
+                    .iter()
+                    .filter(|(m, c)| m == "request" && *c == REQUEST_COST * 110 / 100)
+                    .count(),
+                3
+            );
+        }
+
+        // a quote short of cycles is dropped and quoted again
+        REQUEST_COSTS.with_borrow_mut(|costs| {
+            costs
+                .get_mut(&(Principal::management_canister(), 56, "request".to_string()))
+                .unwrap()
+                .cycles = 1;
+        });
+        let outcall = EvmRpcOutcall {
+            canister: Principal::management_canister(),
+            chain_id: 56,
+            caller: stand_in.clone(),
+        };
+        outcall.check_request_cost::<Value>(
+            "request",
+            &Ok(MultiCallResult::Consistent(Err(
+                "EVM RPC canister error: ProviderError(TooFewCycles { .. })".to_string(),
+            ))),
+        );
+        stand_in.calls.lock().unwrap().clear();
+        assert_eq!(block_on(client.chain_id(2_000)).unwrap(), 56);
+        let calls = stand_in.calls.lock().unwrap();
+        assert!(calls.iter().any(|(m, _)| m == "requestCost"));
+        assert!(
+            calls
+                .iter()
+                .filter(|(m, _)| m == "request")
+                .all(|(_, c)| *c == REQUEST_COST * 110 / 100)
+        );
+    }
+}
@@ -1,10 +1,13 @@
-use alloy_consensus::{SignableTransaction, Signed, TxEip1559};
+use alloy_consensus::{
+    SignableTransaction, Signed, Transaction as _, TxEip1559, TxEip2930, TxEnvelope, TxLegacy,
+};
 use alloy_eips::eip2718::Encodable2718;
 use alloy_primitives::{Address, Bytes, Signature, TxHash, U256, hex};
 use candid::{CandidType, Nat, Principal};
 use ciborium::{from_reader, into_writer};
 use ic_http_certification::{
-    HttpCertification, HttpCertificationPath, HttpCertificationTree, HttpCertificationTreeEntry,
+    DefaultResponseCertification, DefaultResponseOnlyCelExpression, HttpCertification,
+    HttpCertificationPath, HttpCertificationTree, HttpCertificationTreeEntry, HttpResponse,
     cel::{DefaultCelBuilder, create_cel_expr},
 };
 use ic_stable_structures::{
@@ -18,24 +21,30 @@ use icrc_ledger_types::{
 };
 use num_traits::cast::ToPrimitive;
 use serde::{Deserialize, Serialize};
-use serde_bytes::ByteArray;
+use serde_bytes::{ByteArray, ByteBuf};
 use std::{
     borrow::Cow,
     cell::RefCell,
     cmp,
-    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
     time::Duration,
 };
 
 use crate::{
     ecdsa::{cost_sign_with_ecdsa, derive_public_key, ecdsa_public_key, sign_with_ecdsa},
-    evm::{EvmClient, encode_erc20_transfer},
+    evm::{
+        Erc20TransferLog, EvmClient, TRANSFER_EVENT_TOPIC, address_to_topic,
+        decode_erc20_transfer_log, encode_erc20_burn, encode_erc20_mint, encode_erc20_transfer,
+    },
     helper::{call, convert_amount, format_error},
-    outcall::DefaultHttpOutcall,
+    outcall::{DefaultHttpOutcall, EvmRpcOutcall, RpcOutcall},
     schnorr::{derive_schnorr_public_key, schnorr_public_key, sign_with_schnorr},
     svm::{
-        Message, Pubkey, Signature as SvmSignature, SignatureStatus, SvmClient, Transaction,
+        Hash, Instruction, Message, Pubkey, SYSTEM_PROGRAM_ID, Signature as SvmSignature,
+        SignatureStatus, SvmClient, Transaction, burn_checked_instruction,
         create_associated_token_account_idempotent, get_associated_token_address, instruction,
+        mint_to_checked_instruction, prioritization_fee_percentile,
+        set_compute_unit_limit_instruction, set_compute_unit_price_instruction,
         transfer_checked_instruction,
     },
     types::PublicKeyOutput,
@@ -43,7 +52,32 @@ use crate::{
 
 type Memory = VirtualMemory<DefaultMemoryImpl>;
 
-const MAX_ERROR_ROUNDS: u64 = 42;
+// max consecutive errors of a task before it is moved to the dead letters
+const MAX_TASK_ERRORS: u32 = 42;
+// retry delay of a task after an error, doubled on every consecutive error
+const TASK_RETRY_DELAY_MS: u64 = 5_000;
+const MAX_TASK_RETRY_DELAY_MS: u64 = 3_600_000;
+// base fee of a Solana transaction with one signature
+const SVM_TX_FEE_LAMPORTS: u128 = 5_000;
+// rent-exempt balance of a durable nonce account (80 bytes)
+const SVM_NONCE_ACCOUNT_LAMPORTS: u64 = 1_447_680;
+// a nonce account creation not found after this time has expired with its blockhash
+const SVM_NONCE_ACCOUNT_TIMEOUT_MS: u64 = 5 * 60 * 1000;
+// blocks that a Solana blockhash stays valid for after its own block
+const SVM_MAX_PROCESSING_AGE: u64 = 150;
+// rolling windows of rate limits are tracked in this many buckets
+const RATE_WINDOW_BUCKETS: u64 = 24;
+// max block range of an eth_getLogs request when scanning deposits
+const MAX_LOG_BLOCK_RANGE: u64 = 500;
+// max pending tasks processed in parallel in a finalize bridging round
+const MAX_ROUND_TASKS: usize = 10;
+// an outbound EVM transaction unmined for so long is replaced with bumped fees
+const EVM_TX_TIMEOUT_SECS: u64 = 300;
+// rounds that the nonce of an outbound EVM transaction must be found used at finalized depth
+// without any of its transactions mined before the task is left to the controllers
+const EVM_NONCE_USED_CHECKS: u32 = 3;
+// an outbound EVM transaction is replaced at most so many times (fees bumped ~10.5x)
+const MAX_EVM_TX_REPLACEMENTS: usize = 20;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct State {
@@ -52,23 +86,17 @@ pub struct State {
     pub evm_address: Address,
     #[serde(default)]
     pub svm_address: Pubkey,
-    pub token_name: String,
-    pub token_symbol: String,
-    pub token_decimals: u8,
-    pub token_logo: String,
-    pub token_ledger: Principal,
+    // token_symbol => Token
     #[serde(default)]
-    pub token_bridge_fee: u128, // with the same decimals as token
-    pub min_threshold_to_bridge: u128,
-    // chain_name => (contract_address, decimals, chain_id)
-    pub evm_token_contracts: HashMap<String, (Address, u8, u64)>,
+    pub tokens: BTreeMap<String, Token>,
+    // symbol of the token bridged before multi-token support, the archived logs of that time
+    // have no token
+    #[serde(default)]
+    pub legacy_token: String,
     // chain_name => (gas_updated_at, gas_price, max_priority_fee_per_gas)
     pub evm_latest_gas: HashMap<String, (u64, u128, u128)>,
     // chain_name => (max_confirmations, [provider_url])
     pub evm_providers: HashMap<String, (u64, Vec<String>)>,
-    // (token_address, decimals, token_program)
-    #[serde(default)]
-    pub svm_token_address: (Pubkey, u8, Pubkey),
     #[serde(default)]
     pub svm_providers: Vec<String>,
     pub ecdsa_public_key: PublicKeyOutput,
@@ -79,690 +107,3089 @@ pub struct State {
     // (round, running)
     pub finalize_bridging_round: (u64, bool),
     #[serde(default)]
-    pub total_bridged_tokens: u128,
+    pub sub_bridges: BTreeSet<Principal>,
+    // tasks that failed fatally or kept failing, kept for inspection by the administrators
     #[serde(default)]
-    pub total_collected_fees: u128,
+    pub dead_letters: Vec<BridgeLog>,
+    // sender_address => deposit intent, senders are proved by EIP-191 signatures
     #[serde(default)]
-    pub total_withdrawn_fees: u128,
+    pub evm_deposit_intents: BTreeMap<Address, EvmDepositIntent>,
+    // chain_name => last scanned block number
     #[serde(default)]
-    pub sub_bridges: BTreeSet<Principal>,
+    pub evm_deposit_cursors: HashMap<String, u64>,
+    // deposits from unregistered senders, credited once the sender registers
     #[serde(default)]
-    pub error_rounds: u64,
-}
-
-#[derive(CandidType, Serialize, Deserialize)]
-pub struct StateInfo {
-    pub key_name: String,
-    pub icp_address: Principal,
-    pub evm_address: String,
-    pub svm_address: String,
-    pub token_name: String,
-    pub token_symbol: String,
-    pub token_decimals: u8,
-    pub token_logo: String,
-    pub token_ledger: Principal,
-    pub token_bridge_fee: u128,
-    pub min_threshold_to_bridge: u128,
-    pub evm_token_contracts: HashMap<String, (String, u8, u64)>,
-    pub evm_latest_gas: HashMap<String, (u64, u128, u128)>,
-    pub evm_providers: HashMap<String, (u64, Vec<String>)>,
-    pub svm_token_address: (String, u8, String),
-    pub svm_providers: Vec<String>,
-    pub finalize_bridging_round: (u64, bool),
-    pub total_bridged_tokens: u128,
-    pub total_collected_fees: u128,
-    pub total_withdrawn_fees: u128,
-    pub total_bridge_count: u64,
-    pub sub_bridges: BTreeSet<Principal>,
-    pub error_rounds: u64,
-    pub governance_canister: Option<Principal>,
+    pub evm_unmatched_deposits: Vec<EvmDeposit>,
+    #[serde(skip)]
+    pub evm_deposit_scanning: bool,
+    // chain_name ("SOL" or EVM chain names) => number of providers that must agree on reads
+    #[serde(default)]
+    pub rpc_quorums: HashMap<String, u8>,
+    // chain_name => EVM RPC canister that the chain's JSON-RPC requests are routed through
+    #[serde(default)]
+    pub evm_rpc_canisters: HashMap<String, Principal>,
+    // refund a task after its destination leg failed so many times, 0 means only on definitive failures
+    #[serde(default)]
+    pub refund_after_retries: u32,
+    // chain_name ("ICP", "SOL" or EVM chain names) => whether outbound transactions are halted too
+    #[serde(default)]
+    pub paused_chains: HashMap<String, bool>,
+    // (from_chain, to_chain) routes that accept no new bridging
+    #[serde(default)]
+    pub paused_routes: BTreeSet<(String, String)>,
+    // "FROM->TO" route => moving average of the time to finality, in milliseconds
+    #[serde(default)]
+    pub finality_ms: HashMap<String, u64>,
+    // token_symbol => latest reserve reconciliation
+    #[serde(default)]
+    pub reserves: BTreeMap<String, ReserveReport>,
+    #[serde(skip)]
+    pub reserves_reconciling: bool,
+    // chain_name => next nonce of the canister's EVM address, assigned locally
+    #[serde(default)]
+    pub evm_nonces: HashMap<String, u64>,
+    // chain_name => nonces assigned in the running finalize bridging round
+    #[serde(skip)]
+    pub evm_nonces_reserved: HashMap<String, BTreeSet<u64>>,
+    // chain_name => seconds before an unmined outbound transaction is replaced, 0 to disable
+    #[serde(default)]
+    pub evm_tx_timeouts: HashMap<String, u64>,
+    // chain_name => cap of the max_fee_per_gas (the gas price of legacy transactions) that
+    // replacements are bumped to, in wei
+    #[serde(default)]
+    pub evm_max_fees: HashMap<String, u128>,
+    // chain_name => how gas limits are derived from estimates, the default if not set
+    #[serde(default)]
+    pub evm_gas_limits: HashMap<String, GasLimitConfig>,
+    // "chain/token/call" => latest gas estimate of the canister's outbound token transactions,
+    // charged by the gas surcharge
+    #[serde(default)]
+    pub evm_gas_estimates: HashMap<String, u64>,
+    // chain_name => type of the transactions signed for the chain, EIP-1559 if not set
+    #[serde(default)]
+    pub evm_tx_types: HashMap<String, EvmTxType>,
+    // chain_name => minimum native balance (wei or lamports) of the canister's address,
+    // bridging to the chain is refused below it
+    #[serde(default)]
+    pub gas_minimums: HashMap<String, u128>,
+    // chain_name => (checked_at, native balance of the canister's address)
+    #[serde(default)]
+    pub gas_balances: HashMap<String, (u64, u128)>,
+    #[serde(default)]
+    pub svm_priority_fee: SvmPriorityFeeConfig,
+    // (updated_at, compute unit price in micro-lamports) of the latest Solana transaction
+    #[serde(default)]
+    pub svm_latest_priority_fee: (u64, u64),
+    // durable nonce accounts of the canister's SOL address, advanced by its outbound transactions
+    #[serde(default)]
+    pub svm_nonce_accounts: Vec<Pubkey>,
+    // (nonce account, creation signature, sent_at) of the nonce accounts being created, they are
+    // used once the creation is finalized
+    #[serde(default)]
+    pub svm_nonce_accounts_pending: Vec<(Pubkey, ByteArray<64>, u64)>,
+    // nonce accounts used in the running finalize bridging round
+    #[serde(skip)]
+    pub svm_nonces_reserved: BTreeSet<Pubkey>,
 }
 
-impl From<&State> for StateInfo {
-    fn from(s: &State) -> Self {
-        Self {
-            key_name: s.key_name.clone(),
-            icp_address: s.icp_address,
-            evm_address: s.evm_address.to_string(),
-            svm_address: s.svm_address.to_string(),
-            token_name: s.token_name.clone(),
-            token_symbol: s.token_symbol.clone(),
-            token_decimals: s.token_decimals,
-            token_logo: s.token_logo.clone(),
-            token_ledger: s.token_ledger,
-            token_bridge_fee: s.token_bridge_fee,
-            min_threshold_to_bridge: s.min_threshold_to_bridge,
-            evm_token_contracts: s
-                .evm_token_contracts
-                .iter()
-                .map(|(k, v)| (k.clone(), (v.0.to_string(), v.1, v.2)))
-                .collect(),
-
-            evm_latest_gas: s
-                .evm_latest_gas
-                .iter()
-                .map(|(k, v)| (k.clone(), *v))
-                .collect(),
-            evm_providers: s
-                .evm_providers
-                .iter()
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect(),
-            svm_token_address: (
-                s.svm_token_address.0.to_string(),
-                s.svm_token_address.1,
-                s.svm_token_address.2.to_string(),
-            ),
-            svm_providers: s.svm_providers.clone(),
-            finalize_bridging_round: s.finalize_bridging_round,
-            total_bridged_tokens: s.total_bridged_tokens,
-            total_collected_fees: s.total_collected_fees,
-            total_withdrawn_fees: s.total_withdrawn_fees,
-            total_bridge_count: 0,
-            sub_bridges: s.sub_bridges.clone(),
-            error_rounds: s.error_rounds,
-            governance_canister: s.governance_canister,
-        }
-    }
+/// How the ComputeBudget instructions of the canister's Solana transactions are set.
+#[derive(Clone, Debug, CandidType, Serialize, Deserialize)]
+pub struct SvmPriorityFeeConfig {
+    // compute units requested by a transaction
+    pub compute_unit_limit: u32,
+    // percentile (0-100) of the recent prioritization fees paid as the compute unit price
+    pub percentile: u8,
+    // max compute unit price in micro-lamports, 0 disables priority fees
+    pub max_micro_lamports: u64,
 }
 
-impl State {
-    fn new() -> Self {
+impl Default for SvmPriorityFeeConfig {
+    fn default() -> Self {
         Self {
-            key_name: "dfx_test_key".to_string(),
-            icp_address: ic_cdk::api::canister_self(),
-            evm_address: [0u8; 20].into(),
-            svm_address: Pubkey::default(), // 11111111111111111111111111111111
-            token_name: "ICPanda".to_string(),
-            token_symbol: "PANDA".to_string(),
-            token_decimals: 8,
-            token_logo: "https://532er-faaaa-aaaaj-qncpa-cai.icp0.io/f/374?inline&filename=1734188626561.webp".to_string(),
-            token_ledger: Principal::from_text("druyg-tyaaa-aaaaq-aactq-cai").unwrap(), // mainnet ledger
-            token_bridge_fee: 0,
-            min_threshold_to_bridge: 100_000_000, // 1 Token (8 decimals)
-            evm_token_contracts: HashMap::new(),
-            evm_providers: HashMap::new(),
-            evm_latest_gas: HashMap::new(),
-            svm_token_address: (Pubkey::default(), 0, Pubkey::default()),
-            svm_providers: Vec::new(),
-            ecdsa_public_key: PublicKeyOutput::default(),
-            ed25519_public_key: PublicKeyOutput::default(),
-            governance_canister: None,
-            pending: VecDeque::new(),
-            finalize_bridging_round: (0, false),
-            total_bridged_tokens: 0,
-            total_collected_fees: 0,
-            total_withdrawn_fees: 0,
-            sub_bridges: BTreeSet::new(),
-            error_rounds: 0,
+            compute_unit_limit: 100_000,
+            percentile: 75,
+            max_micro_lamports: 1_000_000,
         }
     }
 }
 
-#[derive(Clone, CandidType, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub enum BridgeTarget {
-    Icp,
-    Sol,
-    Evm(String), // chain_name
-}
-
+/// How the gas limit of a transaction is derived from its `eth_estimateGas` estimate on a chain.
 #[derive(Clone, CandidType, Serialize, Deserialize)]
-pub enum BridgeTx {
-    Icp(bool, u64),           // (finalized, block_height)
-    Evm(bool, ByteArray<32>), // (finalized, tx_hash)
-    Sol(bool, ByteArray<64>), // (finalized, tx_signature)
+pub struct GasLimitConfig {
+    // safety multiplier of the estimate, in percent
+    pub multiplier_pct: u16,
+    // transactions estimated above this gas are not sent
+    pub cap: u64,
 }
 
-impl cmp::PartialEq for BridgeTx {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (BridgeTx::Icp(_, tx1), BridgeTx::Icp(_, tx2)) => tx1 == tx2,
-            (BridgeTx::Evm(_, tx1), BridgeTx::Evm(_, tx2)) => tx1 == tx2,
-            (BridgeTx::Sol(_, tx1), BridgeTx::Sol(_, tx2)) => tx1 == tx2,
-            _ => false,
+impl Default for GasLimitConfig {
+    fn default() -> Self {
+        Self {
+            multiplier_pct: 130,
+            cap: 500_000,
         }
     }
 }
 
-impl BridgeTx {
-    pub fn is_finalized(&self) -> bool {
-        match self {
-            BridgeTx::Icp(finalized, _) => *finalized,
-            BridgeTx::Evm(finalized, _) => *finalized,
-            BridgeTx::Sol(finalized, _) => *finalized,
+impl GasLimitConfig {
+    pub fn gas_limit(&self, estimated: u64) -> Result<u64, String> {
+        if estimated > self.cap {
+            return Err(format!(
+                "estimated gas {estimated} exceeds the cap {}",
+                self.cap
+            ));
         }
+        Ok((estimated.saturating_mul(self.multiplier_pct as u64) / 100).min(self.cap))
     }
+}
 
-    pub fn same_with(&self, other: &BridgeTx) -> bool {
-        match (self, other) {
-            (BridgeTx::Icp(_, tx1), BridgeTx::Icp(_, tx2)) => tx1 == tx2,
-            (BridgeTx::Evm(_, tx1), BridgeTx::Evm(_, tx2)) => tx1 == tx2,
-            (BridgeTx::Sol(_, tx1), BridgeTx::Sol(_, tx2)) => tx1 == tx2,
-            _ => false,
-        }
-    }
+/// The type of the EVM transactions signed for a chain. Chains and RPC providers without
+/// `eth_maxPriorityFeePerGas` need legacy (EIP-155) or EIP-2930 transactions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, CandidType, Serialize, Deserialize)]
+pub enum EvmTxType {
+    Legacy,
+    Eip2930,
+    #[default]
+    Eip1559,
 }
 
+/// Where the deposits sent from a user's own EVM wallet to the canister's EVM address are bridged to.
 #[derive(Clone, CandidType, Serialize, Deserialize)]
-pub struct BridgeLog {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub id: Option<u64>,
+pub struct EvmDepositIntent {
     pub user: Principal,
-    pub from: BridgeTarget,
-    pub to: BridgeTarget,
-    pub icp_amount: u128,
-    pub fee: u128,
-    pub from_tx: BridgeTx,
-    pub to_tx: Option<BridgeTx>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_chain: String,
     pub to_addr: Option<String>,
     pub created_at: u64,
-    pub finalized_at: u64,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
 }
 
+/// A confirmed ERC20 transfer to the canister's EVM address, one per Transfer event.
 #[derive(Clone, CandidType, Serialize, Deserialize)]
-pub struct BridgeLogLocal {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub id: Option<u64>,
-    #[serde(rename = "u", alias = "user")]
-    pub user: Principal,
-    #[serde(rename = "f", alias = "from")]
+pub struct EvmDeposit {
+    pub token: String,
+    pub chain: String,
+    pub sender: String,
+    pub tx_hash: ByteArray<32>,
+    #[serde(default)]
+    pub log_index: u64,
+    pub amount: u128, // in EVM token units
+    pub block_number: u64,
+}
+
+/// Fee schedule of bridging to a destination chain, in ICP token units.
+#[derive(Clone, Debug, CandidType, Serialize, Deserialize)]
+pub struct FeeSchedule {
+    pub flat: u128,
+    pub bps: u16,
+    pub min: u128,
+    pub max: u128, // 0 means no max
+    // price of one native coin of the destination chain (ETH, BNB, SOL...) in token units,
+    // the estimated gas cost of the destination transaction is charged if not 0
+    pub native_price: u128,
+}
+
+/// Breakdown of the bridge fee of a log, the fee is `base + gas`.
+#[derive(Clone, Debug, Default, CandidType, Serialize, Deserialize)]
+pub struct FeeDetail {
+    pub flat: u128,
+    pub percentage: u128,
+    // flat + percentage, bounded by the schedule's min and max
+    pub base: u128,
+    pub gas: u128,
+}
+
+impl FeeDetail {
+    pub fn total(&self) -> u128 {
+        self.base.saturating_add(self.gas)
+    }
+}
+
+/// The validated plan of a bridging.
+pub struct BridgePlan {
     pub from: BridgeTarget,
-    #[serde(rename = "t", alias = "to")]
     pub to: BridgeTarget,
-    #[serde(rename = "a", alias = "icp_amount")]
-    pub icp_amount: u128,
-    #[serde(default, rename = "e", alias = "fee")]
-    pub fee: u128,
-    #[serde(rename = "ft", alias = "from_tx")]
-    pub from_tx: BridgeTx,
-    #[serde(rename = "tt", alias = "to_tx")]
-    pub to_tx: Option<BridgeTx>,
-    #[serde(
-        rename = "ta",
-        alias = "to_addr",
-        skip_serializing_if = "Option::is_none"
-    )]
-    pub to_addr: Option<String>,
-    #[serde(rename = "ca", alias = "created_at")]
-    pub created_at: u64,
-    #[serde(rename = "fa", alias = "finalized_at")]
-    pub finalized_at: u64,
-    #[serde(
-        rename = "er",
-        alias = "error",
-        skip_serializing_if = "Option::is_none"
-    )]
-    pub error: Option<String>,
+    pub ledger: Principal,
+    pub fee_detail: FeeDetail,
+    pub dust: u128,
+    pub rate_limit: Option<RateLimit>,
+    pub release_at: u64,
 }
 
-impl From<BridgeLogLocal> for BridgeLog {
-    fn from(log: BridgeLogLocal) -> Self {
-        Self {
-            id: log.id,
-            user: log.user,
-            from: log.from,
-            to: log.to,
-            icp_amount: log.icp_amount,
-            fee: log.fee,
-            from_tx: log.from_tx,
-            to_tx: log.to_tx,
-            to_addr: log.to_addr,
-            created_at: log.created_at,
-            finalized_at: log.finalized_at,
-            error: log.error,
+/// The quote of a bridging, computed with the same validation as `bridge`.
+#[derive(Clone, CandidType, Serialize, Deserialize)]
+pub struct BridgeQuote {
+    pub fee: FeeDetail,
+    // the amount the recipient receives, in token units of the destination chain
+    pub receive_amount: u128,
+    // the amount lost to decimal truncation, in ICP token units
+    pub dust: u128,
+    pub min_threshold_to_bridge: u128,
+    // the remaining rate limit allowance of the user, None if unlimited
+    pub allowance: Option<u128>,
+    // the token held by the canister on the destination chain as of the latest reserve
+    // reconciliation, minus the payouts of the pending tasks to the chain, in ICP token units.
+    // None on mint-burn chains or before the first reconciliation
+    pub liquidity: Option<u128>,
+    // why `bridge` would refuse the bridging now (paused token, chain or route, low gas,
+    // a pending task, rate limit, liquidity), empty if it would be accepted
+    pub blocked: Vec<String>,
+    // the destination leg is held by the timelock until this time, 0 if not held
+    pub release_at: u64,
+    // estimated from recent bridging on the route, None if unknown
+    pub estimated_secs: Option<u64>,
+}
+
+/// Rolling-window volume limits of a token in ICP token units, 0 means unlimited.
+#[derive(Clone, Debug, CandidType, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub window_secs: u64,
+    pub per_user: u128,
+    // per source and per destination chain
+    pub per_chain: u128,
+    pub global: u128,
+}
+
+impl RateLimit {
+    // (usage_key, limit) of the limited scopes of a bridging
+    fn scopes(
+        &self,
+        token: &str,
+        user: &Principal,
+        from_chain: &str,
+        to_chain: &str,
+    ) -> Vec<(String, u128)> {
+        let mut scopes = Vec::with_capacity(4);
+        if self.per_user > 0 {
+            scopes.push((format!("{token}/user/{}", user.to_text()), self.per_user));
+        }
+        if self.per_chain > 0 {
+            scopes.push((format!("{token}/chain/{from_chain}"), self.per_chain));
+            scopes.push((format!("{token}/chain/{to_chain}"), self.per_chain));
         }
+        if self.global > 0 {
+            scopes.push((format!("{token}/global"), self.global));
+        }
+        scopes
     }
 }
 
-impl From<BridgeLog> for BridgeLogLocal {
-    fn from(log: BridgeLog) -> Self {
-        Self {
-            id: log.id,
-            user: log.user,
-            from: log.from,
-            to: log.to,
-            icp_amount: log.icp_amount,
-            fee: log.fee,
-            from_tx: log.from_tx,
-            to_tx: log.to_tx,
-            to_addr: log.to_addr,
-            created_at: log.created_at,
-            finalized_at: log.finalized_at,
-            error: log.error,
+/// Bridged amounts of a rate limit scope, in buckets of the rolling window.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct RateUsage {
+    // (bucket_start_ms, amount)
+    pub buckets: VecDeque<(u64, u128)>,
+}
+
+impl RateUsage {
+    fn used(&mut self, window_ms: u64, now_ms: u64) -> u128 {
+        while let Some((start, _)) = self.buckets.front() {
+            if start + window_ms > now_ms {
+                break;
+            }
+            self.buckets.pop_front();
         }
+        self.buckets
+            .iter()
+            .fold(0u128, |acc, (_, amount)| acc.saturating_add(*amount))
     }
-}
 
-impl BridgeLog {
-    pub fn is_finalized(&self) -> bool {
-        self.from_tx.is_finalized() && self.to_tx.as_ref().is_some_and(|tx| tx.is_finalized())
+    fn add(&mut self, window_ms: u64, amount: u128, now_ms: u64) {
+        let bucket_ms = (window_ms / RATE_WINDOW_BUCKETS).max(1);
+        let start = now_ms - now_ms % bucket_ms;
+        match self.buckets.back_mut() {
+            Some((last, used)) if *last == start => *used = used.saturating_add(amount),
+            _ => self.buckets.push_back((start, amount)),
+        }
     }
 
-    pub fn same_with(&self, other: &BridgeLog) -> bool {
-        self.user == other.user
-            && self.from == other.from
-            && self.to == other.to
-            && self.icp_amount == other.icp_amount
-            && self.from_tx.same_with(&other.from_tx)
+    fn sub(&mut self, mut amount: u128) {
+        for (_, used) in self.buckets.iter_mut().rev() {
+            let n = amount.min(*used);
+            *used -= n;
+            amount -= n;
+            if amount == 0 {
+                break;
+            }
+        }
     }
 }
 
-impl Storable for BridgeLogLocal {
+impl Storable for RateUsage {
     const BOUND: Bound = Bound::Unbounded;
 
     fn into_bytes(self) -> Vec<u8> {
         let mut buf = vec![];
-        into_writer(&self, &mut buf).expect("failed to encode BridgeLogLocal data");
+        into_writer(&self, &mut buf).expect("failed to encode RateUsage data");
         buf
     }
 
     fn to_bytes(&self) -> Cow<'_, [u8]> {
         let mut buf = vec![];
-        into_writer(&self, &mut buf).expect("failed to encode BridgeLogLocal data");
+        into_writer(&self, &mut buf).expect("failed to encode RateUsage data");
         Cow::Owned(buf)
     }
 
     fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
-        from_reader(&bytes[..]).expect("failed to decode BridgeLogLocal data")
+        from_reader(&bytes[..]).expect("failed to decode RateUsage data")
     }
 }
 
-#[derive(Clone, CandidType, Default, Serialize, Deserialize)]
-pub struct UserLogs {
-    pub logs: BTreeSet<u64>,
+/// A reconciliation of a token's reserves on all chains against the accounting, in ICP token units.
+#[derive(Clone, Default, CandidType, Serialize, Deserialize)]
+pub struct ReserveReport {
+    pub checked_at: u64,
+    // the canister's balance on the ICRC ledger
+    pub icp_balance: u128,
+    // collected fees not yet withdrawn, held in icp_balance
+    pub unwithdrawn_fees: u128,
+    // chain_name => (balance held by the canister, total supply)
+    pub chains: BTreeMap<String, (u128, u128)>,
+    // tokens outside ICP, which must be backed by icp_balance
+    pub circulating: u128,
+    // amount of the pending tasks of the token
+    pub in_flight: u128,
+    // the violated invariant, None if the reserves are consistent
+    pub violation: Option<String>,
 }
 
-impl Storable for UserLogs {
-    const BOUND: Bound = Bound::Unbounded;
+/// The proof of reserves of a token, served certified at `GET /reserves`.
+#[derive(Serialize)]
+pub struct TokenReserves {
+    pub checked_at: u64,
+    // chain_name ("ICP", "SOL" or EVM chain names) => balance held by the canister
+    pub locked: BTreeMap<String, u128>,
+    // chain_name => supply circulating outside the canister
+    pub circulating: BTreeMap<String, u128>,
+    pub unwithdrawn_fees: u128,
+    pub in_flight: u128,
+    pub violation: Option<String>,
+}
 
-    fn into_bytes(self) -> Vec<u8> {
-        let mut buf = vec![];
-        into_writer(&self, &mut buf).expect("failed to encode UserLogs data");
-        buf
+impl From<&ReserveReport> for TokenReserves {
+    fn from(r: &ReserveReport) -> Self {
+        let mut locked = BTreeMap::from([("ICP".to_string(), r.icp_balance)]);
+        let mut circulating = BTreeMap::new();
+        for (chain, (balance, supply)) in &r.chains {
+            locked.insert(chain.clone(), *balance);
+            circulating.insert(chain.clone(), supply.saturating_sub(*balance));
+        }
+        Self {
+            checked_at: r.checked_at,
+            locked,
+            circulating,
+            unwithdrawn_fees: r.unwithdrawn_fees,
+            in_flight: r.in_flight,
+            violation: r.violation.clone(),
+        }
     }
+}
 
-    fn to_bytes(&self) -> Cow<'_, [u8]> {
-        let mut buf = vec![];
-        into_writer(&self, &mut buf).expect("failed to encode UserLogs data");
-        Cow::Owned(buf)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Token {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub logo: String,
+    pub ledger: Principal,
+    pub bridge_fee: u128, // with the same decimals as token
+    pub min_threshold_to_bridge: u128,
+    // chain_name => (contract_address, decimals, chain_id)
+    pub evm_contracts: HashMap<String, (Address, u8, u64)>,
+    // (token_address, decimals, token_program)
+    pub svm_contract: Option<(Pubkey, u8, Pubkey)>,
+    // chain names ("SOL" or EVM chain names) whose token contract is minted and burned by the bridge
+    #[serde(default)]
+    pub mint_burn_chains: BTreeSet<String>,
+    // fee charged when a bridging is refunded
+    #[serde(default)]
+    pub refund_fee: u128,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+    // destination chain => fee schedule, bridge_fee is charged if not set
+    #[serde(default)]
+    pub fee_schedules: BTreeMap<String, FeeSchedule>,
+    // bridging of at least this amount is delayed by timelock_secs, 0 means disabled
+    #[serde(default)]
+    pub timelock_threshold: u128,
+    #[serde(default)]
+    pub timelock_secs: u64,
+    // no new bridging is accepted, set on reserve violations
+    #[serde(default)]
+    pub paused: bool,
+    // shortfall of the reserves tolerated by reconciliation
+    #[serde(default)]
+    pub reserve_tolerance: u128,
+    pub total_bridged_tokens: u128,
+    pub total_collected_fees: u128,
+    pub total_withdrawn_fees: u128,
+    // ledger fees of the canister's transfers on the ICRC ledger, paid from the collected fees
+    #[serde(default)]
+    pub total_ledger_fees: u128,
+    // amounts not representable on the destination chain, collected as fees
+    #[serde(default)]
+    pub total_dust: u128,
+    // chain_name => remainders of EVM deposits below one ICP token unit, in EVM token units
+    #[serde(default)]
+    pub deposit_dust: BTreeMap<String, u128>,
+}
+
+impl Token {
+    pub fn new(
+        name: String,
+        symbol: String,
+        decimals: u8,
+        logo: String,
+        ledger: Principal,
+        bridge_fee: u128,
+        min_threshold_to_bridge: u128,
+    ) -> Self {
+        Self {
+            name,
+            symbol,
+            decimals,
+            logo,
+            ledger,
+            bridge_fee,
+            min_threshold_to_bridge,
+            evm_contracts: HashMap::new(),
+            svm_contract: None,
+            mint_burn_chains: BTreeSet::new(),
+            refund_fee: 0,
+            rate_limit: None,
+            fee_schedules: BTreeMap::new(),
+            timelock_threshold: 0,
+            timelock_secs: 0,
+            paused: false,
+            reserve_tolerance: 0,
+            total_bridged_tokens: 0,
+            total_collected_fees: 0,
+            total_withdrawn_fees: 0,
+            total_ledger_fees: 0,
+            total_dust: 0,
+            deposit_dust: BTreeMap::new(),
+        }
     }
 
-    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
-        from_reader(&bytes[..]).expect("failed to decode UserLogs data")
+    // The collected fees that are not withdrawn nor spent on ledger fees.
+    pub fn available_fees(&self) -> u128 {
+        self.total_collected_fees
+            .saturating_sub(self.total_withdrawn_fees)
+            .saturating_sub(self.total_ledger_fees)
+    }
+
+    pub fn chain_decimals(&self, target: &BridgeTarget) -> u8 {
+        match target {
+            BridgeTarget::Icp => self.decimals,
+            BridgeTarget::Sol => self
+                .svm_contract
+                .map(|(_, d, _)| d)
+                .unwrap_or(self.decimals),
+            BridgeTarget::Evm(chain) => self
+                .evm_contracts
+                .get(chain)
+                .map(|(_, d, _)| *d)
+                .unwrap_or(self.decimals),
+        }
+    }
+
+    // Splits an amount into the part exactly representable on the chain and the truncated dust.
+    pub fn split_dust(&self, target: &BridgeTarget, amount: u128) -> Result<(u128, u128), String> {
+        let decimals = self.chain_decimals(target);
+        let exact = convert_amount(
+            convert_amount(amount, self.decimals, decimals)?,
+            decimals,
+            self.decimals,
+        )?;
+        Ok((exact, amount - exact))
+    }
+
+    // When the destination leg of a bridging is released, 0 if not delayed.
+    pub fn release_at(&self, icp_amount: u128, now_ms: u64) -> u64 {
+        if self.timelock_threshold > 0 && icp_amount >= self.timelock_threshold {
+            now_ms + self.timelock_secs * 1000
+        } else {
+            0
+        }
+    }
+
+    pub fn is_mint_burn(&self, chain: &str) -> bool {
+        self.mint_burn_chains.contains(chain)
     }
 }
 
-const STATE_MEMORY_ID: MemoryId = MemoryId::new(0);
-const USER_LOGS_MEMORY_ID: MemoryId = MemoryId::new(1);
-const BRIDGE_LOGS_INDEX_MEMORY_ID: MemoryId = MemoryId::new(2);
-const BRIDGE_LOGS_DATA_MEMORY_ID: MemoryId = MemoryId::new(3);
+/// The token operation of an outgoing EVM or SPL transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenCall<A> {
+    Transfer(A), // transfer to the address
+    Mint(A),     // mint to the address, the sender must be the minter
+    Burn,        // burn the sender's tokens
+}
 
-thread_local! {
-    static STATE: RefCell<State> = RefCell::new(State::new());
-    static HTTP_TREE: RefCell<HttpCertificationTree> = RefCell::new(HttpCertificationTree::default());
+impl<A> TokenCall<A> {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenCall::Transfer(_) => "transfer",
+            TokenCall::Mint(_) => "mint",
+            TokenCall::Burn => "burn",
+        }
+    }
+}
 
-    static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
-        RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
+// Token fields of the single-token state, used to migrate it into the token registry.
+#[derive(Deserialize)]
+struct LegacyTokenState {
+    #[serde(default)]
+    token_name: String,
+    #[serde(default)]
+    token_symbol: String,
+    #[serde(default)]
+    token_decimals: u8,
+    #[serde(default)]
+    token_logo: String,
+    #[serde(default)]
+    token_ledger: Option<Principal>,
+    #[serde(default)]
+    token_bridge_fee: u128,
+    #[serde(default)]
+    min_threshold_to_bridge: u128,
+    #[serde(default)]
+    evm_token_contracts: HashMap<String, (Address, u8, u64)>,
+    #[serde(default)]
+    svm_token_address: (Pubkey, u8, Pubkey),
+    #[serde(default)]
+    total_bridged_tokens: u128,
+    #[serde(default)]
+    total_collected_fees: u128,
+    #[serde(default)]
+    total_withdrawn_fees: u128,
+}
 
-    static STATE_STORE: RefCell<StableCell<Vec<u8>, Memory>> = RefCell::new(
-        StableCell::init(
-            MEMORY_MANAGER.with_borrow(|m| m.get(STATE_MEMORY_ID)),
-            Vec::new()
-        )
-    );
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct StateInfo {
+    pub key_name: String,
+    pub icp_address: Principal,
+    pub evm_address: String,
+    pub svm_address: String,
+    pub tokens: Vec<TokenInfo>,
+    pub evm_latest_gas: HashMap<String, (u64, u128, u128)>,
+    pub evm_providers: HashMap<String, (u64, Vec<String>)>,
+    pub svm_providers: Vec<String>,
+    pub finalize_bridging_round: (u64, bool),
+    pub total_bridge_count: u64,
+    pub sub_bridges: BTreeSet<Principal>,
+    pub dead_letters: u64,
+    pub governance_canister: Option<Principal>,
+    pub evm_deposit_cursors: HashMap<String, u64>,
+    pub rpc_quorums: HashMap<String, u8>,
+    pub evm_rpc_canisters: HashMap<String, Principal>,
+    pub refund_after_retries: u32,
+    pub paused_chains: HashMap<String, bool>,
+    pub paused_routes: BTreeSet<(String, String)>,
+    pub reserves: BTreeMap<String, ReserveReport>,
+    pub evm_nonces: HashMap<String, u64>,
+    pub evm_tx_timeouts: HashMap<String, u64>,
+    pub evm_max_fees: HashMap<String, u128>,
+    pub evm_gas_limits: HashMap<String, GasLimitConfig>,
+    pub evm_tx_types: HashMap<String, EvmTxType>,
+    pub gas_minimums: HashMap<String, u128>,
+    pub gas_balances: HashMap<String, (u64, u128)>,
+    pub svm_priority_fee: SvmPriorityFeeConfig,
+    pub svm_latest_priority_fee: (u64, u64),
+    pub svm_nonce_accounts: Vec<String>,
+    pub svm_nonce_accounts_pending: Vec<String>,
+}
 
-    static USER_LOGS: RefCell<StableBTreeMap<Principal, UserLogs, Memory>> = RefCell::new(
-        StableBTreeMap::init(
-            MEMORY_MANAGER.with_borrow(|m| m.get(USER_LOGS_MEMORY_ID)),
-        )
-    );
+#[derive(CandidType, Serialize, Deserialize)]
+pub struct TokenInfo {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub logo: String,
+    pub ledger: Principal,
+    pub bridge_fee: u128,
+    pub min_threshold_to_bridge: u128,
+    pub evm_contracts: HashMap<String, (String, u8, u64)>,
+    pub svm_contract: Option<(String, u8, String)>,
+    pub mint_burn_chains: BTreeSet<String>,
+    pub refund_fee: u128,
+    pub rate_limit: Option<RateLimit>,
+    pub fee_schedules: BTreeMap<String, FeeSchedule>,
+    pub timelock_threshold: u128,
+    pub timelock_secs: u64,
+    pub paused: bool,
+    pub reserve_tolerance: u128,
+    pub total_bridged_tokens: u128,
+    pub total_collected_fees: u128,
+    pub total_withdrawn_fees: u128,
+    pub total_ledger_fees: u128,
+    pub total_dust: u128,
+    pub deposit_dust: BTreeMap<String, u128>,
+}
 
-    static BRIDGE_LOGS: RefCell<StableLog<BridgeLogLocal, Memory, Memory>> = RefCell::new(
-        StableLog::init(
-            MEMORY_MANAGER.with_borrow(|m| m.get(BRIDGE_LOGS_INDEX_MEMORY_ID)),
-            MEMORY_MANAGER.with_borrow(|m| m.get(BRIDGE_LOGS_DATA_MEMORY_ID)),
-        )
-    );
+impl From<&Token> for TokenInfo {
+    fn from(t: &Token) -> Self {
+        Self {
+            name: t.name.clone(),
+            symbol: t.symbol.clone(),
+            decimals: t.decimals,
+            logo: t.logo.clone(),
+            ledger: t.ledger,
+            bridge_fee: t.bridge_fee,
+            min_threshold_to_bridge: t.min_threshold_to_bridge,
+            evm_contracts: t
+                .evm_contracts
+                .iter()
+                .map(|(k, v)| (k.clone(), (v.0.to_string(), v.1, v.2)))
+                .collect(),
+            svm_contract: t
+                .svm_contract
+                .map(|v| (v.0.to_string(), v.1, v.2.to_string())),
+            mint_burn_chains: t.mint_burn_chains.clone(),
+            refund_fee: t.refund_fee,
+            rate_limit: t.rate_limit.clone(),
+            fee_schedules: t.fee_schedules.clone(),
+            timelock_threshold: t.timelock_threshold,
+            timelock_secs: t.timelock_secs,
+            paused: t.paused,
+            reserve_tolerance: t.reserve_tolerance,
+            total_bridged_tokens: t.total_bridged_tokens,
+            total_collected_fees: t.total_collected_fees,
+            total_withdrawn_fees: t.total_withdrawn_fees,
+            total_ledger_fees: t.total_ledger_fees,
+            total_dust: t.total_dust,
+            deposit_dust: t.deposit_dust.clone(),
+        }
+    }
 }
 
-pub mod state {
-    use std::str::FromStr;
+impl From<&State> for StateInfo {
+    fn from(s: &State) -> Self {
+        Self {
+            key_name: s.key_name.clone(),
+            icp_address: s.icp_address,
+            evm_address: s.evm_address.to_string(),
+            svm_address: s.svm_address.to_string(),
+            tokens: s.tokens.values().map(TokenInfo::from).collect(),
+            evm_latest_gas: s
+                .evm_latest_gas
+                .iter()
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            evm_providers: s
+                .evm_providers
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            svm_providers: s.svm_providers.clone(),
+            finalize_bridging_round: s.finalize_bridging_round,
+            total_bridge_count: 0,
+            sub_bridges: s.sub_bridges.clone(),
+            dead_letters: s.dead_letters.len() as u64,
+            governance_canister: s.governance_canister,
+            evm_deposit_cursors: s
+                .evm_deposit_cursors
+                .iter()
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            rpc_quorums: s.rpc_quorums.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+            evm_rpc_canisters: s
+                .evm_rpc_canisters
+                .iter()
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            refund_after_retries: s.refund_after_retries,
+            paused_chains: s
+                .paused_chains
+                .iter()
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            paused_routes: s.paused_routes.clone(),
+            reserves: s.reserves.clone(),
+            evm_nonces: s.evm_nonces.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+            evm_tx_timeouts: s
+                .evm_tx_timeouts
+                .iter()
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            evm_max_fees: s
+                .evm_max_fees
+                .iter()
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            evm_gas_limits: s
+                .evm_gas_limits
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            evm_tx_types: s
+                .evm_tx_types
+                .iter()
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            gas_minimums: s
+                .gas_minimums
+                .iter()
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            gas_balances: s
+                .gas_balances
+                .iter()
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            svm_priority_fee: s.svm_priority_fee.clone(),
+            svm_latest_priority_fee: s.svm_latest_priority_fee,
+            svm_nonce_accounts: s.svm_nonce_accounts.iter().map(|a| a.to_string()).collect(),
+            svm_nonce_accounts_pending: s
+                .svm_nonce_accounts_pending
+                .iter()
+                .map(|(a, _, _)| a.to_string())
+                .collect(),
+        }
+    }
+}
 
-    use super::*;
+impl State {
+    fn new() -> Self {
+        Self::with_icp_address(ic_cdk::api::canister_self())
+    }
 
-    use lazy_static::lazy_static;
-    use once_cell::sync::Lazy;
+    fn with_icp_address(icp_address: Principal) -> Self {
+        Self {
+            key_name: "dfx_test_key".to_string(),
+            icp_address,
+            evm_address: [0u8; 20].into(),
+            svm_address: Pubkey::default(), // 11111111111111111111111111111111
+            tokens: BTreeMap::new(),
+            legacy_token: String::new(),
+            evm_providers: HashMap::new(),
+            evm_latest_gas: HashMap::new(),
+            evm_gas_estimates: HashMap::new(),
+            svm_providers: Vec::new(),
+            ecdsa_public_key: PublicKeyOutput::default(),
+            ed25519_public_key: PublicKeyOutput::default(),
+            governance_canister: None,
+            pending: VecDeque::new(),
+            finalize_bridging_round: (0, false),
+            sub_bridges: BTreeSet::new(),
+            dead_letters: Vec::new(),
+            evm_deposit_intents: BTreeMap::new(),
+            evm_deposit_cursors: HashMap::new(),
+            evm_unmatched_deposits: Vec::new(),
+            evm_deposit_scanning: false,
+            rpc_quorums: HashMap::new(),
+            evm_rpc_canisters: HashMap::new(),
+            refund_after_retries: 0,
+            paused_chains: HashMap::new(),
+            paused_routes: BTreeSet::new(),
+            finality_ms: HashMap::new(),
+            reserves: BTreeMap::new(),
+            reserves_reconciling: false,
+            evm_nonces: HashMap::new(),
+            evm_nonces_reserved: HashMap::new(),
+            evm_tx_timeouts: HashMap::new(),
+            evm_max_fees: HashMap::new(),
+            evm_gas_limits: HashMap::new(),
+            evm_tx_types: HashMap::new(),
+            gas_minimums: HashMap::new(),
+            gas_balances: HashMap::new(),
+            svm_priority_fee: SvmPriorityFeeConfig::default(),
+            svm_latest_priority_fee: (0, 0),
+            svm_nonce_accounts: Vec::new(),
+            svm_nonce_accounts_pending: Vec::new(),
+            svm_nonces_reserved: BTreeSet::new(),
+        }
+    }
 
-    lazy_static! {
-        pub static ref DEFAULT_EXPR_PATH: HttpCertificationPath<'static> =
-            HttpCertificationPath::wildcard("");
-        pub static ref DEFAULT_CERTIFICATION: HttpCertification = HttpCertification::skip();
-        pub static ref DEFAULT_CEL_EXPR: String =
-            create_cel_expr(&DefaultCelBuilder::skip_certification());
+    pub fn check_route(&self, from_chain: &str, to_chain: &str) -> Result<(), String> {
+        for chain in [from_chain, to_chain] {
+            if self.paused_chains.contains_key(chain) {
+                return Err(format!("chain {chain} is paused"));
+            }
+        }
+        if self
+            .paused_routes
+            .contains(&(from_chain.to_string(), to_chain.to_string()))
+        {
+            return Err(format!("route {from_chain} -> {to_chain} is paused"));
+        }
+        Ok(())
     }
 
-    pub static DEFAULT_CERT_ENTRY: Lazy<HttpCertificationTreeEntry> =
-        Lazy::new(|| HttpCertificationTreeEntry::new(&*DEFAULT_EXPR_PATH, *DEFAULT_CERTIFICATION));
+    // Refuses bridging to a chain while the last checked native balance of the canister's
+    // address on it is below the minimum, its outbound transactions could not pay the fees.
+    pub fn check_gas_balance(&self, chain: &str) -> Result<(), String> {
+        let minimum = self.gas_minimums.get(chain).copied().unwrap_or_default();
+        match self.gas_balances.get(chain) {
+            Some((_, balance)) if *balance < minimum => Err(format!(
+                "chain {chain} is low on gas, balance {balance} is below the minimum {minimum}"
+            )),
+            _ => Ok(()),
+        }
+    }
 
-    pub async fn init_public_key() {
-        let key_name = STATE.with_borrow(|r| r.key_name.clone());
-        match ecdsa_public_key(key_name.clone(), vec![]).await {
-            Ok(root_pk) => {
-                STATE.with_borrow_mut(|s| {
-                    let self_pk =
-                        derive_public_key(&root_pk, vec![s.icp_address.as_slice().to_vec()])
-                            .expect("derive_public_key failed");
-                    s.ecdsa_public_key = root_pk;
-                    s.evm_address = self_pk.to_evm_adress().unwrap();
-                });
+    pub fn is_outbound_halted(&self, chain: &str) -> bool {
+        self.paused_chains.get(chain).copied().unwrap_or(false)
+    }
+
+    pub fn token(&self, symbol: &str) -> Result<&Token, String> {
+        self.tokens
+            .get(symbol)
+            .ok_or_else(|| format!("token {symbol} not found"))
+    }
+
+    pub fn token_mut(&mut self, symbol: &str) -> Result<&mut Token, String> {
+        self.tokens
+            .get_mut(symbol)
+            .ok_or_else(|| format!("token {symbol} not found"))
+    }
+
+    pub fn evm_chain_id(&self, chain: &str) -> Option<u64> {
+        self.tokens
+            .values()
+            .find_map(|t| t.evm_contracts.get(chain).map(|(_, _, chain_id)| *chain_id))
+    }
+
+    fn migrate_legacy_token(&mut self, legacy: LegacyTokenState) {
+        let Some(ledger) = legacy.token_ledger else {
+            return;
+        };
+        if legacy.token_symbol.is_empty() {
+            return;
+        }
+
+        let symbol = legacy.token_symbol;
+        let mut token = Token::new(
+            legacy.token_name,
+            symbol.clone(),
+            legacy.token_decimals,
+            legacy.token_logo,
+            ledger,
+            legacy.token_bridge_fee,
+            legacy.min_threshold_to_bridge,
+        );
+        token.evm_contracts = legacy.evm_token_contracts;
+        if legacy.svm_token_address.0 != Pubkey::default() {
+            token.svm_contract = Some(legacy.svm_token_address);
+        }
+        token.total_bridged_tokens = legacy.total_bridged_tokens;
+        token.total_collected_fees = legacy.total_collected_fees;
+        token.total_withdrawn_fees = legacy.total_withdrawn_fees;
+
+        for log in self.pending.iter_mut() {
+            if log.token.is_empty() {
+                log.token = symbol.clone();
             }
-            Err(err) => {
-                ic_cdk::api::debug_print(format!("failed to retrieve ECDSA public key: {err}"));
+        }
+        for log in self.dead_letters.iter_mut() {
+            if log.token.is_empty() {
+                log.token = symbol.clone();
             }
         }
+        self.legacy_token = symbol.clone();
+        self.tokens.insert(symbol, token);
+    }
+}
 
-        match schnorr_public_key(key_name, vec![], None).await {
-            Ok(root_pk) => {
-                STATE.with_borrow_mut(|s| {
-                    let self_pk = derive_schnorr_public_key(
-                        &root_pk,
-                        vec![s.icp_address.as_slice().to_vec()],
-                        None,
-                    )
-                    .expect("derive_schnorr_public_key failed");
+#[derive(Clone, CandidType, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum BridgeTarget {
+    Icp,
+    Sol,
+    Evm(String), // chain_name
+}
 
-                    s.ed25519_public_key = root_pk;
-                    s.svm_address = self_pk.to_svm_pubkey().unwrap();
-                });
+impl BridgeTarget {
+    pub fn chain_name(&self) -> &str {
+        match self {
+            BridgeTarget::Icp => "ICP",
+            BridgeTarget::Sol => "SOL",
+            BridgeTarget::Evm(chain) => chain,
+        }
+    }
+}
+
+#[derive(Clone, Debug, CandidType, Serialize, Deserialize)]
+pub enum BridgeTx {
+    Icp(bool, u64),           // (finalized, block_height)
+    Evm(bool, ByteArray<32>), // (finalized, tx_hash)
+    Sol(bool, ByteArray<64>), // (finalized, tx_signature)
+}
+
+impl cmp::PartialEq for BridgeTx {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BridgeTx::Icp(_, tx1), BridgeTx::Icp(_, tx2)) => tx1 == tx2,
+            (BridgeTx::Evm(_, tx1), BridgeTx::Evm(_, tx2)) => tx1 == tx2,
+            (BridgeTx::Sol(_, tx1), BridgeTx::Sol(_, tx2)) => tx1 == tx2,
+            _ => false,
+        }
+    }
+}
+
+impl BridgeTx {
+    pub fn is_finalized(&self) -> bool {
+        match self {
+            BridgeTx::Icp(finalized, _) => *finalized,
+            BridgeTx::Evm(finalized, _) => *finalized,
+            BridgeTx::Sol(finalized, _) => *finalized,
+        }
+    }
+
+    pub fn same_with(&self, other: &BridgeTx) -> bool {
+        match (self, other) {
+            (BridgeTx::Icp(_, tx1), BridgeTx::Icp(_, tx2)) => tx1 == tx2,
+            (BridgeTx::Evm(_, tx1), BridgeTx::Evm(_, tx2)) => tx1 == tx2,
+            (BridgeTx::Sol(_, tx1), BridgeTx::Sol(_, tx2)) => tx1 == tx2,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, CandidType, Serialize, Deserialize)]
+pub struct BridgeLog {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    pub user: Principal,
+    #[serde(default)]
+    pub token: String, // token symbol
+    pub from: BridgeTarget,
+    pub to: BridgeTarget,
+    pub icp_amount: u128,
+    pub fee: u128,
+    pub from_tx: BridgeTx,
+    pub to_tx: Option<BridgeTx>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_addr: Option<String>,
+    // the EVM wallet that sent a deposit, refunds of the deposit go back to it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_addr: Option<String>,
+    // the log index of a deposit's Transfer event in its transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_log_index: Option<u64>,
+    pub created_at: u64,
+    pub finalized_at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    // the task failed with a non-retryable error and will not be processed again
+    #[serde(default)]
+    pub failed: bool,
+    // failures of the destination leg
+    #[serde(default)]
+    pub retries: u32,
+    // the amount refunded to the user on the source chain when the destination leg is given up
+    #[serde(default)]
+    pub refund: Option<u128>,
+    #[serde(default)]
+    pub refund_tx: Option<BridgeTx>,
+    // consecutive errors of the task
+    #[serde(default)]
+    pub errors: u32,
+    // the task is not processed again before this time, in milliseconds
+    #[serde(default)]
+    pub next_retry_at: u64,
+    // the destination leg of a large bridging is held until this time, in milliseconds
+    #[serde(default)]
+    pub release_at: u64,
+    #[serde(default)]
+    pub fee_detail: Option<FeeDetail>,
+    // withheld because not representable on the receiving chain, collected as fees
+    #[serde(default)]
+    pub dust: u128,
+    // the canister's outbound EVM transaction (to_tx, or refund_tx when refunding)
+    #[serde(default)]
+    pub evm_tx: Option<EvmOutboundTx>,
+    // the canister's outbound Solana transaction (to_tx, or refund_tx when refunding)
+    #[serde(default)]
+    pub svm_tx: Option<SvmOutboundTx>,
+}
+
+#[derive(Clone, CandidType, Serialize, Deserialize)]
+pub struct BridgeLogLocal {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    #[serde(rename = "u", alias = "user")]
+    pub user: Principal,
+    #[serde(default, rename = "tk", alias = "token")]
+    pub token: String,
+    #[serde(rename = "f", alias = "from")]
+    pub from: BridgeTarget,
+    #[serde(rename = "t", alias = "to")]
+    pub to: BridgeTarget,
+    #[serde(rename = "a", alias = "icp_amount")]
+    pub icp_amount: u128,
+    #[serde(default, rename = "e", alias = "fee")]
+    pub fee: u128,
+    #[serde(rename = "ft", alias = "from_tx")]
+    pub from_tx: BridgeTx,
+    #[serde(rename = "tt", alias = "to_tx")]
+    pub to_tx: Option<BridgeTx>,
+    #[serde(
+        rename = "ta",
+        alias = "to_addr",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_addr: Option<String>,
+    #[serde(
+        default,
+        rename = "fr",
+        alias = "from_addr",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_addr: Option<String>,
+    #[serde(
+        default,
+        rename = "fi",
+        alias = "from_log_index",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_log_index: Option<u64>,
+    #[serde(rename = "ca", alias = "created_at")]
+    pub created_at: u64,
+    #[serde(rename = "fa", alias = "finalized_at")]
+    pub finalized_at: u64,
+    #[serde(
+        rename = "er",
+        alias = "error",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub error: Option<String>,
+    #[serde(default, rename = "fl", alias = "failed")]
+    pub failed: bool,
+    #[serde(default, rename = "rt", alias = "retries")]
+    pub retries: u32,
+    #[serde(
+        default,
+        rename = "rf",
+        alias = "refund",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub refund: Option<u128>,
+    #[serde(
+        default,
+        rename = "rx",
+        alias = "refund_tx",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub refund_tx: Option<BridgeTx>,
+    #[serde(default, rename = "ec", alias = "errors")]
+    pub errors: u32,
+    #[serde(default, rename = "nr", alias = "next_retry_at")]
+    pub next_retry_at: u64,
+    #[serde(default, rename = "ra", alias = "release_at")]
+    pub release_at: u64,
+    #[serde(
+        default,
+        rename = "fd",
+        alias = "fee_detail",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fee_detail: Option<FeeDetail>,
+    #[serde(default, rename = "du", alias = "dust")]
+    pub dust: u128,
+    #[serde(
+        default,
+        rename = "et",
+        alias = "evm_tx",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub evm_tx: Option<EvmOutboundTx>,
+    #[serde(
+        default,
+        rename = "st",
+        alias = "svm_tx",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub svm_tx: Option<SvmOutboundTx>,
+}
+
+impl From<BridgeLogLocal> for BridgeLog {
+    fn from(log: BridgeLogLocal) -> Self {
+        Self {
+            id: log.id,
+            user: log.user,
+            token: log.token,
+            from: log.from,
+            to: log.to,
+            icp_amount: log.icp_amount,
+            fee: log.fee,
+            from_tx: log.from_tx,
+            to_tx: log.to_tx,
+            to_addr: log.to_addr,
+            from_addr: log.from_addr,
+            from_log_index: log.from_log_index,
+            created_at: log.created_at,
+            finalized_at: log.finalized_at,
+            error: log.error,
+            failed: log.failed,
+            retries: log.retries,
+            refund: log.refund,
+            refund_tx: log.refund_tx,
+            errors: log.errors,
+            next_retry_at: log.next_retry_at,
+            release_at: log.release_at,
+            fee_detail: log.fee_detail,
+            dust: log.dust,
+            evm_tx: log.evm_tx,
+            svm_tx: log.svm_tx,
+        }
+    }
+}
+
+impl From<BridgeLog> for BridgeLogLocal {
+    fn from(log: BridgeLog) -> Self {
+        Self {
+            id: log.id,
+            user: log.user,
+            token: log.token,
+            from: log.from,
+            to: log.to,
+            icp_amount: log.icp_amount,
+            fee: log.fee,
+            from_tx: log.from_tx,
+            to_tx: log.to_tx,
+            to_addr: log.to_addr,
+            from_addr: log.from_addr,
+            from_log_index: log.from_log_index,
+            created_at: log.created_at,
+            finalized_at: log.finalized_at,
+            error: log.error,
+            failed: log.failed,
+            retries: log.retries,
+            refund: log.refund,
+            refund_tx: log.refund_tx,
+            errors: log.errors,
+            next_retry_at: log.next_retry_at,
+            release_at: log.release_at,
+            fee_detail: log.fee_detail,
+            dust: log.dust,
+            evm_tx: log.evm_tx,
+            svm_tx: log.svm_tx,
+        }
+    }
+}
+
+/// The canister's outbound EVM transaction of a task, replaced with bumped fees while unmined.
+#[derive(Clone, Debug, Default, CandidType, Serialize, Deserialize)]
+pub struct EvmOutboundTx {
+    pub nonce: u64,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    pub sent_at: u64,
+    // hashes of the replaced transactions with the same nonce, any of them may land
+    pub replaced: Vec<ByteArray<32>>,
+    // the EIP-2718 encoded signed transaction, re-broadcast while its nonce is unused
+    #[serde(default)]
+    pub tx: ByteBuf,
+    // rounds that its nonce was found used at finalized depth without any of its transactions
+    // mined
+    #[serde(default)]
+    pub nonce_used_checks: u32,
+    // the replacements reached their limits, the transaction is re-broadcast as is until it is
+    // mined, flagged for the controllers
+    #[serde(default)]
+    pub capped: bool,
+}
+
+/// The canister's outbound Solana transaction of a task, kept signed to be re-broadcast as is.
+#[derive(Clone, Debug, Default, CandidType, Serialize, Deserialize)]
+pub struct SvmOutboundTx {
+    // the durable nonce account advanced by the transaction, None if it uses a recent blockhash
+    pub nonce_account: Option<String>,
+    // the durable nonce or the recent blockhash of the transaction
+    pub blockhash: ByteArray<32>,
+    // the last block height at which the recent blockhash is valid, 0 with a durable nonce
+    #[serde(default)]
+    pub last_valid_block_height: u64,
+    // the bincode-encoded signed transaction
+    pub tx: ByteBuf,
+    pub sent_at: u64,
+}
+
+/// The blockhash that a Solana transaction is signed with.
+pub enum SvmBlockhash {
+    Recent(Hash),
+    // the transaction advances the nonce account, signed with its nonce
+    DurableNonce(Pubkey, Hash),
+}
+
+/// The error of a bridging task, fatal errors are not retried.
+#[derive(Clone, Debug)]
+pub enum TaskError {
+    Retry(String),
+    Fatal(String),
+    // the transaction definitively failed without moving tokens, e.g. reverted or invalid recipient
+    Refund(String),
+}
+
+impl From<String> for TaskError {
+    fn from(err: String) -> Self {
+        TaskError::Retry(err)
+    }
+}
+
+impl BridgeLog {
+    pub fn is_finalized(&self) -> bool {
+        self.from_tx.is_finalized()
+            && (self.to_tx.as_ref().is_some_and(|tx| tx.is_finalized())
+                || self.refund_tx.as_ref().is_some_and(|tx| tx.is_finalized()))
+    }
+
+    // The nonce of the task's outbound transaction on an EVM chain that is not finalized yet.
+    pub fn pending_nonce(&self, chain: &str) -> Option<u64> {
+        let (target, tx) = if self.refund.is_some() {
+            (&self.from, &self.refund_tx)
+        } else {
+            (&self.to, &self.to_tx)
+        };
+        match (target, tx) {
+            (BridgeTarget::Evm(c), Some(BridgeTx::Evm(false, _))) if c == chain => {
+                self.evm_tx.as_ref().map(|tx| tx.nonce)
+            }
+            _ => None,
+        }
+    }
+
+    // The durable nonce account advanced by the task's outbound Solana transaction that is not
+    // finalized yet.
+    pub fn pending_nonce_account(&self) -> Option<&str> {
+        let (target, tx) = if self.refund.is_some() {
+            (&self.from, &self.refund_tx)
+        } else {
+            (&self.to, &self.to_tx)
+        };
+        match (target, tx) {
+            (BridgeTarget::Sol, Some(BridgeTx::Sol(false, _))) => self
+                .svm_tx
+                .as_ref()
+                .and_then(|tx| tx.nonce_account.as_deref()),
+            _ => None,
+        }
+    }
+
+    // The destination leg is held by the timelock, unless the task is being refunded.
+    pub fn is_held(&self, now_ms: u64) -> bool {
+        self.refund.is_none() && self.release_at > now_ms
+    }
+
+    pub fn same_with(&self, other: &BridgeLog) -> bool {
+        self.user == other.user
+            && self.token == other.token
+            && self.from == other.from
+            && self.to == other.to
+            && self.icp_amount == other.icp_amount
+            && self.from_tx.same_with(&other.from_tx)
+            // a transaction may carry several deposits, one per Transfer event
+            && self.from_log_index == other.from_log_index
+    }
+}
+
+impl Storable for BridgeLogLocal {
+    const BOUND: Bound = Bound::Unbounded;
+
+    fn into_bytes(self) -> Vec<u8> {
+        let mut buf = vec![];
+        into_writer(&self, &mut buf).expect("failed to encode BridgeLogLocal data");
+        buf
+    }
+
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        let mut buf = vec![];
+        into_writer(&self, &mut buf).expect("failed to encode BridgeLogLocal data");
+        Cow::Owned(buf)
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        from_reader(&bytes[..]).expect("failed to decode BridgeLogLocal data")
+    }
+}
+
+#[derive(Clone, CandidType, Default, Serialize, Deserialize)]
+pub struct UserLogs {
+    pub logs: BTreeSet<u64>,
+}
+
+impl Storable for UserLogs {
+    const BOUND: Bound = Bound::Unbounded;
+
+    fn into_bytes(self) -> Vec<u8> {
+        let mut buf = vec![];
+        into_writer(&self, &mut buf).expect("failed to encode UserLogs data");
+        buf
+    }
+
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        let mut buf = vec![];
+        into_writer(&self, &mut buf).expect("failed to encode UserLogs data");
+        Cow::Owned(buf)
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        from_reader(&bytes[..]).expect("failed to decode UserLogs data")
+    }
+}
+
+const STATE_MEMORY_ID: MemoryId = MemoryId::new(0);
+const USER_LOGS_MEMORY_ID: MemoryId = MemoryId::new(1);
+const BRIDGE_LOGS_INDEX_MEMORY_ID: MemoryId = MemoryId::new(2);
+const BRIDGE_LOGS_DATA_MEMORY_ID: MemoryId = MemoryId::new(3);
+const RATE_USAGE_MEMORY_ID: MemoryId = MemoryId::new(4);
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::new());
+    static HTTP_TREE: RefCell<HttpCertificationTree> = RefCell::new(HttpCertificationTree::default());
+    // the certification and JSON body of the `GET /reserves` response
+    static RESERVES_RESPONSE: RefCell<Option<(HttpCertification, Vec<u8>)>> = const { RefCell::new(None) };
+
+    static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
+        RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
+
+    static STATE_STORE: RefCell<StableCell<Vec<u8>, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with_borrow(|m| m.get(STATE_MEMORY_ID)),
+            Vec::new()
+        )
+    );
+
+    static USER_LOGS: RefCell<StableBTreeMap<Principal, UserLogs, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with_borrow(|m| m.get(USER_LOGS_MEMORY_ID)),
+        )
+    );
+
+    static BRIDGE_LOGS: RefCell<StableLog<BridgeLogLocal, Memory, Memory>> = RefCell::new(
+        StableLog::init(
+            MEMORY_MANAGER.with_borrow(|m| m.get(BRIDGE_LOGS_INDEX_MEMORY_ID)),
+            MEMORY_MANAGER.with_borrow(|m| m.get(BRIDGE_LOGS_DATA_MEMORY_ID)),
+        )
+    );
+
+    // rate limit scope => bridged amounts of the rolling window
+    static RATE_USAGE: RefCell<StableBTreeMap<String, RateUsage, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with_borrow(|m| m.get(RATE_USAGE_MEMORY_ID)),
+        )
+    );
+}
+
+pub mod state {
+    use std::str::FromStr;
+
+    use super::*;
+
+    use lazy_static::lazy_static;
+    use once_cell::sync::Lazy;
+
+    lazy_static! {
+        pub static ref DEFAULT_EXPR_PATH: HttpCertificationPath<'static> =
+            HttpCertificationPath::wildcard("");
+        pub static ref DEFAULT_CERTIFICATION: HttpCertification = HttpCertification::skip();
+        pub static ref DEFAULT_CEL_EXPR: String =
+            create_cel_expr(&DefaultCelBuilder::skip_certification());
+        pub static ref RESERVES_EXPR_PATH: HttpCertificationPath<'static> =
+            HttpCertificationPath::exact("/reserves");
+        pub static ref RESERVES_CEL_EXPR_DEF: DefaultResponseOnlyCelExpression<'static> =
+            DefaultCelBuilder::response_only_certification()
+                .with_response_certification(
+                    DefaultResponseCertification::certified_response_headers(vec!["content-type"])
+                )
+                .build();
+        pub static ref RESERVES_CEL_EXPR: String = RESERVES_CEL_EXPR_DEF.to_string();
+    }
+
+    pub static DEFAULT_CERT_ENTRY: Lazy<HttpCertificationTreeEntry> =
+        Lazy::new(|| HttpCertificationTreeEntry::new(&*DEFAULT_EXPR_PATH, *DEFAULT_CERTIFICATION));
+
+    pub async fn init_public_key() {
+        let key_name = STATE.with_borrow(|r| r.key_name.clone());
+        match ecdsa_public_key(key_name.clone(), vec![]).await {
+            Ok(root_pk) => {
+                STATE.with_borrow_mut(|s| {
+                    let self_pk =
+                        derive_public_key(&root_pk, vec![s.icp_address.as_slice().to_vec()])
+                            .expect("derive_public_key failed");
+                    s.ecdsa_public_key = root_pk;
+                    s.evm_address = self_pk.to_evm_adress().unwrap();
+                });
+            }
+            Err(err) => {
+                ic_cdk::api::debug_print(format!("failed to retrieve ECDSA public key: {err}"));
+            }
+        }
+
+        match schnorr_public_key(key_name, vec![], None).await {
+            Ok(root_pk) => {
+                STATE.with_borrow_mut(|s| {
+                    let self_pk = derive_schnorr_public_key(
+                        &root_pk,
+                        vec![s.icp_address.as_slice().to_vec()],
+                        None,
+                    )
+                    .expect("derive_schnorr_public_key failed");
+
+                    s.ed25519_public_key = root_pk;
+                    s.svm_address = self_pk.to_svm_pubkey().unwrap();
+                });
+            }
+            Err(err) => {
+                ic_cdk::api::debug_print(format!("failed to retrieve Schnorr public key: {err}"));
+            }
+        }
+    }
+
+    pub async fn try_init_ed25519_public_key() {
+        let (key_name, has_ed25519_public_key) = STATE.with_borrow(|r| {
+            (
+                r.key_name.clone(),
+                !r.ed25519_public_key.public_key.is_empty(),
+            )
+        });
+
+        if has_ed25519_public_key {
+            return;
+        }
+
+        match schnorr_public_key(key_name, vec![], None).await {
+            Ok(root_pk) => {
+                STATE.with_borrow_mut(|s| {
+                    let self_pk = derive_schnorr_public_key(
+                        &root_pk,
+                        vec![s.icp_address.as_slice().to_vec()],
+                        None,
+                    )
+                    .expect("derive_schnorr_public_key failed");
+
+                    s.ed25519_public_key = root_pk;
+                    s.svm_address = self_pk.to_svm_pubkey().unwrap();
+                });
+            }
+            Err(err) => {
+                ic_cdk::api::debug_print(format!("failed to retrieve Schnorr public key: {err}"));
+            }
+        }
+    }
+
+    pub fn with<R>(f: impl FnOnce(&State) -> R) -> R {
+        STATE.with_borrow(f)
+    }
+
+    pub fn with_mut<R>(f: impl FnOnce(&mut State) -> R) -> R {
+        STATE.with_borrow_mut(f)
+    }
+
+    pub fn http_tree_with<R>(f: impl FnOnce(&HttpCertificationTree) -> R) -> R {
+        HTTP_TREE.with(|r| f(&r.borrow()))
+    }
+
+    pub fn init_http_certified_data() {
+        HTTP_TREE.with(|r| {
+            let mut tree = r.borrow_mut();
+            tree.insert(&DEFAULT_CERT_ENTRY);
+            ic_cdk::api::certified_data_set(tree.root_hash())
+        });
+        certify_reserves();
+    }
+
+    // The headers of the `GET /reserves` response, all of them are certified.
+    pub fn reserves_headers() -> Vec<(String, String)> {
+        vec![
+            ("content-type".to_string(), "application/json".to_string()),
+            (
+                "ic-certificateexpression".to_string(),
+                RESERVES_CEL_EXPR.clone(),
+            ),
+        ]
+    }
+
+    pub fn reserves_response() -> Option<(HttpCertificationTreeEntry<'static>, Vec<u8>)> {
+        RESERVES_RESPONSE.with_borrow(|r| {
+            r.as_ref().map(|(certification, body)| {
+                (
+                    HttpCertificationTreeEntry::new(&*RESERVES_EXPR_PATH, *certification),
+                    body.clone(),
+                )
+            })
+        })
+    }
+
+    // Certifies the `GET /reserves` response with the latest reconciliations.
+    pub fn certify_reserves() {
+        let reserves: BTreeMap<String, TokenReserves> = STATE.with_borrow(|s| {
+            s.reserves
+                .iter()
+                .map(|(token, r)| (token.clone(), TokenReserves::from(r)))
+                .collect()
+        });
+        let body = serde_json::to_vec(&reserves).expect("failed to serialize reserves");
+        let response = HttpResponse::ok(body.as_slice(), reserves_headers()).build();
+        let certification =
+            HttpCertification::response_only(&RESERVES_CEL_EXPR_DEF, &response, None)
+                .expect("failed to certify reserves");
+
+        HTTP_TREE.with(|r| {
+            let mut tree = r.borrow_mut();
+            RESERVES_RESPONSE.with_borrow_mut(|res| {
+                if let Some((prev, _)) = res.take() {
+                    tree.delete(&HttpCertificationTreeEntry::new(&*RESERVES_EXPR_PATH, prev));
+                }
+                tree.insert(&HttpCertificationTreeEntry::new(
+                    &*RESERVES_EXPR_PATH,
+                    certification,
+                ));
+                *res = Some((certification, body));
+            });
+            ic_cdk::api::certified_data_set(tree.root_hash())
+        });
+    }
+
+    pub fn load() {
+        STATE_STORE.with_borrow(|r| {
+            STATE.with_borrow_mut(|h| {
+                let bytes = r.get();
+                if bytes.is_empty() {
+                    return;
+                }
+                let mut v: State =
+                    from_reader(&bytes[..]).expect("failed to decode STATE_STORE data");
+                if v.tokens.is_empty() {
+                    let legacy: LegacyTokenState =
+                        from_reader(&bytes[..]).expect("failed to decode legacy token data");
+                    v.migrate_legacy_token(legacy);
+                }
+                *h = v;
+            });
+        });
+    }
+
+    pub fn save() {
+        STATE.with_borrow(|h| {
+            STATE_STORE.with_borrow_mut(|r| {
+                let mut buf = vec![];
+                into_writer(h, &mut buf).expect("failed to encode STATE_STORE data");
+                r.set(buf);
+            });
+        });
+    }
+
+    pub fn info() -> StateInfo {
+        let mut info = STATE.with_borrow(|s| StateInfo::from(s));
+        info.total_bridge_count = BRIDGE_LOGS.with_borrow(|r| r.len());
+        info
+    }
+
+    pub fn evm_address(user: &Principal) -> Address {
+        STATE.with_borrow(|s| {
+            let pk = derive_public_key(&s.ecdsa_public_key, vec![user.as_slice().to_vec()])
+                .expect("derive_public_key failed");
+            pk.to_evm_adress().unwrap()
+        })
+    }
+
+    pub fn evm_client(chain: &str) -> EvmClient<RpcOutcall> {
+        STATE.with_borrow(|s| {
+            let outcall = match s.evm_rpc_canisters.get(chain) {
+                Some(canister) => RpcOutcall::EvmRpc(EvmRpcOutcall::new(
+                    *canister,
+                    s.evm_chain_id(chain).unwrap_or_default(),
+                )),
+                None => RpcOutcall::Http(DefaultHttpOutcall::new(s.icp_address)),
+            };
+            match s.evm_providers.get(chain) {
+                Some((max_confirmations, providers)) => {
+                    EvmClient::new(providers.clone(), *max_confirmations, None, outcall)
+                        .with_quorum(s.rpc_quorums.get(chain).cloned().unwrap_or(1) as usize)
+                }
+                None => EvmClient::new(vec![], 1, None, outcall),
+            }
+        })
+    }
+
+    pub async fn evm_sign(user: &Principal, message_hash: Vec<u8>) -> Result<Vec<u8>, String> {
+        let key_name = STATE.with_borrow(|s| {
+            if !s.sub_bridges.contains(user) {
+                Err("user is not authorized to sign".to_string())
+            } else {
+                Ok(s.key_name.clone())
+            }
+        })?;
+
+        let cycles = cost_sign_with_ecdsa(key_name.clone())?;
+        let received = ic_cdk::api::msg_cycles_accept(cycles);
+        if received < cycles {
+            return Err(format!(
+                "insufficient cycles: required {}, accepted {}",
+                cycles, received
+            ));
+        }
+
+        let derivation_path = vec![user.as_slice().to_vec()];
+        sign_with_ecdsa(key_name, derivation_path, message_hash).await
+    }
+
+    pub fn svm_address(user: &Principal) -> Pubkey {
+        STATE.with_borrow(|s| {
+            let pk = derive_schnorr_public_key(
+                &s.ed25519_public_key,
+                vec![user.as_slice().to_vec()],
+                None,
+            )
+            .expect("derive_schnorr_public_key failed");
+            pk.to_svm_pubkey().unwrap()
+        })
+    }
+
+    pub fn svm_client() -> SvmClient<DefaultHttpOutcall> {
+        STATE.with_borrow(|s| {
+            SvmClient::new(
+                s.svm_providers.clone(),
+                None,
+                None,
+                DefaultHttpOutcall::new(s.icp_address),
+            )
+            .with_quorum(s.rpc_quorums.get("SOL").cloned().unwrap_or(1) as usize)
+        })
+    }
+
+    pub async fn bridge(
+        token: String,
+        from_chain: String,
+        to_chain: String,
+        icp_amount: u128,
+        to_addr: Option<String>,
+        user: Principal,
+        now_ms: u64,
+    ) -> Result<BridgeTx, String> {
+        ensure_evm_gas(&to_chain, now_ms).await;
+        let BridgePlan {
+            from,
+            to,
+            ledger: token_ledger,
+            fee_detail,
+            dust,
+            rate_limit,
+            release_at,
+        } = STATE.with_borrow(|s| {
+            s.check_route(&from_chain, &to_chain)?;
+            check_pending_from_evm(s, &from_chain, &user)?;
+            check_bridge(
+                s,
+                &token,
+                &from_chain,
+                &to_chain,
+                icp_amount,
+                to_addr.as_deref(),
+                now_ms,
+            )
+        })?;
+
+        // reserve the allowance before pulling funds, released if it fails
+        consume_rate_limit(
+            rate_limit.as_ref(),
+            &token,
+            &user,
+            from.chain_name(),
+            to.chain_name(),
+            icp_amount,
+            true,
+            now_ms,
+        )?;
+        let from_tx = match &from {
+            BridgeTarget::Icp => from_icp(token_ledger, user, icp_amount).await,
+            BridgeTarget::Sol => from_svm(&token, user, icp_amount, now_ms).await,
+            BridgeTarget::Evm(chain) => from_evm(&token, chain, user, icp_amount, now_ms).await,
+        };
+        let from_tx = match from_tx {
+            Ok(tx) => tx,
+            Err(err) => {
+                release_rate_limit(
+                    rate_limit.as_ref(),
+                    &token,
+                    &user,
+                    from.chain_name(),
+                    to.chain_name(),
+                    icp_amount,
+                );
+                return Err(err);
+            }
+        };
+
+        let delay = if from == BridgeTarget::Icp { 0 } else { 5 };
+        let round = STATE.with_borrow_mut(|s| {
+            s.pending.push_back(BridgeLog {
+                id: None,
+                user,
+                token,
+                from,
+                to,
+                icp_amount,
+                fee: fee_detail.total(),
+                from_tx: from_tx.clone(),
+                to_tx: None,
+                to_addr,
+                from_addr: None,
+                from_log_index: None,
+                created_at: now_ms,
+                finalized_at: 0,
+                error: None,
+                failed: false,
+                retries: 0,
+                refund: None,
+                refund_tx: None,
+                errors: 0,
+                next_retry_at: 0,
+                release_at,
+                fee_detail: Some(fee_detail),
+                dust,
+                evm_tx: None,
+                svm_tx: None,
+            });
+            s.finalize_bridging_round.0
+        });
+
+        ic_cdk_timers::set_timer(Duration::from_secs(delay), finalize_bridging(round));
+
+        Ok(from_tx)
+    }
+
+    // Validates a bridging and computes its fee, refuses it while the token is paused or the
+    // destination chain is low on gas.
+    #[allow(clippy::too_many_arguments)]
+    fn check_bridge(
+        s: &State,
+        token: &str,
+        from_chain: &str,
+        to_chain: &str,
+        icp_amount: u128,
+        to_addr: Option<&str>,
+        now_ms: u64,
+    ) -> Result<BridgePlan, String> {
+        let t = s.token(token)?;
+        if t.paused {
+            return Err(format!("token {token} is paused"));
+        }
+        s.check_gas_balance(to_chain)?;
+        plan_bridge(s, t, from_chain, to_chain, icp_amount, to_addr, now_ms)
+    }
+
+    // Validates a bridging and plans its fee and dust, regardless of whether the token,
+    // chains and route are open now.
+    fn plan_bridge(
+        s: &State,
+        t: &Token,
+        from_chain: &str,
+        to_chain: &str,
+        icp_amount: u128,
+        to_addr: Option<&str>,
+        now_ms: u64,
+    ) -> Result<BridgePlan, String> {
+        if from_chain == to_chain {
+            return Err("from_chain and to_chain cannot be the same".to_string());
+        }
+
+        let token = &t.symbol;
+        if icp_amount < t.min_threshold_to_bridge {
+            return Err(format!(
+                "amount {} is below the minimum threshold to bridge {}",
+                icp_amount, t.min_threshold_to_bridge
+            ));
+        }
+        let from = if from_chain == "ICP" {
+            BridgeTarget::Icp
+        } else if from_chain == "SOL" {
+            if t.svm_contract.is_none() {
+                return Err(format!("SOL is not supported for token {token}"));
+            }
+            BridgeTarget::Sol
+        } else {
+            if !t.evm_contracts.contains_key(from_chain) {
+                return Err(format!(
+                    "from_chain {} not found or not supported for token {}",
+                    from_chain, token
+                ));
+            }
+            BridgeTarget::Evm(from_chain.to_string())
+        };
+
+        let to = if to_chain == "ICP" {
+            if let Some(to_addr) = to_addr {
+                let _ = Principal::from_text(to_addr)
+                    .map_err(|_| format!("invalid ICP address {to_addr}"))?;
+            }
+            BridgeTarget::Icp
+        } else if to_chain == "SOL" {
+            if t.svm_contract.is_none() {
+                return Err(format!("SOL is not supported for token {token}"));
+            }
+            if let Some(to_addr) = to_addr {
+                let _ = Pubkey::from_str(to_addr)
+                    .map_err(|_| format!("invalid SOL address: {}", to_addr))?;
+            }
+            BridgeTarget::Sol
+        } else {
+            if !t.evm_contracts.contains_key(to_chain) {
+                return Err(format!(
+                    "to_chain {} not found or not supported for token {}",
+                    to_chain, token
+                ));
+            }
+            if let Some(to_addr) = to_addr {
+                let _ = to_addr
+                    .parse::<Address>()
+                    .map_err(|_| format!("invalid EVM address: {}", to_addr))?;
+            }
+
+            BridgeTarget::Evm(to_chain.to_string())
+        };
+
+        let fee_detail = bridge_fee(s, t, &to, icp_amount)?;
+        if fee_detail.total() >= icp_amount {
+            return Err(format!(
+                "amount {} does not cover the bridge fee {}",
+                icp_amount,
+                fee_detail.total()
+            ));
+        }
+        let (_, src_dust) = t.split_dust(&from, icp_amount)?;
+        if src_dust > 0 {
+            return Err(format!(
+                "amount {} is not exactly representable on {}, remove the remainder {}",
+                icp_amount, from_chain, src_dust
+            ));
+        }
+        let (_, dust) = t.split_dust(&to, icp_amount - fee_detail.total())?;
+
+        Ok(BridgePlan {
+            from,
+            to,
+            ledger: t.ledger,
+            fee_detail,
+            dust,
+            rate_limit: t.rate_limit.clone(),
+            release_at: t.release_at(icp_amount, now_ms),
+        })
+    }
+
+    // Transfers from the user's derived EVM address are signed with its current nonce,
+    // one of them is bridged at a time.
+    fn check_pending_from_evm(s: &State, from_chain: &str, user: &Principal) -> Result<(), String> {
+        for log in s.pending.iter() {
+            if log.user == *user
+                && log.from.chain_name() == from_chain
+                && matches!(log.from_tx, BridgeTx::Evm(false, _))
+            {
+                return Err(format!(
+                    "there is already a pending bridging task from {:?} for user {:?}",
+                    log.from, log.user
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // The token held by the canister on a lock-mode destination chain as of the latest reserve
+    // reconciliation, minus the payouts that the pending tasks to the chain have not finalized.
+    fn destination_liquidity(s: &State, t: &Token, to: &BridgeTarget) -> Option<u128> {
+        let chain = to.chain_name();
+        if *to != BridgeTarget::Icp && t.is_mint_burn(chain) {
+            return None;
+        }
+        let report = s.reserves.get(&t.symbol).filter(|r| r.checked_at > 0)?;
+        let balance = match to {
+            BridgeTarget::Icp => report.icp_balance.saturating_sub(report.unwithdrawn_fees),
+            _ => report.chains.get(chain)?.0,
+        };
+        let owed: u128 = s
+            .pending
+            .iter()
+            .filter(|log| {
+                log.token == t.symbol
+                    && log.to == *to
+                    && log.refund.is_none()
+                    && !log.to_tx.as_ref().is_some_and(|tx| tx.is_finalized())
+            })
+            .map(|log| log.icp_amount.saturating_sub(log.fee + log.dust))
+            .sum();
+        Some(balance.saturating_sub(owed))
+    }
+
+    /// Quotes a bridging with the same validation as `bridge`. The conditions that make `bridge`
+    /// refuse the bridging now are reported in `blocked` instead of as an error.
+    pub fn quote_bridge(
+        token: &str,
+        from_chain: &str,
+        to_chain: &str,
+        icp_amount: u128,
+        to_addr: Option<&str>,
+        user: &Principal,
+        now_ms: u64,
+    ) -> Result<BridgeQuote, String> {
+        let (mut blocked, receive_amount, min_threshold_to_bridge, finality_ms, liquidity, plan) =
+            STATE.with_borrow(|s| {
+                let t = s.token(token)?;
+                let plan = plan_bridge(s, t, from_chain, to_chain, icp_amount, to_addr, now_ms)?;
+                let amount = icp_amount - plan.fee_detail.total() - plan.dust;
+                let receive_amount =
+                    convert_amount(amount, t.decimals, t.chain_decimals(&plan.to))?;
+
+                let mut blocked = Vec::new();
+                if t.paused {
+                    blocked.push(format!("token {token} is paused"));
+                }
+                if let Err(err) = s.check_route(from_chain, to_chain) {
+                    blocked.push(err);
+                }
+                if let Err(err) = s.check_gas_balance(to_chain) {
+                    blocked.push(err);
+                }
+                if let Err(err) = check_pending_from_evm(s, from_chain, user) {
+                    blocked.push(err);
+                }
+                let liquidity = destination_liquidity(s, t, &plan.to);
+                if let Some(liquidity) = liquidity
+                    && liquidity < amount
+                {
+                    blocked.push(format!(
+                        "insufficient liquidity on {to_chain}: {liquidity} available, {amount} needed"
+                    ));
+                }
+                Ok::<_, String>((
+                    blocked,
+                    receive_amount,
+                    t.min_threshold_to_bridge,
+                    s.finality_ms
+                        .get(&format!("{from_chain}->{to_chain}"))
+                        .cloned(),
+                    liquidity,
+                    plan,
+                ))
+            })?;
+
+        let allowance = bridge_allowance(token, user, from_chain, to_chain, now_ms)?;
+        if let Some(allowance) = allowance
+            && allowance < icp_amount
+        {
+            blocked.push(format!(
+                "amount {icp_amount} exceeds the rate limit allowance {allowance}"
+            ));
+        }
+        let hold_ms = plan.release_at.saturating_sub(now_ms);
+        Ok(BridgeQuote {
+            fee: plan.fee_detail,
+            receive_amount,
+            dust: plan.dust,
+            min_threshold_to_bridge,
+            allowance,
+            liquidity,
+            blocked,
+            release_at: plan.release_at,
+            estimated_secs: finality_ms.map(|ms| (hold_ms + ms) / 1000),
+        })
+    }
+
+    pub fn evm_deposit_message(sender: &Address, user: &Principal) -> String {
+        let bridge = STATE.with_borrow(|s| s.icp_address);
+        format!("One Bridge {bridge}: link EVM address {sender} to ICP principal {user}")
+    }
+
+    pub async fn register_evm_deposit(
+        user: Principal,
+        sender: Address,
+        signature: &Signature,
+        to_chain: String,
+        to_addr: Option<String>,
+        now_ms: u64,
+    ) -> Result<(), String> {
+        let message = evm_deposit_message(&sender, &user);
+        let signer = signature
+            .recover_address_from_msg(message)
+            .map_err(|err| format!("failed to recover signer: {err}"))?;
+        if signer != sender {
+            return Err(format!("signature signer {signer} does not match {sender}"));
+        }
+        // the derived address's transfers are bridged by `from_evm` already
+        if sender == evm_address(&user) {
+            return Err("cannot register the derived EVM address of the user".to_string());
+        }
+
+        STATE.with_borrow(|s| {
+            match to_chain.as_str() {
+                "ICP" => {
+                    if let Some(to_addr) = &to_addr {
+                        let _ = Principal::from_text(to_addr)
+                            .map_err(|_| format!("invalid ICP address {to_addr}"))?;
+                    }
+                }
+                "SOL" => {
+                    if let Some(to_addr) = &to_addr {
+                        let _ = Pubkey::from_str(to_addr)
+                            .map_err(|_| format!("invalid SOL address: {}", to_addr))?;
+                    }
+                }
+                chain => {
+                    if s.evm_chain_id(chain).is_none() {
+                        return Err(format!("to_chain {chain} not found"));
+                    }
+                    if let Some(to_addr) = &to_addr {
+                        let _ = to_addr
+                            .parse::<Address>()
+                            .map_err(|_| format!("invalid EVM address: {}", to_addr))?;
+                    }
+                }
+            }
+            Ok::<_, String>(())
+        })?;
+
+        // deposits are scanned from the blocks at which the intent is created
+        init_evm_deposit_cursors(now_ms).await;
+
+        let round = STATE.with_borrow_mut(|s| {
+            let intent = EvmDepositIntent {
+                user,
+                to_chain,
+                to_addr,
+                created_at: now_ms,
+            };
+            s.evm_deposit_intents.insert(sender, intent.clone());
+
+            let sender = sender.to_string();
+            let (matched, unmatched): (Vec<_>, Vec<_>) = s
+                .evm_unmatched_deposits
+                .drain(..)
+                .partition(|d| d.sender == sender);
+            s.evm_unmatched_deposits = unmatched;
+            let mut credited = false;
+            for deposit in matched {
+                match credit_evm_deposit(s, &deposit, &intent, now_ms) {
+                    Ok(()) => credited = true,
+                    Err(err) => {
+                        ic_cdk::api::debug_print(format!(
+                            "{}: failed to credit deposit {:?}: {err}",
+                            deposit.chain, deposit.tx_hash
+                        ));
+                        // kept to be credited again on the next registration
+                        s.evm_unmatched_deposits.push(deposit);
+                    }
+                }
+            }
+            credited.then_some(s.finalize_bridging_round.0)
+        });
+
+        if let Some(round) = round {
+            ic_cdk_timers::set_timer(Duration::from_secs(0), finalize_bridging(round));
+        }
+        Ok(())
+    }
+
+    // Starts the deposit scanning of the chains that were never scanned at their latest
+    // confirmed block, so that the deposits sent after an intent is created are not missed.
+    async fn init_evm_deposit_cursors(now_ms: u64) {
+        let chains: BTreeSet<String> = STATE.with_borrow(|s| {
+            s.tokens
+                .values()
+                .flat_map(|t| t.evm_contracts.keys())
+                .filter(|chain| !s.evm_deposit_cursors.contains_key(*chain))
+                .cloned()
+                .collect()
+        });
+
+        let rt = futures::future::join_all(chains.iter().map(|chain| async move {
+            let client = evm_client(chain);
+            let latest = client.block_number(now_ms).await?;
+            Ok::<_, String>(latest.saturating_sub(client.max_confirmations))
+        }))
+        .await;
+
+        STATE.with_borrow_mut(|s| {
+            for (chain, r) in chains.into_iter().zip(rt) {
+                match r {
+                    Ok(confirmed) => {
+                        s.evm_deposit_cursors.entry(chain).or_insert(confirmed);
+                    }
+                    Err(err) => {
+                        ic_cdk::api::debug_print(format!(
+                            "{chain}: failed to init the deposit cursor: {err}"
+                        ));
+                    }
+                }
+            }
+        });
+    }
+
+    // Pushes a bridging task for a deposit. The deposit is bridged to ICP if the token
+    // is not supported on the intent's target chain. A deposit that can not be bridged,
+    // because of a paused route, a rate limit or an amount not covering the fee, has
+    // arrived already and is refunded to its sender.
+    fn credit_evm_deposit(
+        s: &mut State,
+        deposit: &EvmDeposit,
+        intent: &EvmDepositIntent,
+        now_ms: u64,
+    ) -> Result<(), String> {
+        let from_tx = BridgeTx::Evm(true, deposit.tx_hash);
+        if s.pending.iter().chain(s.dead_letters.iter()).any(|log| {
+            log.token == deposit.token
+                && log.from_tx == from_tx
+                && log.from_log_index.unwrap_or_default() == deposit.log_index
+        }) {
+            return Ok(());
+        }
+
+        let t = s.token(&deposit.token)?;
+        let (_, decimals, _) = t.evm_contracts.get(&deposit.chain).ok_or_else(|| {
+            format!(
+                "{}: token {} contract not found",
+                deposit.chain, deposit.token
+            )
+        })?;
+        let icp_amount = convert_amount(deposit.amount, *decimals, t.decimals)?;
+        let deposit_dust = deposit.amount - convert_amount(icp_amount, t.decimals, *decimals)?;
+        let (to_chain, to_addr) = match intent.to_chain.as_str() {
+            "ICP" => ("ICP", intent.to_addr.clone()),
+            "SOL" if t.svm_contract.is_some() => ("SOL", intent.to_addr.clone()),
+            chain if chain != deposit.chain && t.evm_contracts.contains_key(chain) => (
+                chain,
+                // default to the sender's own wallet
+                Some(
+                    intent
+                        .to_addr
+                        .clone()
+                        .unwrap_or_else(|| deposit.sender.clone()),
+                ),
+            ),
+            _ => ("ICP", None),
+        };
+
+        let mut log = BridgeLog {
+            id: None,
+            user: intent.user,
+            token: deposit.token.clone(),
+            from: BridgeTarget::Evm(deposit.chain.clone()),
+            to: BridgeTarget::Icp,
+            icp_amount,
+            fee: 0,
+            from_tx,
+            to_tx: None,
+            to_addr: None,
+            from_addr: Some(deposit.sender.clone()),
+            from_log_index: Some(deposit.log_index),
+            created_at: now_ms,
+            finalized_at: 0,
+            error: None,
+            failed: false,
+            retries: 0,
+            refund: None,
+            refund_tx: None,
+            errors: 0,
+            next_retry_at: 0,
+            release_at: 0,
+            fee_detail: None,
+            dust: 0,
+            evm_tx: None,
+            svm_tx: None,
+        };
+        let plan = s
+            .check_route(&deposit.chain, to_chain)
+            .and_then(|_| {
+                check_bridge(
+                    s,
+                    &deposit.token,
+                    &deposit.chain,
+                    to_chain,
+                    icp_amount,
+                    to_addr.as_deref(),
+                    now_ms,
+                )
+            })
+            .and_then(|plan| {
+                consume_rate_limit(
+                    plan.rate_limit.as_ref(),
+                    &deposit.token,
+                    &intent.user,
+                    &deposit.chain,
+                    to_chain,
+                    icp_amount,
+                    true,
+                    now_ms,
+                )?;
+                Ok(plan)
+            });
+        match plan {
+            Ok(plan) => {
+                log.to = plan.to;
+                log.to_addr = to_addr;
+                log.fee = plan.fee_detail.total();
+                log.fee_detail = Some(plan.fee_detail);
+                log.dust = plan.dust;
+                log.release_at = plan.release_at;
+            }
+            Err(err) => {
+                ic_cdk::api::debug_print(format!(
+                    "{}: refunding deposit {:?}: {err}",
+                    deposit.chain, deposit.tx_hash
+                ));
+                if icp_amount > t.refund_fee {
+                    set_refund(&mut log, t);
+                } else {
+                    // too small to be refunded, left to the controllers
+                    log.failed = true;
+                }
+                log.error = Some(err);
+            }
+        }
+
+        if deposit_dust > 0 {
+            let t = s.token_mut(&deposit.token)?;
+            let d = t.deposit_dust.entry(deposit.chain.clone()).or_default();
+            *d = d.saturating_add(deposit_dust);
+        }
+        if log.failed {
+            s.dead_letters.push(log);
+        } else {
+            s.pending.push_back(log);
+        }
+        Ok(())
+    }
+
+    // The dead letter of a deposit that failed to be credited, kept for the controllers.
+    fn failed_evm_deposit_log(
+        deposit: &EvmDeposit,
+        intent: &EvmDepositIntent,
+        err: String,
+        now_ms: u64,
+    ) -> BridgeLog {
+        BridgeLog {
+            id: None,
+            user: intent.user,
+            token: deposit.token.clone(),
+            from: BridgeTarget::Evm(deposit.chain.clone()),
+            to: BridgeTarget::Icp,
+            icp_amount: 0,
+            fee: 0,
+            from_tx: BridgeTx::Evm(true, deposit.tx_hash),
+            to_tx: None,
+            to_addr: None,
+            from_addr: Some(deposit.sender.clone()),
+            from_log_index: Some(deposit.log_index),
+            created_at: now_ms,
+            finalized_at: 0,
+            error: Some(format!(
+                "failed to credit the deposit of {} {} token units: {err}",
+                deposit.amount, deposit.token
+            )),
+            failed: true,
+            retries: 0,
+            refund: None,
+            refund_tx: None,
+            errors: 0,
+            next_retry_at: 0,
+            release_at: 0,
+            fee_detail: None,
+            dust: 0,
+            evm_tx: None,
+            svm_tx: None,
+        }
+    }
+
+    /// Computes the fee of bridging the amount to the destination chain. The gas surcharge
+    /// can not be computed while the gas price of the destination EVM chain is unknown.
+    pub fn bridge_fee(
+        s: &State,
+        t: &Token,
+        to: &BridgeTarget,
+        icp_amount: u128,
+    ) -> Result<FeeDetail, String> {
+        let Some(fs) = t.fee_schedules.get(to.chain_name()) else {
+            return Ok(FeeDetail {
+                flat: t.bridge_fee,
+                percentage: 0,
+                base: t.bridge_fee,
+                gas: 0,
+            });
+        };
+
+        let percentage = icp_amount.saturating_mul(fs.bps as u128) / 10_000;
+        let mut base = fs.flat.saturating_add(percentage).max(fs.min);
+        if fs.max > 0 {
+            base = base.min(fs.max);
+        }
+        let gas = if fs.native_price == 0 {
+            0
+        } else {
+            match to {
+                BridgeTarget::Icp => 0,
+                BridgeTarget::Evm(chain) => {
+                    let (_, gas_price, max_priority_fee_per_gas) = s
+                        .evm_latest_gas
+                        .get(chain)
+                        .cloned()
+                        .filter(|(_, gas_price, _)| *gas_price > 0)
+                        .ok_or_else(|| format!("the gas price of {chain} is unknown yet"))?;
+                    let config = s.evm_gas_limits.get(chain).cloned().unwrap_or_default();
+                    let call = if t.is_mint_burn(chain) {
+                        TokenCall::Mint(())
+                    } else {
+                        TokenCall::Transfer(())
+                    };
+                    // the cap bounds the gas limit until a transaction has been estimated
+                    let estimated = s
+                        .evm_gas_estimates
+                        .get(&evm_gas_estimate_key(chain, &t.symbol, &call))
+                        .cloned()
+                        .unwrap_or(config.cap);
+                    let cost = (config.gas_limit(estimated)? as u128)
+                        .saturating_mul(gas_price.saturating_add(max_priority_fee_per_gas));
+                    cost.saturating_mul(fs.native_price) / 10u128.pow(18)
+                }
+                BridgeTarget::Sol => {
+                    let priority_fee = (s.svm_priority_fee.compute_unit_limit as u128)
+                        .saturating_mul(s.svm_latest_priority_fee.1 as u128)
+                        / 1_000_000;
+                    SVM_TX_FEE_LAMPORTS
+                        .saturating_add(priority_fee)
+                        .saturating_mul(fs.native_price)
+                        / 10u128.pow(9)
+                }
+            }
+        };
+
+        Ok(FeeDetail {
+            flat: fs.flat,
+            percentage,
+            base,
+            gas,
+        })
+    }
+
+    /// Returns the remaining amount that the user can bridge on the route, None if unlimited.
+    pub fn bridge_allowance(
+        token: &str,
+        user: &Principal,
+        from_chain: &str,
+        to_chain: &str,
+        now_ms: u64,
+    ) -> Result<Option<u128>, String> {
+        let Some(limit) = STATE.with_borrow(|s| s.token(token).map(|t| t.rate_limit.clone()))?
+        else {
+            return Ok(None);
+        };
+        let window_ms = limit.window_secs * 1000;
+        let allowance = RATE_USAGE.with_borrow(|r| {
+            limit
+                .scopes(token, user, from_chain, to_chain)
+                .into_iter()
+                .map(|(key, max)| {
+                    let used = r
+                        .get(&key)
+                        .map(|mut u| u.used(window_ms, now_ms))
+                        .unwrap_or_default();
+                    max.saturating_sub(used)
+                })
+                .min()
+        });
+        Ok(allowance)
+    }
+
+    // Records the bridged amount in the rolling windows of the token's rate limit.
+    // With `enforce`, nothing is recorded if any limit would be exceeded.
+    #[allow(clippy::too_many_arguments)]
+    fn consume_rate_limit(
+        limit: Option<&RateLimit>,
+        token: &str,
+        user: &Principal,
+        from_chain: &str,
+        to_chain: &str,
+        amount: u128,
+        enforce: bool,
+        now_ms: u64,
+    ) -> Result<(), String> {
+        let Some(limit) = limit else {
+            return Ok(());
+        };
+        let window_ms = limit.window_secs * 1000;
+        RATE_USAGE.with_borrow_mut(|r| {
+            let mut usages = Vec::new();
+            for (key, max) in limit.scopes(token, user, from_chain, to_chain) {
+                let mut usage = r.get(&key).unwrap_or_default();
+                let used = usage.used(window_ms, now_ms);
+                if enforce && used.saturating_add(amount) > max {
+                    return Err(format!(
+                        "amount {amount} exceeds the rate limit of {key}, remaining allowance {}",
+                        max.saturating_sub(used)
+                    ));
+                }
+                usage.add(window_ms, amount, now_ms);
+                usages.push((key, usage));
+            }
+            for (key, usage) in usages {
+                r.insert(key, usage);
+            }
+            Ok(())
+        })
+    }
+
+    fn release_rate_limit(
+        limit: Option<&RateLimit>,
+        token: &str,
+        user: &Principal,
+        from_chain: &str,
+        to_chain: &str,
+        amount: u128,
+    ) {
+        let Some(limit) = limit else {
+            return;
+        };
+        RATE_USAGE.with_borrow_mut(|r| {
+            for (key, _) in limit.scopes(token, user, from_chain, to_chain) {
+                if let Some(mut usage) = r.get(&key) {
+                    usage.sub(amount);
+                    r.insert(key, usage);
+                }
+            }
+        });
+    }
+
+    pub async fn scan_evm_deposits() {
+        let chains = STATE.with_borrow_mut(|s| {
+            if s.evm_deposit_scanning || s.evm_deposit_intents.is_empty() {
+                return None;
+            }
+            s.evm_deposit_scanning = true;
+            let chains: BTreeSet<String> = s
+                .tokens
+                .values()
+                .flat_map(|t| t.evm_contracts.keys().cloned())
+                .collect();
+            Some(chains)
+        });
+
+        if let Some(chains) = chains {
+            let now_ms = ic_cdk::api::time() / 1_000_000;
+            // deposits may be bridged to any of the chains
+            futures::future::join_all(chains.iter().map(|chain| ensure_evm_gas(chain, now_ms)))
+                .await;
+            let rt = futures::future::join_all(
+                chains
+                    .iter()
+                    .map(|chain| scan_evm_chain_deposits(chain, now_ms)),
+            )
+            .await;
+
+            let mut credited = false;
+            for r in rt {
+                match r {
+                    Ok(v) => credited = credited || v,
+                    Err(err) => {
+                        ic_cdk::api::debug_print(format!("scan_evm_deposits failed: {err}"));
+                    }
+                }
             }
-            Err(err) => {
-                ic_cdk::api::debug_print(format!("failed to retrieve Schnorr public key: {err}"));
+
+            let round = STATE.with_borrow_mut(|s| {
+                s.evm_deposit_scanning = false;
+                s.finalize_bridging_round.0
+            });
+            if credited {
+                ic_cdk_timers::set_timer(Duration::from_secs(0), finalize_bridging(round));
             }
         }
     }
 
-    pub async fn try_init_ed25519_public_key() {
-        let (key_name, has_ed25519_public_key) = STATE.with_borrow(|r| {
-            (
-                r.key_name.clone(),
-                !r.ed25519_public_key.public_key.is_empty(),
-            )
+    // Scans the confirmed Transfer events to the canister's EVM address on a chain,
+    // returns true if any deposit was credited.
+    async fn scan_evm_chain_deposits(chain: &str, now_ms: u64) -> Result<bool, String> {
+        let (contracts, cursor, to_addr) = STATE.with_borrow(|s| {
+            let contracts: HashMap<Address, String> = s
+                .tokens
+                .values()
+                .filter_map(|t| {
+                    t.evm_contracts
+                        .get(chain)
+                        .map(|(addr, _, _)| (*addr, t.symbol.clone()))
+                })
+                .collect();
+            let cursor = s
+                .evm_deposit_cursors
+                .get(chain)
+                .cloned()
+                .unwrap_or_default();
+            (contracts, cursor, s.evm_address)
         });
-
-        if has_ed25519_public_key {
-            return;
+        if contracts.is_empty() {
+            return Ok(false);
         }
 
-        match schnorr_public_key(key_name, vec![], None).await {
-            Ok(root_pk) => {
-                STATE.with_borrow_mut(|s| {
-                    let self_pk = derive_schnorr_public_key(
-                        &root_pk,
-                        vec![s.icp_address.as_slice().to_vec()],
-                        None,
-                    )
-                    .expect("derive_schnorr_public_key failed");
+        let client = evm_client(chain);
+        let latest = client
+            .block_number(now_ms)
+            .await
+            .map_err(|err| format!("{chain}: {err}"))?;
+        let confirmed = latest.saturating_sub(client.max_confirmations);
+        // start from the latest confirmed block if the cursor was not initialized
+        let from_block = if cursor == 0 { confirmed } else { cursor + 1 };
+        if from_block > confirmed {
+            return Ok(false);
+        }
+        let to_block = confirmed.min(from_block + MAX_LOG_BLOCK_RANGE - 1);
+
+        let addresses: Vec<Address> = contracts.keys().cloned().collect();
+        let logs = client
+            .get_logs(
+                now_ms,
+                from_block,
+                to_block,
+                &addresses,
+                &[
+                    Some(TRANSFER_EVENT_TOPIC.into()),
+                    None,
+                    Some(address_to_topic(&to_addr)),
+                ],
+            )
+            .await
+            .map_err(|err| format!("{chain}: {err}"))?;
 
-                    s.ed25519_public_key = root_pk;
-                    s.svm_address = self_pk.to_svm_pubkey().unwrap();
-                });
+        let mut deposits: Vec<EvmDeposit> = Vec::new();
+        for log in logs {
+            if log.removed {
+                continue;
             }
-            Err(err) => {
-                ic_cdk::api::debug_print(format!("failed to retrieve Schnorr public key: {err}"));
+            let (Some(tx_hash), Some(transfer)) =
+                (log.transaction_hash, decode_erc20_transfer_log(&log))
+            else {
+                continue;
+            };
+            let Some(token) = contracts.get(&transfer.contract) else {
+                continue;
+            };
+            if transfer.to != to_addr {
+                continue;
             }
+            let Ok(amount) = u128::try_from(transfer.value) else {
+                ic_cdk::api::debug_print(format!("{chain}: deposit amount overflow in {tx_hash}"));
+                continue;
+            };
+            deposits.push(EvmDeposit {
+                token: token.clone(),
+                chain: chain.to_string(),
+                sender: transfer.from.to_string(),
+                tx_hash: tx_hash.0.into(),
+                log_index: log.log_index.unwrap_or_default(),
+                amount,
+                block_number: log.block_number.unwrap_or(to_block),
+            });
         }
-    }
 
-    pub fn with<R>(f: impl FnOnce(&State) -> R) -> R {
-        STATE.with_borrow(f)
+        // the cursor moves with the credited deposits, a deposit failing to be credited is
+        // dead-lettered and does not hold back the others
+        STATE.with_borrow_mut(|s| {
+            let mut credited = false;
+            for deposit in deposits {
+                let Ok(sender) = deposit.sender.parse::<Address>() else {
+                    continue;
+                };
+                match s.evm_deposit_intents.get(&sender).cloned() {
+                    Some(intent) => match credit_evm_deposit(s, &deposit, &intent, now_ms) {
+                        Ok(()) => credited = true,
+                        Err(err) => {
+                            let log = failed_evm_deposit_log(&deposit, &intent, err, now_ms);
+                            s.dead_letters.push(log);
+                        }
+                    },
+                    None => s.evm_unmatched_deposits.push(deposit),
+                }
+            }
+            s.evm_deposit_cursors.insert(chain.to_string(), to_block);
+            Ok(credited)
+        })
     }
 
-    pub fn with_mut<R>(f: impl FnOnce(&mut State) -> R) -> R {
-        STATE.with_borrow_mut(f)
-    }
+    pub async fn reconcile_reserves() {
+        let tokens = STATE.with_borrow_mut(|s| {
+            if s.reserves_reconciling || s.tokens.is_empty() {
+                return None;
+            }
+            s.reserves_reconciling = true;
+            Some(s.tokens.keys().cloned().collect::<Vec<_>>())
+        });
 
-    pub fn http_tree_with<R>(f: impl FnOnce(&HttpCertificationTree) -> R) -> R {
-        HTTP_TREE.with(|r| f(&r.borrow()))
+        if let Some(tokens) = tokens {
+            let now_ms = ic_cdk::api::time() / 1_000_000;
+            let mut recheck = false;
+            for token in tokens {
+                match reconcile_token(&token, now_ms).await {
+                    Ok(report) => recheck = record_reserve_report(&token, report) || recheck,
+                    Err(err) => {
+                        ic_cdk::api::debug_print(format!(
+                            "reconcile_reserves for {token} failed: {err}"
+                        ));
+                    }
+                }
+            }
+
+            STATE.with_borrow_mut(|s| s.reserves_reconciling = false);
+            certify_reserves();
+            if recheck {
+                // the reads are not atomic, a bridging in between can look like a shortfall
+                ic_cdk_timers::set_timer(Duration::from_secs(60), reconcile_reserves());
+            }
+        }
     }
 
-    pub fn init_http_certified_data() {
-        HTTP_TREE.with(|r| {
-            let mut tree = r.borrow_mut();
-            tree.insert(&DEFAULT_CERT_ENTRY);
-            ic_cdk::api::certified_data_set(tree.root_hash())
+    // Reads the native balances of the canister's addresses on all EVM chains and Solana.
+    pub async fn check_gas_balances() {
+        let (chains, evm_address, svm_address) = STATE.with_borrow(|s| {
+            let mut chains: Vec<String> = s.evm_providers.keys().cloned().collect();
+            if !s.svm_providers.is_empty() {
+                chains.push("SOL".to_string());
+            }
+            (chains, s.evm_address, s.svm_address)
         });
-    }
 
-    pub fn load() {
-        STATE_STORE.with_borrow(|r| {
-            STATE.with_borrow_mut(|h| {
-                let bytes = r.get();
-                if bytes.is_empty() {
-                    return;
+        let now_ms = ic_cdk::api::time() / 1_000_000;
+        let results = futures::future::join_all(chains.iter().map(|chain| async move {
+            if chain == "SOL" {
+                svm_client()
+                    .get_balance(now_ms, &svm_address.to_string())
+                    .await
+                    .map(|balance| balance as u128)
+            } else {
+                let client = evm_client(chain);
+                // keeps the gas prices of the bridge fees fresh without outbound transactions
+                let tx_type =
+                    STATE.with_borrow(|s| s.evm_tx_types.get(chain).copied().unwrap_or_default());
+                if let Err(err) = refresh_evm_gas(&client, chain, tx_type, now_ms).await {
+                    ic_cdk::api::debug_print(format!(
+                        "{chain}: failed to fetch the gas price: {err}"
+                    ));
                 }
-                let v: State = from_reader(&bytes[..]).expect("failed to decode STATE_STORE data");
-                *h = v;
-            });
-        });
-    }
+                client.get_balance(now_ms, &evm_address).await
+            }
+        }))
+        .await;
 
-    pub fn save() {
-        STATE.with_borrow(|h| {
-            STATE_STORE.with_borrow_mut(|r| {
-                let mut buf = vec![];
-                into_writer(h, &mut buf).expect("failed to encode STATE_STORE data");
-                r.set(buf);
-            });
+        STATE.with_borrow_mut(|s| {
+            for (chain, res) in chains.into_iter().zip(results) {
+                match res {
+                    Ok(balance) => {
+                        s.gas_balances.insert(chain, (now_ms, balance));
+                    }
+                    Err(err) => {
+                        ic_cdk::api::debug_print(format!(
+                            "check_gas_balances for {chain} failed: {err}"
+                        ));
+                    }
+                }
+            }
         });
     }
 
-    pub fn info() -> StateInfo {
-        let mut info = STATE.with_borrow(|s| StateInfo::from(s));
-        info.total_bridge_count = BRIDGE_LOGS.with_borrow(|r| r.len());
-        info
-    }
+    // Reads the reserves of a token on all chains and checks that the tokens
+    // circulating outside ICP are backed by the canister's ICRC ledger balance.
+    async fn reconcile_token(token: &str, now_ms: u64) -> Result<ReserveReport, String> {
+        let (t, evm_address, svm_address) = STATE.with_borrow(|s| {
+            s.token(token)
+                .map(|t| (t.clone(), s.evm_address, s.svm_address))
+        })?;
+
+        let mut report = ReserveReport {
+            checked_at: now_ms,
+            ..Default::default()
+        };
+        for (chain, (contract, decimals, _)) in &t.evm_contracts {
+            let cli = evm_client(chain);
+            let supply = cli.erc20_total_supply(now_ms, contract).await?;
+            let balance = if t.is_mint_burn(chain) {
+                0
+            } else {
+                cli.erc20_balance_of(now_ms, contract, &evm_address).await?
+            };
+            report.chains.insert(
+                chain.clone(),
+                (
+                    convert_amount(balance, *decimals, t.decimals)?,
+                    convert_amount(supply, *decimals, t.decimals)?,
+                ),
+            );
+        }
+
+        if let Some((mint, decimals, program_id)) = &t.svm_contract {
+            let cli = svm_client();
+            let supply = cli.get_token_supply(now_ms, &mint.to_string()).await?;
+            let supply: u128 = supply
+                .amount
+                .parse()
+                .map_err(|err| format!("SOL: invalid token supply: {err}"))?;
+            let balance: u128 = if t.is_mint_burn("SOL") {
+                0
+            } else {
+                let ata = get_associated_token_address(&svm_address, mint, program_id);
+                cli.get_token_account_balance(now_ms, &ata.to_string())
+                    .await?
+                    .amount
+                    .parse()
+                    .map_err(|err| format!("SOL: invalid token balance: {err}"))?
+            };
+            report.chains.insert(
+                "SOL".to_string(),
+                (
+                    convert_amount(balance, *decimals, t.decimals)?,
+                    convert_amount(supply, *decimals, t.decimals)?,
+                ),
+            );
+        }
+
+        // read last, bridging from ICP during the reads only adds to the reserve
+        let balance: Nat = call(
+            t.ledger,
+            "icrc1_balance_of",
+            (Account {
+                owner: ic_cdk::api::canister_self(),
+                subaccount: None,
+            },),
+            0,
+        )
+        .await?;
+        report.icp_balance = balance
+            .0
+            .to_u128()
+            .ok_or_else(|| "ICP: balance too large".to_string())?;
+        report.circulating = report
+            .chains
+            .values()
+            .map(|(balance, supply)| supply.saturating_sub(*balance))
+            .sum();
 
-    pub fn evm_address(user: &Principal) -> Address {
         STATE.with_borrow(|s| {
-            let pk = derive_public_key(&s.ecdsa_public_key, vec![user.as_slice().to_vec()])
-                .expect("derive_public_key failed");
-            pk.to_evm_adress().unwrap()
+            let t = s.token(token)?;
+            report.unwithdrawn_fees = t.available_fees();
+            report.in_flight = s
+                .pending
+                .iter()
+                .filter(|log| log.token == token && !log.is_finalized())
+                .map(|log| log.icp_amount)
+                .sum();
+
+            // The check is one-sided: a pending task has either not yet reduced the supply
+            // outside ICP or not yet been paid out of icp_balance, so in_flight only adds to the
+            // reserve and is reported, not deducted. total_bridged_tokens is a cumulative volume,
+            // not a balance, and has no part in the invariant.
+            let reserve = report.icp_balance.saturating_sub(report.unwithdrawn_fees);
+            if report.circulating > reserve.saturating_add(t.reserve_tolerance) {
+                report.violation = Some(format!(
+                    "circulating supply {} exceeds the reserve {} by {}",
+                    report.circulating,
+                    reserve,
+                    report.circulating - reserve
+                ));
+            }
+            Ok(report)
         })
     }
 
-    pub fn evm_client(chain: &str) -> EvmClient<DefaultHttpOutcall> {
-        STATE.with_borrow(|s| {
-            s.evm_providers
-                .get(chain)
-                .map(|(max_confirmations, providers)| {
-                    EvmClient::new(
-                        providers.clone(),
-                        *max_confirmations,
-                        None,
-                        DefaultHttpOutcall::new(s.icp_address),
-                    )
-                })
-                .unwrap_or_else(|| {
-                    EvmClient::new(vec![], 1, None, DefaultHttpOutcall::new(s.icp_address))
-                })
+    // Records a reconciliation and pauses the token if the previous one was violated too,
+    // returns true if a new violation needs to be confirmed.
+    fn record_reserve_report(token: &str, report: ReserveReport) -> bool {
+        STATE.with_borrow_mut(|s| {
+            let mut recheck = false;
+            if let Some(violation) = &report.violation {
+                let confirmed = s.reserves.get(token).is_some_and(|r| r.violation.is_some());
+                if confirmed {
+                    if let Some(t) = s.tokens.get_mut(token) {
+                        t.paused = true;
+                    }
+                    ic_cdk::api::debug_print(format!(
+                        "token {token} is paused on reserve violation: {violation}"
+                    ));
+                } else {
+                    recheck = true;
+                }
+            }
+            s.reserves.insert(token.to_string(), report);
+            recheck
         })
     }
 
-    pub async fn evm_sign(user: &Principal, message_hash: Vec<u8>) -> Result<Vec<u8>, String> {
-        let key_name = STATE.with_borrow(|s| {
-            if !s.sub_bridges.contains(user) {
-                Err("user is not authorized to sign".to_string())
-            } else {
-                Ok(s.key_name.clone())
-            }
-        })?;
+    fn is_task(
+        log: &BridgeLog,
+        token: &str,
+        from_tx: &BridgeTx,
+        from_log_index: Option<u64>,
+    ) -> bool {
+        log.token == token
+            && log.from_tx.same_with(from_tx)
+            && (from_log_index.is_none() || log.from_log_index == from_log_index)
+    }
 
-        let cycles = cost_sign_with_ecdsa(key_name.clone())?;
-        let received = ic_cdk::api::msg_cycles_accept(cycles);
-        if received < cycles {
+    /// Finds a pending or dead-lettered task by its token and source transaction, and by the
+    /// log index of its Transfer event if the transaction carries several deposits.
+    pub fn find_task<'a>(
+        s: &'a State,
+        token: &str,
+        from_tx: &BridgeTx,
+        from_log_index: Option<u64>,
+    ) -> Result<&'a BridgeLog, String> {
+        let mut found = s
+            .pending
+            .iter()
+            .chain(s.dead_letters.iter())
+            .filter(|log| is_task(log, token, from_tx, from_log_index));
+        let task = found
+            .next()
+            .ok_or_else(|| format!("task {token} {from_tx:?} not found"))?;
+        if found.next().is_some() {
             return Err(format!(
-                "insufficient cycles: required {}, accepted {}",
-                cycles, received
+                "task {token} {from_tx:?} is ambiguous, specify its from_log_index"
             ));
         }
-
-        let derivation_path = vec![user.as_slice().to_vec()];
-        sign_with_ecdsa(key_name, derivation_path, message_hash).await
+        Ok(task)
     }
 
-    pub fn svm_address(user: &Principal) -> Pubkey {
-        STATE.with_borrow(|s| {
-            let pk = derive_schnorr_public_key(
-                &s.ed25519_public_key,
-                vec![user.as_slice().to_vec()],
-                None,
-            )
-            .expect("derive_schnorr_public_key failed");
-            pk.to_svm_pubkey().unwrap()
-        })
+    // Removes a task from the pending tasks or the dead letters.
+    fn take_task(
+        s: &mut State,
+        token: &str,
+        from_tx: &BridgeTx,
+        from_log_index: Option<u64>,
+    ) -> Result<BridgeLog, String> {
+        find_task(s, token, from_tx, from_log_index)?;
+        let matches = |log: &BridgeLog| is_task(log, token, from_tx, from_log_index);
+        if let Some(idx) = s.pending.iter().position(matches) {
+            return Ok(s.pending.remove(idx).unwrap());
+        }
+        if let Some(idx) = s.dead_letters.iter().position(matches) {
+            return Ok(s.dead_letters.remove(idx));
+        }
+        Err(format!("task {token} {from_tx:?} not found"))
     }
 
-    pub fn svm_client() -> SvmClient<DefaultHttpOutcall> {
-        STATE.with_borrow(|s| {
-            SvmClient::new(
-                s.svm_providers.clone(),
-                None,
-                None,
-                DefaultHttpOutcall::new(s.icp_address),
-            )
-        })
+    pub fn check_cancel_task(task: &BridgeLog) -> Result<(), String> {
+        if !task.from_tx.is_finalized() {
+            return Err("the source transaction is not finalized".to_string());
+        }
+        if task.to_tx.is_some() {
+            return Err(
+                "the destination transaction was sent, mark it finalized instead".to_string(),
+            );
+        }
+        if task.refund.is_some() {
+            return Err("the task is already being refunded".to_string());
+        }
+        Ok(())
     }
 
-    pub async fn bridge(
-        from_chain: String,
-        to_chain: String,
-        icp_amount: u128,
-        to_addr: Option<String>,
-        user: Principal,
-        now_ms: u64,
-    ) -> Result<BridgeTx, String> {
-        if from_chain == to_chain {
-            return Err("from_chain and to_chain cannot be the same".to_string());
+    pub fn check_mark_finalized(task: &BridgeLog, to_tx: &BridgeTx) -> Result<(), String> {
+        if task.refund_tx.is_some() {
+            return Err("the task has a refund transaction".to_string());
+        }
+        if !task.from_tx.is_finalized() {
+            return Err("the source transaction is not finalized".to_string());
+        }
+        match (&task.to, to_tx) {
+            (BridgeTarget::Icp, BridgeTx::Icp(..))
+            | (BridgeTarget::Evm(_), BridgeTx::Evm(..))
+            | (BridgeTarget::Sol, BridgeTx::Sol(..)) => {}
+            _ => {
+                return Err(format!(
+                    "{to_tx:?} is not a transaction on the destination chain {:?}",
+                    task.to
+                ));
+            }
         }
 
-        let (from, to, token_ledger, token_bridge_fee) = STATE.with_borrow(|s| {
-            if s.error_rounds >= MAX_ERROR_ROUNDS {
-                return Err("the bridge is temporarily disabled due to errors, please contact the administrator".to_string());
+        // the canister's own destination transaction in flight must be the one marked, or one
+        // it replaced with the same nonce, otherwise it could still land and pay twice
+        match (&task.to_tx, to_tx) {
+            (None, _) => Ok(()),
+            (Some(BridgeTx::Evm(_, tx)), BridgeTx::Evm(_, proof))
+                if tx == proof
+                    || task
+                        .evm_tx
+                        .as_ref()
+                        .is_some_and(|sent| sent.replaced.contains(proof)) =>
+            {
+                Ok(())
             }
+            (Some(tx), proof) if tx.same_with(proof) => Ok(()),
+            (Some(tx), _) => Err(format!(
+                "the destination transaction {tx:?} of the task may still land"
+            )),
+        }
+    }
 
-            for log in s.pending.iter() {
-                if let Some(err) = &log.error
-                    && (err.starts_with(from_chain.as_str()) || err.starts_with(to_chain.as_str()))
-                {
-                    return Err(format!(
-                        "there is a pending bridging task with error, please retry later:\n{}",
-                        err
-                    ));
+    // Verifies on the destination chain that the transaction marking a task finalized is
+    // finalized and, on EVM chains, pays the task's amount to its recipient.
+    pub async fn verify_mark_finalized(
+        task: &BridgeLog,
+        to_tx: &BridgeTx,
+        now_ms: u64,
+    ) -> Result<(), String> {
+        check_mark_finalized(task, to_tx)?;
+        match (&task.to, to_tx) {
+            (BridgeTarget::Evm(chain), BridgeTx::Evm(_, tx_hash)) => {
+                let to_addr =
+                    evm_to_addr(task.to_addr.as_deref(), &task.user).map_err(task_error_message)?;
+                let amount = task.icp_amount.saturating_sub(task.fee + task.dust);
+                let expected = expected_evm_transfer(&task.token, chain, to_addr, amount, true)?;
+                let tx_hash: TxHash = (**tx_hash).into();
+                match check_evm_tx_finalized(chain, &tx_hash, &expected, now_ms).await {
+                    Ok(true) => Ok(()),
+                    Ok(false) => Err(format!("{chain}: {tx_hash} is not finalized")),
+                    Err(err) => Err(task_error_message(err)),
                 }
             }
-            if icp_amount < s.min_threshold_to_bridge {
-                return Err(format!(
-                    "amount {} is below the minimum threshold to bridge {}",
-                    icp_amount, s.min_threshold_to_bridge
-                ));
-            }
-            let from = if from_chain == "ICP" {
-                BridgeTarget::Icp
-            } else if from_chain == "SOL" {
-                if s.svm_token_address.0 == Pubkey::default() {
-                    return Err("SOL token is not supported".to_string());
-                }
-                BridgeTarget::Sol
-            } else {
-                if !s.evm_token_contracts.contains_key(&from_chain) {
-                    return Err(format!(
-                        "from_chain {} not found or not supported",
-                        from_chain
-                    ));
+            (BridgeTarget::Sol, BridgeTx::Sol(_, tx_hash)) => {
+                match check_sol_tx_finalized(tx_hash, now_ms).await? {
+                    Some(status) if status.is_error() => {
+                        Err(format!("SOL: transaction failed: {:?}", status.err))
+                    }
+                    Some(status) if status.is_finalized() => Ok(()),
+                    _ => Err("SOL: transaction is not finalized".to_string()),
                 }
-                BridgeTarget::Evm(from_chain)
-            };
+            }
+            // transfers on the ICRC ledger are final once they have a block index
+            _ => Ok(()),
+        }
+    }
 
-            let to = if to_chain == "ICP" {
-                if let Some(to_addr) = &to_addr {
-                    let _ = Principal::from_text(to_addr)
-                        .map_err(|_| format!("invalid ICP address {to_addr}"))?;
-                }
-                BridgeTarget::Icp
-            } else if to_chain == "SOL" {
-                if s.svm_token_address.0 == Pubkey::default() {
-                    return Err("SOL token is not supported".to_string());
-                }
-                if let Some(to_addr) = &to_addr {
-                    let _ = Pubkey::from_str(to_addr)
-                        .map_err(|_| format!("invalid SOL address: {}", to_addr))?;
-                }
-                BridgeTarget::Sol
-            } else {
-                if !s.evm_token_contracts.contains_key(&to_chain) {
-                    return Err(format!("to_chain {} not found or not supported", to_chain));
-                }
-                if let Some(to_addr) = &to_addr {
-                    let _ = to_addr
-                        .parse::<Address>()
-                        .map_err(|_| format!("invalid EVM address: {}", to_addr))?;
-                }
+    fn task_error_message(err: TaskError) -> String {
+        match err {
+            TaskError::Retry(err) | TaskError::Fatal(err) | TaskError::Refund(err) => err,
+        }
+    }
+
+    fn reset_task(task: &mut BridgeLog) {
+        task.error = None;
+        task.failed = false;
+        task.errors = 0;
+        task.next_retry_at = 0;
+    }
+
+    /// Moves a task back to the pending tasks and processes it immediately.
+    pub fn retry_task(
+        token: &str,
+        from_tx: &BridgeTx,
+        from_log_index: Option<u64>,
+    ) -> Result<(), String> {
+        let round = STATE.with_borrow_mut(|s| {
+            let mut task = take_task(s, token, from_tx, from_log_index)?;
+            reset_task(&mut task);
+            s.pending.push_back(task);
+            Ok::<_, String>(s.finalize_bridging_round.0)
+        })?;
+        ic_cdk_timers::set_timer(Duration::from_secs(0), finalize_bridging(round));
+        Ok(())
+    }
+
+    /// Gives up the destination leg of a task and refunds it on the source chain.
+    pub fn cancel_task(
+        token: &str,
+        from_tx: &BridgeTx,
+        from_log_index: Option<u64>,
+    ) -> Result<(), String> {
+        let round = STATE.with_borrow_mut(|s| {
+            let task = find_task(s, token, from_tx, from_log_index)?;
+            check_cancel_task(task)?;
+            let mut task = take_task(s, token, from_tx, from_log_index)?;
+            reset_task(&mut task);
+            set_refund(&mut task, s.token(token)?);
+            s.pending.push_back(task);
+            Ok::<_, String>(s.finalize_bridging_round.0)
+        })?;
+        ic_cdk_timers::set_timer(Duration::from_secs(0), finalize_bridging(round));
+        Ok(())
+    }
 
-                BridgeTarget::Evm(to_chain)
+    /// Finalizes a task with a destination transaction verified on chain.
+    pub async fn mark_task_finalized(
+        token: &str,
+        from_tx: &BridgeTx,
+        from_log_index: Option<u64>,
+        to_tx: BridgeTx,
+        now_ms: u64,
+    ) -> Result<(), String> {
+        let task = STATE.with_borrow(|s| find_task(s, token, from_tx, from_log_index).cloned())?;
+        verify_mark_finalized(&task, &to_tx, now_ms).await?;
+        STATE.with_borrow_mut(|s| {
+            // the task may have changed while verifying
+            let task = find_task(s, token, from_tx, from_log_index)?;
+            check_mark_finalized(task, &to_tx)?;
+            let mut task = take_task(s, token, from_tx, from_log_index)?;
+            task.from_tx = match task.from_tx {
+                BridgeTx::Icp(_, tx) => BridgeTx::Icp(true, tx),
+                BridgeTx::Evm(_, tx) => BridgeTx::Evm(true, tx),
+                BridgeTx::Sol(_, tx) => BridgeTx::Sol(true, tx),
             };
+            task.to_tx = Some(match to_tx {
+                BridgeTx::Icp(_, tx) => BridgeTx::Icp(true, tx),
+                BridgeTx::Evm(_, tx) => BridgeTx::Evm(true, tx),
+                BridgeTx::Sol(_, tx) => BridgeTx::Sol(true, tx),
+            });
+            task.refund = None;
+            task.failed = false;
+            archive_log(&mut s.tokens, &mut task, now_ms);
+            Ok(())
+        })
+    }
 
-            for log in s.pending.iter() {
-                if log.user == user
-                    && log.from == from
-                    && matches!(log.from_tx, BridgeTx::Evm(false, _))
-                {
-                    return Err(format!(
-                        "there is already a pending bridging task from {:?} for user {:?}",
-                        log.from, log.user
-                    ));
-                }
+    /// Pauses or resumes a chain, and resumes the halted tasks when it is resumed.
+    pub fn set_chain_paused(chain: String, paused: bool, halt_outbound: bool) {
+        let round = STATE.with_borrow_mut(|s| {
+            if paused {
+                s.paused_chains.insert(chain, halt_outbound);
+                None
+            } else {
+                s.paused_chains.remove(&chain);
+                Some(s.finalize_bridging_round.0)
             }
+        });
+        if let Some(round) = round {
+            ic_cdk_timers::set_timer(Duration::from_secs(0), finalize_bridging(round));
+        }
+    }
+
+    pub fn check_release_task(task: &BridgeLog) -> Result<(), String> {
+        if task.release_at == 0 {
+            return Err("the task is not held by the timelock".to_string());
+        }
+        if task.to_tx.is_some() {
+            return Err("the task is already released".to_string());
+        }
+        Ok(())
+    }
 
-            Ok((from, to, s.token_ledger, s.token_bridge_fee))
+    /// Releases the destination leg of a task held by the timelock immediately.
+    pub fn release_task(
+        token: &str,
+        from_tx: &BridgeTx,
+        from_log_index: Option<u64>,
+    ) -> Result<(), String> {
+        let round = STATE.with_borrow_mut(|s| {
+            check_release_task(find_task(s, token, from_tx, from_log_index)?)?;
+            let task = s
+                .pending
+                .iter_mut()
+                .find(|log| is_task(log, token, from_tx, from_log_index))
+                .ok_or_else(|| format!("task {token} {from_tx:?} is not pending"))?;
+            task.release_at = 0;
+            Ok::<_, String>(s.finalize_bridging_round.0)
         })?;
+        ic_cdk_timers::set_timer(Duration::from_secs(0), finalize_bridging(round));
+        Ok(())
+    }
 
-        let from_tx = match &from {
-            BridgeTarget::Icp => from_icp(token_ledger, user, icp_amount).await?,
-            BridgeTarget::Sol => from_svm(user, icp_amount, now_ms).await?,
-            BridgeTarget::Evm(chain) => from_evm(chain, user, icp_amount, now_ms).await?,
-        };
-
-        let delay = if from == BridgeTarget::Icp { 0 } else { 5 };
+    /// Clears the error counters of all pending tasks so they are retried immediately.
+    pub fn reset_error_rounds() {
         let round = STATE.with_borrow_mut(|s| {
-            s.pending.push_back(BridgeLog {
-                id: None,
-                user,
-                from,
-                to,
-                icp_amount,
-                fee: token_bridge_fee,
-                from_tx: from_tx.clone(),
-                to_tx: None,
-                to_addr,
-                created_at: now_ms,
-                finalized_at: 0,
-                error: None,
-            });
+            for task in s.pending.iter_mut() {
+                task.errors = 0;
+                task.next_retry_at = 0;
+            }
             s.finalize_bridging_round.0
         });
-
-        ic_cdk_timers::set_timer(Duration::from_secs(delay), finalize_bridging(round));
-
-        Ok(from_tx)
+        ic_cdk_timers::set_timer(Duration::from_secs(0), finalize_bridging(round));
     }
 
     pub fn my_bridge_log(user: Principal, from_tx: BridgeTx) -> Option<BridgeLog> {
         let mut log = STATE.with_borrow(|s| {
             s.pending
                 .iter()
+                .chain(s.dead_letters.iter())
                 .find(|item| item.user == user && item.from_tx == from_tx)
                 .cloned()
         });
@@ -785,7 +3212,7 @@ pub mod state {
                             && log.from_tx == from_tx
                         {
                             log.id = Some(id);
-                            return Some(log.into());
+                            return Some(archived_log(log));
                         }
                     }
                     None
@@ -796,6 +3223,15 @@ pub mod state {
         log
     }
 
+    // archived logs are immutable, the ones from before multi-token support get the migrated token
+    fn archived_log(log: BridgeLogLocal) -> BridgeLog {
+        let mut log: BridgeLog = log.into();
+        if log.token.is_empty() {
+            log.token = STATE.with_borrow(|s| s.legacy_token.clone());
+        }
+        log
+    }
+
     pub fn user_logs(user: Principal, take: usize, prev: Option<u64>) -> Vec<BridgeLog> {
         USER_LOGS.with_borrow(|r| {
             let item = r.get(&user).unwrap_or_default();
@@ -819,7 +3255,7 @@ pub mod state {
                 for id in ids {
                     if let Some(mut log) = log_store.get(id) {
                         log.id = Some(id);
-                        logs.push(log.into());
+                        logs.push(archived_log(log));
                     }
                 }
                 logs
@@ -836,7 +3272,7 @@ pub mod state {
                 idx -= 1;
                 if let Some(mut log) = log_store.get(idx) {
                     log.id = Some(idx);
-                    logs.push(log.into());
+                    logs.push(archived_log(log));
                 }
             }
             logs
@@ -844,6 +3280,7 @@ pub mod state {
     }
 
     pub async fn finalize_bridging(round: u64) {
+        let now_ms = ic_cdk::api::time() / 1_000_000;
         let tasks = STATE.with_borrow_mut(|s| {
             if s.finalize_bridging_round.1 || round < s.finalize_bridging_round.0 {
                 // already running or old round
@@ -854,24 +3291,49 @@ pub mod state {
                 return None;
             }
 
-            s.finalize_bridging_round.1 = true;
-            // take up to 3 pending tasks to process in parallel
-            let mut tasks = Vec::with_capacity(3);
-            // 针对 EVM 出口，按链互斥，避免同一 from 地址的 nonce 冲突
-            let mut evm_outgoing_locked: HashSet<String> = HashSet::new();
+            // take up to MAX_ROUND_TASKS pending tasks to process in parallel
+            let mut tasks = Vec::with_capacity(MAX_ROUND_TASKS);
+            // the earliest retry time of the tasks that are backing off or held
+            let mut next_retry_at = u64::MAX;
             for task in s.pending.iter() {
-                if let BridgeTarget::Evm(chain) = &task.to
-                    && !evm_outgoing_locked.insert(chain.clone())
-                {
-                    // 已有同链任务在本轮处理，跳过以避免 nonce 冲突
+                if task.next_retry_at > now_ms {
+                    next_retry_at = next_retry_at.min(task.next_retry_at);
+                    continue;
+                }
+                if task.is_held(now_ms) && task.from_tx.is_finalized() {
+                    next_retry_at = next_retry_at.min(task.release_at);
+                    continue;
+                }
+                // refunds are sent on the source chain
+                let outgoing = if task.refund.is_some() {
+                    &task.from
+                } else {
+                    &task.to
+                };
+                if s.is_outbound_halted(outgoing.chain_name()) {
                     continue;
                 }
 
                 tasks.push(task.clone());
-                if tasks.len() == 3 {
+                if tasks.len() == MAX_ROUND_TASKS {
                     break;
                 }
             }
+            if tasks.is_empty() {
+                if next_retry_at == u64::MAX {
+                    // all tasks are halted, resumed by the administrators
+                    return None;
+                }
+                // all tasks are backing off, held or halted, wait for the earliest one
+                let delay = next_retry_at.saturating_sub(now_ms).div_ceil(1000);
+                ic_cdk_timers::set_timer(
+                    Duration::from_secs(delay.max(1)),
+                    finalize_bridging(s.finalize_bridging_round.0),
+                );
+                return None;
+            }
+
+            s.finalize_bridging_round.1 = true;
             Some(tasks)
         });
 
@@ -879,28 +3341,15 @@ pub mod state {
             let tasks = try_finalize_tasks(tasks).await;
             let now_ms = ic_cdk::api::time() / 1_000_000;
             let next = STATE.with_borrow_mut(|s| {
-                let mut has_error = false;
                 for task in tasks {
-                    has_error = has_error || task.error.is_some();
                     for t in s.pending.iter_mut() {
                         if t.same_with(&task) {
                             *t = task;
-                            if t.to_tx.as_ref().is_some_and(|tx| tx.is_finalized()) {
-                                t.error = None;
-                                t.finalized_at = now_ms;
-                                s.total_bridged_tokens =
-                                    s.total_bridged_tokens.saturating_add(t.icp_amount);
-                                s.total_collected_fees =
-                                    s.total_collected_fees.saturating_add(t.fee);
-
-                                let idx = BRIDGE_LOGS
-                                    .with_borrow_mut(|r| r.append(&t.clone().into()))
-                                    .expect("failed to append to BRIDGE_LOGS");
-                                USER_LOGS.with_borrow_mut(|r| {
-                                    let mut logs = r.get(&t.user).unwrap_or_default();
-                                    logs.logs.insert(idx);
-                                    r.insert(t.user, logs);
-                                });
+                            if t.is_finalized() {
+                                archive_log(&mut s.tokens, t, now_ms);
+                                if t.refund_tx.is_none() {
+                                    record_finality(&mut s.finality_ms, t);
+                                }
                             }
                             break;
                         }
@@ -908,19 +3357,21 @@ pub mod state {
                 }
 
                 s.pending.retain(|t| !t.is_finalized());
+                // move the hopeless tasks out of the way of the others
+                let (dead, pending): (VecDeque<_>, VecDeque<_>) = s
+                    .pending
+                    .drain(..)
+                    .partition(|t| t.failed || t.errors >= MAX_TASK_ERRORS);
+                s.pending = pending;
+                s.dead_letters.extend(dead);
+                // the nonces sent in this round are recorded in the tasks now
+                s.evm_nonces_reserved.clear();
+                s.svm_nonces_reserved.clear();
                 s.finalize_bridging_round = (s.finalize_bridging_round.0 + 1, false);
 
                 if s.pending.is_empty() {
                     None
-                } else if has_error {
-                    s.error_rounds += 1;
-                    if s.error_rounds >= MAX_ERROR_ROUNDS {
-                        None
-                    } else {
-                        Some((5 * s.error_rounds, s.finalize_bridging_round.0))
-                    }
                 } else {
-                    s.error_rounds = 0;
                     Some((1, s.finalize_bridging_round.0))
                 }
             });
@@ -931,112 +3382,552 @@ pub mod state {
         }
     }
 
+    fn archive_log(tokens: &mut BTreeMap<String, Token>, t: &mut BridgeLog, now_ms: u64) {
+        t.error = None;
+        t.finalized_at = now_ms;
+        if let Some(token) = tokens.get_mut(&t.token) {
+            if t.refund_tx.is_none() {
+                token.total_bridged_tokens =
+                    token.total_bridged_tokens.saturating_add(t.icp_amount);
+            }
+            token.total_collected_fees = token
+                .total_collected_fees
+                .saturating_add(t.fee)
+                .saturating_add(t.dust);
+            token.total_dust = token.total_dust.saturating_add(t.dust);
+        }
+
+        let idx = BRIDGE_LOGS
+            .with_borrow_mut(|r| r.append(&t.clone().into()))
+            .expect("failed to append to BRIDGE_LOGS");
+        USER_LOGS.with_borrow_mut(|r| {
+            let mut logs = r.get(&t.user).unwrap_or_default();
+            logs.logs.insert(idx);
+            r.insert(t.user, logs);
+        });
+    }
+
+    // Updates the moving average of the time to finality of the log's route,
+    // not counting the time held by the timelock.
+    fn record_finality(finality_ms: &mut HashMap<String, u64>, t: &BridgeLog) {
+        let elapsed = t
+            .finalized_at
+            .saturating_sub(t.created_at.max(t.release_at));
+        let route = format!("{}->{}", t.from.chain_name(), t.to.chain_name());
+        let avg = finality_ms.entry(route).or_insert(elapsed);
+        *avg = (*avg * 4 + elapsed) / 5;
+    }
+
     async fn try_finalize_tasks(tasks: Vec<BridgeLog>) -> Vec<BridgeLog> {
         let now_ms = ic_cdk::api::time() / 1_000_000;
         futures::future::join_all(tasks.into_iter().map(|task| process_task(task, now_ms))).await
     }
 
     async fn process_task(mut task: BridgeLog, now_ms: u64) -> BridgeLog {
-        let rt = async {
-            let from_finalized = match (&task.from, &mut task.from_tx) {
-                (BridgeTarget::Evm(chain), BridgeTx::Evm(finalized, tx_hash)) if !*finalized => {
-                    let tx_hash: TxHash = (**tx_hash).into();
-                    let from_finalized = check_evm_tx_finalized(chain, &tx_hash, now_ms).await?;
-                    if from_finalized {
-                        *finalized = true;
-                    }
-                    from_finalized
+        let rt = match check_from_tx(&mut task, now_ms).await {
+            Ok(false) => Ok(()),
+            Ok(true) if task.refund.is_some() => process_refund(&mut task, now_ms).await,
+            Ok(true) if task.is_held(now_ms) => Ok(()),
+            Ok(true) => match process_to_tx(&mut task, now_ms).await {
+                Err(err) => Err(on_to_tx_error(&mut task, err)),
+                rt => rt,
+            },
+            // the source transaction failed, nothing to refund
+            Err(TaskError::Refund(err)) => Err(TaskError::Fatal(err)),
+            Err(err) => Err(err),
+        };
+
+        task.error = match rt {
+            Ok(()) => None,
+            Err(TaskError::Retry(err)) | Err(TaskError::Refund(err)) => Some(err),
+            Err(TaskError::Fatal(err)) => {
+                task.failed = true;
+                Some(err)
+            }
+        };
+        if let Some(err) = &task.error {
+            ic_cdk::api::debug_print(format!("finalize_tasks failed: {err}"));
+            task.errors = task.errors.saturating_add(1);
+            task.next_retry_at = now_ms + retry_delay_ms(task.errors);
+        } else {
+            task.errors = 0;
+            task.next_retry_at = 0;
+        }
+
+        task
+    }
+
+    fn retry_delay_ms(errors: u32) -> u64 {
+        TASK_RETRY_DELAY_MS
+            .saturating_mul(1 << errors.saturating_sub(1).min(20))
+            .min(MAX_TASK_RETRY_DELAY_MS)
+    }
+
+    // Returns true if the source transaction is finalized.
+    async fn check_from_tx(task: &mut BridgeLog, now_ms: u64) -> Result<bool, TaskError> {
+        match (&task.from, &mut task.from_tx) {
+            (BridgeTarget::Evm(chain), BridgeTx::Evm(finalized, tx_hash)) if !*finalized => {
+                let tx_hash: TxHash = (**tx_hash).into();
+                let expected = expected_evm_transfer(
+                    &task.token,
+                    chain,
+                    evm_address(&task.user),
+                    task.icp_amount,
+                    false,
+                )?;
+                let from_finalized =
+                    check_evm_tx_finalized(chain, &tx_hash, &expected, now_ms).await?;
+                if from_finalized {
+                    *finalized = true;
                 }
-                (BridgeTarget::Sol, BridgeTx::Sol(finalized, tx_hash)) if !*finalized => {
-                    let status = check_sol_tx_finalized(tx_hash, now_ms).await?;
-                    let from_finalized = status.is_some_and(|f| f.is_finalized());
-                    if from_finalized {
-                        *finalized = true;
-                    }
-                    from_finalized
+                Ok(from_finalized)
+            }
+            (BridgeTarget::Sol, BridgeTx::Sol(finalized, tx_hash)) if !*finalized => {
+                let status = check_sol_tx_finalized(tx_hash, now_ms).await?;
+                let from_finalized = status.is_some_and(|f| f.is_finalized());
+                if from_finalized {
+                    *finalized = true;
                 }
-                _ => true,
-            };
+                Ok(from_finalized)
+            }
+            _ => Ok(true),
+        }
+    }
 
-            if from_finalized {
-                match (&task.to, &mut task.to_tx) {
-                    (BridgeTarget::Icp, None) => {
-                        let token_ledger = STATE.with_borrow(|s| s.token_ledger);
-                        let to_addr = if let Some(addr) = &task.to_addr {
-                            Principal::from_text(addr)
-                                .map_err(|_| format!("ICP: invalid to_addr principal: {}", addr))?
-                        } else {
-                            task.user
-                        };
-                        let to_tx = to_icp(
-                            token_ledger,
-                            to_addr,
-                            task.icp_amount.saturating_sub(task.fee),
-                        )
-                        .await?;
-                        task.to_tx = Some(to_tx);
-                    }
-                    (BridgeTarget::Evm(chain), None) => {
-                        let to_addr = if let Some(addr) = &task.to_addr {
-                            addr.parse::<Address>()
-                                .map_err(|_| format!("EVM: invalid to_addr address: {}", addr))?
-                        } else {
-                            state::evm_address(&task.user)
-                        };
-                        let to_tx = to_evm(
-                            chain,
-                            to_addr,
-                            task.icp_amount.saturating_sub(task.fee),
-                            now_ms,
-                        )
-                        .await?;
-                        task.to_tx = Some(to_tx);
+    async fn process_to_tx(task: &mut BridgeLog, now_ms: u64) -> Result<(), TaskError> {
+        let amount = task.icp_amount.saturating_sub(task.fee + task.dust);
+        match (&task.to, &mut task.to_tx) {
+            (BridgeTarget::Icp, None) => {
+                let to_addr = if let Some(addr) = &task.to_addr {
+                    Principal::from_text(addr).map_err(|_| {
+                        TaskError::Refund(format!("ICP: invalid to_addr principal: {}", addr))
+                    })?
+                } else {
+                    task.user
+                };
+                let to_tx = to_icp(&task.token, to_addr, amount).await?;
+                task.to_tx = Some(to_tx);
+            }
+            (BridgeTarget::Evm(chain), None) => {
+                let to_addr = evm_to_addr(task.to_addr.as_deref(), &task.user)?;
+                let (to_tx, evm_tx) =
+                    to_evm(&task.token, chain, to_addr, amount, None, now_ms).await?;
+                task.to_tx = Some(to_tx);
+                task.evm_tx = Some(evm_tx);
+            }
+            (BridgeTarget::Evm(chain), Some(BridgeTx::Evm(false, _))) => {
+                let chain = chain.clone();
+                let to_addr = evm_to_addr(task.to_addr.as_deref(), &task.user)?;
+                check_outbound_evm_tx(task, &chain, to_addr, amount, now_ms).await?;
+            }
+            (BridgeTarget::Sol, None) => {
+                let to_addr = if let Some(addr) = &task.to_addr {
+                    Pubkey::from_str(addr).map_err(|_| {
+                        TaskError::Refund(format!("SOL: invalid to_addr address: {}", addr))
+                    })?
+                } else {
+                    state::svm_address(&task.user)
+                };
+                if let Some((to_tx, svm_tx)) = to_svm(&task.token, to_addr, amount, now_ms).await? {
+                    task.to_tx = Some(to_tx);
+                    task.svm_tx = Some(svm_tx);
+                }
+            }
+            (BridgeTarget::Sol, Some(BridgeTx::Sol(false, _))) => {
+                check_outbound_svm_tx(task, now_ms).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Counts the failures of the destination leg, and gives it up for a refund on the source
+    // chain when the failure is definitive or the retries are exhausted. No refund is made while
+    // a destination transaction may still land.
+    fn on_to_tx_error(task: &mut BridgeLog, err: TaskError) -> TaskError {
+        if matches!(err, TaskError::Fatal(_)) {
+            return err;
+        }
+
+        task.retries = task.retries.saturating_add(1);
+        let definitive = matches!(err, TaskError::Refund(_));
+        if task.to_tx.is_some() && !definitive {
+            return err;
+        }
+
+        STATE.with_borrow(|s| {
+            let refund_after_retries = s.refund_after_retries;
+            if (definitive || (refund_after_retries > 0 && task.retries >= refund_after_retries))
+                && let Some(t) = s.tokens.get(&task.token)
+            {
+                set_refund(task, t);
+            }
+        });
+        err
+    }
+
+    // Switches a task to be refunded on its source chain, withholding the refund fee and the dust.
+    fn set_refund(task: &mut BridgeLog, t: &Token) {
+        task.fee = t.refund_fee.min(task.icp_amount);
+        task.fee_detail = None;
+        let amount = task.icp_amount - task.fee;
+        let (refund, dust) = t.split_dust(&task.from, amount).unwrap_or((amount, 0));
+        task.dust = dust;
+        task.refund = Some(refund);
+    }
+
+    // Returns the bridged amount (minus the refund fee) to the user on the source chain.
+    async fn process_refund(task: &mut BridgeLog, now_ms: u64) -> Result<(), TaskError> {
+        let amount = task.refund.unwrap_or_default();
+        match (&task.from, &mut task.refund_tx) {
+            (BridgeTarget::Icp, None) => {
+                let refund_tx = to_icp(&task.token, task.user, amount).await?;
+                task.refund_tx = Some(refund_tx);
+            }
+            (BridgeTarget::Evm(chain), None) => {
+                let to_addr = evm_refund_addr(task);
+                let (refund_tx, evm_tx) =
+                    to_evm(&task.token, chain, to_addr, amount, None, now_ms).await?;
+                task.refund_tx = Some(refund_tx);
+                task.evm_tx = Some(evm_tx);
+            }
+            (BridgeTarget::Evm(chain), Some(BridgeTx::Evm(false, _))) => {
+                let chain = chain.clone();
+                let to_addr = evm_refund_addr(task);
+                match check_outbound_evm_tx(task, &chain, to_addr, amount, now_ms).await {
+                    Ok(()) => {}
+                    Err(TaskError::Refund(err)) => {
+                        task.refund_tx = None; // reset refund_tx to retry
+                        return Err(TaskError::Retry(err));
                     }
-                    (BridgeTarget::Evm(chain), Some(BridgeTx::Evm(finalized, tx_hash)))
-                        if !*finalized =>
+                    Err(err) => return Err(err),
+                }
+            }
+            (BridgeTarget::Sol, None) => {
+                let to_addr = state::svm_address(&task.user);
+                if let Some((refund_tx, svm_tx)) =
+                    to_svm(&task.token, to_addr, amount, now_ms).await?
+                {
+                    task.refund_tx = Some(refund_tx);
+                    task.svm_tx = Some(svm_tx);
+                }
+            }
+            (BridgeTarget::Sol, Some(BridgeTx::Sol(false, _))) => {
+                check_outbound_svm_tx(task, now_ms).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Checks the canister's outbound Solana transaction of a task. While its durable nonce is not
+    // advanced or its blockhash not expired, an unlanded transaction is re-broadcast as is, since
+    // it can still land. It is given up to be re-signed only once it provably can not land.
+    async fn check_outbound_svm_tx(task: &mut BridgeLog, now_ms: u64) -> Result<(), TaskError> {
+        let refunding = task.refund.is_some();
+        let tx_hash: [u8; 64] = match if refunding {
+            &task.refund_tx
+        } else {
+            &task.to_tx
+        } {
+            Some(BridgeTx::Sol(false, tx_hash)) => **tx_hash,
+            _ => return Ok(()),
+        };
+
+        let mut status = check_sol_tx_finalized(&tx_hash, now_ms).await?;
+        if status.is_none() {
+            let client = svm_client();
+            let recorded = task
+                .svm_tx
+                .as_ref()
+                .is_some_and(|tx| tx.nonce_account.is_some() || tx.last_valid_block_height > 0);
+            if !recorded {
+                // the blockhash of a transaction sent before it was recorded is unknown, it was
+                // fetched before now and expires within MAX_PROCESSING_AGE blocks, doubled for
+                // providers lagging behind
+                let block_height = client
+                    .get_block_height(now_ms)
+                    .await
+                    .map_err(|err| format!("SOL: {err}"))?;
+                let sent = task.svm_tx.get_or_insert_with(|| SvmOutboundTx {
+                    sent_at: now_ms,
+                    ..Default::default()
+                });
+                sent.last_valid_block_height = block_height + 2 * SVM_MAX_PROCESSING_AGE;
+                return Ok(());
+            }
+
+            let sent = task.svm_tx.as_ref().expect("recorded outbound transaction");
+            let valid = match &sent.nonce_account {
+                Some(nonce_account) => {
+                    let nonce = client
+                        .get_finalized_durable_nonce(now_ms, nonce_account)
+                        .await
+                        .map_err(|err| format!("SOL: {err}"))?;
+                    nonce.to_bytes() == *sent.blockhash
+                }
+                None => {
+                    let block_height = client
+                        .get_block_height(now_ms)
+                        .await
+                        .map_err(|err| format!("SOL: {err}"))?;
+                    if block_height > sent.last_valid_block_height
+                        && block_height <= sent.last_valid_block_height + SVM_MAX_PROCESSING_AGE
                     {
-                        let tx_hash: TxHash = (**tx_hash).into();
-                        let to_finalized = check_evm_tx_finalized(chain, &tx_hash, now_ms).await?;
-                        if to_finalized {
-                            *finalized = true;
-                        }
-                    }
-                    (BridgeTarget::Sol, None) => {
-                        let to_addr = if let Some(addr) = &task.to_addr {
-                            Pubkey::from_str(addr)
-                                .map_err(|_| format!("SOL: invalid to_addr address: {}", addr))?
-                        } else {
-                            state::svm_address(&task.user)
-                        };
-                        let to_tx =
-                            to_svm(to_addr, task.icp_amount.saturating_sub(task.fee), now_ms)
-                                .await?;
-                        task.to_tx = Some(to_tx);
-                    }
-                    (BridgeTarget::Sol, Some(BridgeTx::Sol(finalized, tx_hash))) if !*finalized => {
-                        let status = check_sol_tx_finalized(tx_hash, now_ms).await?;
-                        let from_finalized = status.as_ref().is_some_and(|f| f.is_finalized());
-                        if from_finalized {
-                            *finalized = true;
-                        } else if status.as_ref().is_none_or(|f| f.is_error()) {
-                            task.to_tx = None; // reset to_tx to retry
-                            return Err("SOL: transaction failed".to_string());
-                        }
+                        // expired, but a transaction that landed near its last valid block
+                        // height may not be visible at finalized commitment yet
+                        return Ok(());
                     }
-                    _ => {}
+                    block_height <= sent.last_valid_block_height
+                }
+            };
+            if valid {
+                if !sent.tx.is_empty() {
+                    let _ = client
+                        .send_transaction(now_ms, sent.tx.to_vec().into(), true)
+                        .await
+                        .map_err(|err| format!("SOL: {err}"))?;
                 }
+                return Ok(());
             }
+            // the nonce has advanced, or the blockhash expired a grace period ago, the
+            // transaction may have landed in the meantime
+            status = check_sol_tx_finalized(&tx_hash, now_ms).await?;
+        }
 
-            Ok::<(), String>(())
+        let outbound = if refunding {
+            &mut task.refund_tx
+        } else {
+            &mut task.to_tx
+        };
+        if status.as_ref().is_some_and(|f| f.is_finalized()) {
+            *outbound = Some(BridgeTx::Sol(true, tx_hash.into()));
+        } else if status.as_ref().is_none_or(|f| f.is_error()) {
+            *outbound = None; // reset to retry
+            return Err(if refunding {
+                "SOL: refund transaction failed".to_string().into()
+            } else {
+                "SOL: transaction failed".to_string().into()
+            });
         }
+        Ok(())
+    }
+
+    // Checks the canister's outbound EVM transaction of a task and the transactions it replaced,
+    // finalizing on whichever one lands. A transaction that stays unmined longer than the
+    // chain's timeout is replaced by one with the same nonce and bumped fees.
+    async fn check_outbound_evm_tx(
+        task: &mut BridgeLog,
+        chain: &str,
+        to_addr: Address,
+        amount: u128,
+        now_ms: u64,
+    ) -> Result<(), TaskError> {
+        let refunding = task.refund.is_some();
+        let tx_hash = match if refunding {
+            &task.refund_tx
+        } else {
+            &task.to_tx
+        } {
+            Some(BridgeTx::Evm(false, tx_hash)) => *tx_hash,
+            _ => return Ok(()),
+        };
+        let sent = task.evm_tx.clone().unwrap_or_default();
+        let expected = expected_evm_transfer(&task.token, chain, to_addr, amount, true)?;
+        let hashes: Vec<TxHash> = std::iter::once(&tx_hash)
+            .chain(sent.replaced.iter())
+            .map(|h| (**h).into())
+            .collect();
+        let rt = futures::future::join_all(
+            hashes
+                .iter()
+                .map(|h| check_evm_tx_finalized(chain, h, &expected, now_ms)),
+        )
         .await;
 
-        task.error = rt.err();
-        if let Some(err) = &task.error {
-            ic_cdk::api::debug_print(format!("finalize_tasks failed: {err}"));
+        let mut retry = None;
+        for (hash, rt) in hashes.iter().zip(rt) {
+            match rt {
+                Ok(true) => {
+                    let landed = Some(BridgeTx::Evm(true, hash.0.into()));
+                    if refunding {
+                        task.refund_tx = landed;
+                    } else {
+                        task.to_tx = landed;
+                    }
+                    return Ok(());
+                }
+                Ok(false) => {}
+                Err(TaskError::Retry(err)) => retry = Some(err),
+                Err(err) => return Err(err),
+            }
+        }
+        if let Some(err) = retry {
+            return Err(TaskError::Retry(err));
         }
 
-        task
+        let (timeout_secs, bridge_addr) = STATE.with_borrow(|s| {
+            (
+                s.evm_tx_timeouts
+                    .get(chain)
+                    .copied()
+                    .unwrap_or(EVM_TX_TIMEOUT_SECS),
+                s.evm_address,
+            )
+        });
+        // with replacement disabled, an unmined transaction is re-broadcast as is
+        let replace = timeout_secs > 0;
+        let timeout_secs = if replace {
+            timeout_secs
+        } else {
+            EVM_TX_TIMEOUT_SECS
+        };
+        if task.evm_tx.is_none() || now_ms < sent.sent_at + timeout_secs * 1000 {
+            return Ok(());
+        }
+        // replace only the next transaction to be mined, the later ones wait for it
+        let client = evm_client(chain);
+        let chain_nonce = client
+            .get_transaction_count(now_ms, &bridge_addr, "latest")
+            .await?;
+        if chain_nonce < sent.nonce {
+            return Ok(());
+        }
+        if chain_nonce > sent.nonce {
+            // the nonce is used by one of the transactions still being confirmed, the nonces of
+            // the bridge address are only assigned by the canister. A task none of whose
+            // transactions is found mined once the nonce is finalized is left to the
+            // controllers, re-signing it could pay twice.
+            let finalized_nonce = client
+                .get_finalized_transaction_count(now_ms, &bridge_addr)
+                .await
+                .map_err(|err| format!("{chain}: {err}"))?;
+            if finalized_nonce <= sent.nonce {
+                return Ok(());
+            }
+            let receipts = futures::future::join_all(
+                hashes
+                    .iter()
+                    .map(|h| client.get_transaction_receipt(now_ms, h)),
+            )
+            .await;
+            let mut mined = false;
+            for r in receipts {
+                mined = mined || r.map_err(|err| format!("{chain}: {err}"))?.is_some();
+            }
+            let Some(evm_tx) = task.evm_tx.as_mut() else {
+                return Ok(());
+            };
+            if mined {
+                evm_tx.nonce_used_checks = 0;
+                return Ok(());
+            }
+            evm_tx.nonce_used_checks += 1;
+            if evm_tx.nonce_used_checks < EVM_NONCE_USED_CHECKS {
+                return Ok(());
+            }
+            return Err(TaskError::Fatal(format!(
+                "{chain}: nonce {} is used at finalized depth, but none of the task's transactions \
+                 was found mined",
+                sent.nonce
+            )));
+        }
+
+        // a transaction that the capped fees can not get mined stays pending, it can still be
+        // mined and the later nonces of the chain wait for it
+        let max_fee = STATE.with_borrow(|s| s.evm_max_fees.get(chain).copied());
+        let capped = replace
+            && (sent.replaced.len() >= MAX_EVM_TX_REPLACEMENTS
+                || max_fee.is_some_and(|max| sent.max_fee_per_gas * 9 / 8 + 1 > max));
+        if capped && !sent.capped {
+            ic_cdk::api::debug_print(format!(
+                "{chain}: nonce {} is unmined after {} replacements, max_fee_per_gas {}",
+                sent.nonce,
+                sent.replaced.len(),
+                sent.max_fee_per_gas
+            ));
+        }
+
+        if !replace || capped {
+            // the node may still know the transaction, so a failure is not an error
+            if !sent.tx.is_empty()
+                && let Err(err) = client
+                    .send_raw_transaction(now_ms, Bytes::from(sent.tx.to_vec()).to_string())
+                    .await
+            {
+                ic_cdk::api::debug_print(format!("{chain}: failed to re-broadcast: {err}"));
+            }
+            if let Some(evm_tx) = task.evm_tx.as_mut() {
+                evm_tx.sent_at = now_ms;
+                evm_tx.capped = capped;
+            }
+            return Ok(());
+        }
+
+        let (tx, mut evm_tx) =
+            to_evm(&task.token, chain, to_addr, amount, Some(&sent), now_ms).await?;
+        evm_tx.replaced = sent.replaced;
+        evm_tx.replaced.push(tx_hash);
+        if refunding {
+            task.refund_tx = Some(tx);
+        } else {
+            task.to_tx = Some(tx);
+        }
+        task.evm_tx = Some(evm_tx);
+        Ok(())
+    }
+
+    // Refunds of a deposit go back to the wallet that sent it.
+    fn evm_refund_addr(task: &BridgeLog) -> Address {
+        task.from_addr
+            .as_deref()
+            .and_then(|addr| addr.parse::<Address>().ok())
+            .unwrap_or_else(|| evm_address(&task.user))
+    }
+
+    fn evm_to_addr(to_addr: Option<&str>, user: &Principal) -> Result<Address, TaskError> {
+        match to_addr {
+            Some(addr) => addr
+                .parse::<Address>()
+                .map_err(|_| TaskError::Refund(format!("EVM: invalid to_addr address: {}", addr))),
+            None => Ok(evm_address(user)),
+        }
+    }
+
+    // The ERC20 Transfer event that an inbound (from the user) or outbound (to the user)
+    // EVM transaction must emit.
+    fn expected_evm_transfer(
+        token: &str,
+        chain: &str,
+        user_addr: Address,
+        icp_amount: u128,
+        outbound: bool,
+    ) -> Result<Erc20TransferLog, String> {
+        STATE.with_borrow(|s| {
+            let t = s.token(token)?;
+            let (contract, decimals, _) = t
+                .evm_contracts
+                .get(chain)
+                .ok_or_else(|| format!("{chain}: token {token} contract not found"))?;
+            // minted from and burned to the zero address
+            let bridge_addr = if t.is_mint_burn(chain) {
+                Address::ZERO
+            } else {
+                s.evm_address
+            };
+            let (from, to) = if outbound {
+                (bridge_addr, user_addr)
+            } else {
+                (user_addr, bridge_addr)
+            };
+            let value = convert_amount(icp_amount, t.decimals, *decimals)?;
+            Ok(Erc20TransferLog {
+                contract: *contract,
+                from,
+                to,
+                value: U256::from(value),
+            })
+        })
     }
 
     async fn from_icp(
@@ -1074,11 +3965,15 @@ pub mod state {
         Ok(BridgeTx::Icp(true, idx))
     }
 
+    // Transfers from the canister's ledger balance, the ledger fee is recorded to be paid from
+    // the collected fees.
     pub async fn to_icp(
-        token_ledger: Principal,
+        token: &str,
         to_addr: Principal,
         icp_amount: u128,
     ) -> Result<BridgeTx, String> {
+        let token_ledger = STATE.with_borrow(|s| s.token(token).map(|t| t.ledger))?;
+        let fee: Nat = call(token_ledger, "icrc1_fee", (), 0).await?;
         let res: Result<Nat, TransferFromError> = call(
             token_ledger,
             "icrc1_transfer",
@@ -1088,7 +3983,7 @@ pub mod state {
                     owner: to_addr,
                     subaccount: None,
                 },
-                fee: None,
+                fee: Some(fee.clone()),
                 created_at_time: None,
                 memo: None,
                 amount: icp_amount.into(),
@@ -1098,6 +3993,13 @@ pub mod state {
         .await?;
         let res =
             res.map_err(|err| format!("ICP: failed to transfer token to user, error: {:?}", err))?;
+        STATE.with_borrow_mut(|s| {
+            if let Some(t) = s.tokens.get_mut(token) {
+                t.total_ledger_fees = t
+                    .total_ledger_fees
+                    .saturating_add(fee.0.to_u128().unwrap_or_default());
+            }
+        });
         let idx = res
             .0
             .to_u64()
@@ -1106,17 +4008,24 @@ pub mod state {
     }
 
     async fn from_evm(
+        token: &str,
         chain: &str,
         user: Principal,
         icp_amount: u128,
         now_ms: u64,
     ) -> Result<BridgeTx, String> {
-        let to_addr = STATE.with_borrow(|s| s.evm_address);
+        let call = STATE.with_borrow(|s| {
+            if s.token(token)?.is_mint_burn(chain) {
+                Ok::<_, String>(TokenCall::Burn)
+            } else {
+                Ok(TokenCall::Transfer(s.evm_address))
+            }
+        })?;
         let (client, signed_tx) =
-            build_erc20_transfer_tx(chain, &user, &to_addr, icp_amount, now_ms)
+            build_erc20_tx(token, chain, &user, call, icp_amount, None, now_ms)
                 .await
                 .map_err(|err| format!("{chain}: {err}"))?;
-        let tx_hash: [u8; 32] = (*signed_tx.hash()).into();
+        let tx_hash: [u8; 32] = (*signed_tx.tx_hash()).into();
         let data = signed_tx.encoded_2718();
 
         let _ = client
@@ -1127,130 +4036,497 @@ pub mod state {
     }
 
     async fn to_evm(
+        token: &str,
         chain: &str,
         to_addr: Address,
         icp_amount: u128,
+        replacing: Option<&EvmOutboundTx>,
         now_ms: u64,
-    ) -> Result<BridgeTx, String> {
-        // let to_addr = evm_address(&user);
-        let (client, signed_tx) = build_erc20_transfer_tx(
+    ) -> Result<(BridgeTx, EvmOutboundTx), String> {
+        let call = STATE.with_borrow(|s| {
+            if s.token(token)?.is_mint_burn(chain) {
+                Ok::<_, String>(TokenCall::Mint(to_addr))
+            } else {
+                Ok(TokenCall::Transfer(to_addr))
+            }
+        })?;
+        let (client, signed_tx) = build_erc20_tx(
+            token,
             chain,
             &ic_cdk::api::canister_self(),
-            &to_addr,
+            call,
             icp_amount,
+            replacing,
             now_ms,
         )
         .await
         .map_err(|err| format!("{chain}: {err}"))?;
 
-        let tx_hash: [u8; 32] = (*signed_tx.hash()).into();
-        let data = signed_tx.encoded_2718();
+        let tx_hash: [u8; 32] = (*signed_tx.tx_hash()).into();
+        let evm_tx = EvmOutboundTx {
+            nonce: signed_tx.nonce(),
+            max_fee_per_gas: signed_tx.max_fee_per_gas(),
+            max_priority_fee_per_gas: signed_tx.max_priority_fee_per_gas().unwrap_or_default(),
+            sent_at: now_ms,
+            replaced: Vec::new(),
+            tx: signed_tx.encoded_2718().into(),
+            nonce_used_checks: 0,
+            capped: false,
+        };
 
-        let _ = client
-            .send_raw_transaction(now_ms, Bytes::from(data).to_string())
+        // the signed transaction is kept even if it fails to be sent, its nonce is taken and
+        // it is re-broadcast or replaced later
+        if let Err(err) = client
+            .send_raw_transaction(now_ms, Bytes::from(evm_tx.tx.to_vec()).to_string())
             .await
-            .map_err(|err| format!("{chain}: {err}"))?;
-        Ok(BridgeTx::Evm(false, tx_hash.into()))
+        {
+            ic_cdk::api::debug_print(format!("{chain}: failed to send {tx_hash:?}: {err}"));
+        }
+        Ok((BridgeTx::Evm(false, tx_hash.into()), evm_tx))
     }
 
-    async fn from_svm(user: Principal, icp_amount: u128, now_ms: u64) -> Result<BridgeTx, String> {
-        let to_addr = STATE.with_borrow(|s| s.svm_address);
-        let (client, signed_tx) = build_spl_transfer_tx(&user, &to_addr, icp_amount, now_ms)
+    async fn from_svm(
+        token: &str,
+        user: Principal,
+        icp_amount: u128,
+        now_ms: u64,
+    ) -> Result<BridgeTx, String> {
+        let call = STATE.with_borrow(|s| {
+            if s.token(token)?.is_mint_burn("SOL") {
+                Ok::<_, String>(TokenCall::Burn)
+            } else {
+                Ok(TokenCall::Transfer(s.svm_address))
+            }
+        })?;
+        let (client, signed_tx) = build_spl_tx(token, &user, call, icp_amount, None, now_ms)
+            .await
+            .map_err(|err| format!("SOL: {err}"))?;
+        let tx_hash: [u8; 64] = signed_tx.signatures[0].into();
+        let data = bincode::serialize(&signed_tx).map_err(|err| format!("SOL: {err}"))?;
+
+        let _ = client
+            .send_transaction(now_ms, data.into(), true)
             .await
             .map_err(|err| format!("SOL: {err}"))?;
+        Ok(BridgeTx::Sol(false, tx_hash.into()))
+    }
+
+    // Returns None while all the durable nonce accounts are advanced by transactions in flight.
+    async fn to_svm(
+        token: &str,
+        to_addr: Pubkey,
+        icp_amount: u128,
+        now_ms: u64,
+    ) -> Result<Option<(BridgeTx, SvmOutboundTx)>, String> {
+        let call = STATE.with_borrow(|s| {
+            if s.token(token)?.is_mint_burn("SOL") {
+                Ok::<_, String>(TokenCall::Mint(to_addr))
+            } else {
+                Ok(TokenCall::Transfer(to_addr))
+            }
+        })?;
+        let Some(nonce_account) = reserve_svm_nonce_account() else {
+            return Ok(None);
+        };
+        let client = svm_client();
+        let (blockhash, last_valid_block_height) = match nonce_account {
+            Some(nonce_account) => {
+                let nonce = client
+                    .get_durable_nonce(now_ms, &nonce_account.to_string())
+                    .await
+                    .map_err(|err| format!("SOL: {err}"))?;
+                (SvmBlockhash::DurableNonce(nonce_account, nonce), 0)
+            }
+            None => {
+                let (hash, last_valid_block_height) = client
+                    .get_latest_blockhash_with_height(now_ms)
+                    .await
+                    .map_err(|err| {
+                        format!("SOL: failed to get latest blockhash, error: {}", err)
+                    })?;
+                (SvmBlockhash::Recent(hash), last_valid_block_height)
+            }
+        };
+        let (client, signed_tx) = build_spl_tx(
+            token,
+            &ic_cdk::api::canister_self(),
+            call,
+            icp_amount,
+            Some(blockhash),
+            now_ms,
+        )
+        .await
+        .map_err(|err| format!("SOL: {err}"))?;
+
         let tx_hash: [u8; 64] = signed_tx.signatures[0].into();
         let data = bincode::serialize(&signed_tx).map_err(|err| format!("SOL: {err}"))?;
+        let svm_tx = SvmOutboundTx {
+            nonce_account: nonce_account.map(|a| a.to_string()),
+            blockhash: signed_tx.message.recent_blockhash.to_bytes().into(),
+            last_valid_block_height,
+            tx: data.clone().into(),
+            sent_at: now_ms,
+        };
+
+        // the transaction may have been sent anyway, it is kept and re-broadcast
+        if let Err(err) = client.send_transaction(now_ms, data.into(), true).await {
+            ic_cdk::api::debug_print(format!("SOL: failed to send transaction: {err}"));
+        }
+        Ok(Some((BridgeTx::Sol(false, tx_hash.into()), svm_tx)))
+    }
+
+    // Reserves a durable nonce account that no outbound transaction in flight advances, Some(None)
+    // if the canister has no nonce account and transactions use a recent blockhash.
+    fn reserve_svm_nonce_account() -> Option<Option<Pubkey>> {
+        STATE.with_borrow_mut(|s| {
+            if s.svm_nonce_accounts.is_empty() {
+                return Some(None);
+            }
+
+            let in_flight: BTreeSet<&str> = s
+                .pending
+                .iter()
+                .filter_map(|t| t.pending_nonce_account())
+                .collect();
+            let nonce_account = s.svm_nonce_accounts.iter().copied().find(|a| {
+                !s.svm_nonces_reserved.contains(a) && !in_flight.contains(a.to_string().as_str())
+            })?;
+            s.svm_nonces_reserved.insert(nonce_account);
+            Some(Some(nonce_account))
+        })
+    }
+
+    // Creates a durable nonce account of the canister's SOL address, derived from it with a seed
+    // and funded by it. Outbound transactions can use it once the creation is finalized.
+    pub async fn create_svm_nonce_account(now_ms: u64) -> Result<Pubkey, String> {
+        let (key_name, from_addr) = STATE.with_borrow(|s| (s.key_name.clone(), s.svm_address));
+        let seed = format!("nonce-{now_ms}");
+        let nonce_account = Pubkey::create_with_seed(&from_addr, &seed, &SYSTEM_PROGRAM_ID)
+            .map_err(|err| format!("SOL: {err}"))?;
+        let ixs = instruction::create_nonce_account_with_seed(
+            &from_addr,
+            &nonce_account,
+            &from_addr,
+            &seed,
+            &from_addr,
+            SVM_NONCE_ACCOUNT_LAMPORTS,
+        );
 
+        let client = svm_client();
+        let block = client
+            .get_latest_blockhash(now_ms)
+            .await
+            .map_err(|err| format!("SOL: failed to get latest blockhash, error: {}", err))?;
+        let message = Message::new_with_blockhash(&ixs, Some(&from_addr), &block);
+        let msg = bincode::serialize(&message).map_err(|err| format!("SOL: {err}"))?;
+        let from = ic_cdk::api::canister_self();
+        let sig = sign_with_schnorr(key_name, vec![from.as_slice().to_vec()], msg, None).await?;
+        let signature: [u8; 64] = sig.try_into().map_err(|_| "invalid signature length")?;
+        let transaction = Transaction {
+            message,
+            signatures: vec![signature.into()],
+        };
+        let data = bincode::serialize(&transaction).map_err(|err| format!("SOL: {err}"))?;
+        // tracked before sending, the creation may land even if the sending fails
+        STATE.with_borrow_mut(|s| {
+            s.svm_nonce_accounts_pending
+                .push((nonce_account, signature.into(), now_ms))
+        });
+        ic_cdk_timers::set_timer(Duration::from_secs(30), check_svm_nonce_accounts());
         let _ = client
-            .send_transaction(now_ms, data.into(), true)
+            .send_transaction(now_ms, data.into(), false)
             .await
             .map_err(|err| format!("SOL: {err}"))?;
-        Ok(BridgeTx::Sol(false, tx_hash.into()))
+        Ok(nonce_account)
     }
 
-    async fn to_svm(to_addr: Pubkey, icp_amount: u128, now_ms: u64) -> Result<BridgeTx, String> {
-        // let to_addr = evm_address(&user);
-        let (client, signed_tx) =
-            build_spl_transfer_tx(&ic_cdk::api::canister_self(), &to_addr, icp_amount, now_ms)
-                .await
-                .map_err(|err| format!("SOL: {err}"))?;
+    // Adds the nonce accounts whose creation is finalized to the usable ones, and drops those
+    // whose creation failed or expired. Checked again while any creation is unresolved.
+    pub async fn check_svm_nonce_accounts() {
+        let pending = STATE.with_borrow(|s| s.svm_nonce_accounts_pending.clone());
+        if pending.is_empty() {
+            return;
+        }
 
-        let tx_hash: [u8; 64] = signed_tx.signatures[0].into();
-        let data = bincode::serialize(&signed_tx).map_err(|err| format!("SOL: {err}"))?;
+        let now_ms = ic_cdk::api::time() / 1_000_000;
+        let rt = futures::future::join_all(
+            pending
+                .iter()
+                .map(|(_, sig, _)| check_sol_tx_finalized(sig, now_ms)),
+        )
+        .await;
 
-        let _ = client
-            .send_transaction(now_ms, data.into(), true)
-            .await
-            .map_err(|err| format!("SOL: {err}"))?;
-        Ok(BridgeTx::Sol(false, tx_hash.into()))
+        let unresolved = STATE.with_borrow_mut(|s| {
+            for ((nonce_account, _, sent_at), r) in pending.iter().zip(rt) {
+                let resolved = match r {
+                    Ok(Some(status)) if status.is_error() => {
+                        ic_cdk::api::debug_print(format!(
+                            "SOL: nonce account {nonce_account} creation failed: {:?}",
+                            status.err
+                        ));
+                        true
+                    }
+                    Ok(Some(status)) if status.is_finalized() => {
+                        if !s.svm_nonce_accounts.contains(nonce_account) {
+                            s.svm_nonce_accounts.push(*nonce_account);
+                        }
+                        true
+                    }
+                    Ok(None) => now_ms > sent_at + SVM_NONCE_ACCOUNT_TIMEOUT_MS,
+                    _ => false,
+                };
+                if resolved {
+                    s.svm_nonce_accounts_pending
+                        .retain(|(a, _, _)| a != nonce_account);
+                }
+            }
+            !s.svm_nonce_accounts_pending.is_empty()
+        });
+        if unresolved {
+            ic_cdk_timers::set_timer(Duration::from_secs(30), check_svm_nonce_accounts());
+        }
     }
 
     pub async fn build_erc20_transfer_tx(
+        token: &str,
         chain: &str,
         from: &Principal,
         to_addr: &Address,
         icp_amount: u128,
         now_ms: u64,
-    ) -> Result<(EvmClient<DefaultHttpOutcall>, Signed<TxEip1559>), String> {
-        let (key_name, from_pk, mut tx, gas_updated_at) = STATE.with_borrow(|s| {
-            let (contract, decimals, chain_id) = s
-                .evm_token_contracts
+    ) -> Result<(EvmClient<RpcOutcall>, TxEnvelope), String> {
+        build_erc20_tx(
+            token,
+            chain,
+            from,
+            TokenCall::Transfer(*to_addr),
+            icp_amount,
+            None,
+            now_ms,
+        )
+        .await
+    }
+
+    pub async fn build_erc20_tx(
+        token: &str,
+        chain: &str,
+        from: &Principal,
+        call: TokenCall<Address>,
+        icp_amount: u128,
+        replacing: Option<&EvmOutboundTx>,
+        now_ms: u64,
+    ) -> Result<(EvmClient<RpcOutcall>, TxEnvelope), String> {
+        let (key_name, from_pk, mut tx) = STATE.with_borrow(|s| {
+            let t = s.token(token)?;
+            let (contract, decimals, chain_id) = t
+                .evm_contracts
                 .get(chain)
                 .cloned()
-                .ok_or_else(|| format!("chain {chain} not found"))?;
+                .ok_or_else(|| format!("chain {chain} not found for token {token}"))?;
 
-            let value = convert_amount(icp_amount, s.token_decimals, decimals)?;
+            let value = convert_amount(icp_amount, t.decimals, decimals)?;
             let from_pk = derive_public_key(&s.ecdsa_public_key, vec![from.as_slice().to_vec()])
                 .map_err(|_e| format!("{chain}: derive_public_key failed"))?;
 
-            let input = encode_erc20_transfer(to_addr, value);
-            let (gas_updated_at, gas_price, max_priority_fee_per_gas) =
-                s.evm_latest_gas.get(chain).cloned().unwrap_or_default();
-            let max_priority_fee_per_gas = max_priority_fee_per_gas + max_priority_fee_per_gas / 5;
+            let input = match &call {
+                TokenCall::Transfer(to_addr) => encode_erc20_transfer(to_addr, value),
+                TokenCall::Mint(to_addr) => encode_erc20_mint(to_addr, value),
+                TokenCall::Burn => encode_erc20_burn(value),
+            };
             Ok::<_, String>((
                 s.key_name.clone(),
                 from_pk,
                 TxEip1559 {
                     chain_id,
                     nonce: 0u64,
-                    gas_limit: 84_000u64, // sample: ~53,696
-                    max_fee_per_gas: gas_price * 2 + max_priority_fee_per_gas,
-                    max_priority_fee_per_gas,
+                    gas_limit: 0, // estimated
                     to: contract.into(),
                     input: input.into(),
                     ..Default::default()
                 },
-                gas_updated_at,
             ))
         })?;
 
         let from_addr = from_pk.to_evm_adress()?;
-        if &from_addr == to_addr {
+        if call == TokenCall::Transfer(from_addr) {
             return Err("from and to cannot be the same".to_string());
         }
 
         let client = evm_client(chain);
-        if gas_updated_at + 120_000 >= now_ms {
-            let nonce = client.get_transaction_count(now_ms, &from_addr).await?;
-            tx.nonce = nonce;
+        (tx.nonce, tx.max_fee_per_gas, tx.max_priority_fee_per_gas) =
+            evm_nonce_and_fees(&client, chain, &from_addr, now_ms).await?;
+
+        // only the estimates of the canister's own transactions are quoted by the gas surcharge
+        let estimate_key = STATE.with_borrow(|s| {
+            (*from == s.icp_address).then(|| evm_gas_estimate_key(chain, token, &call))
+        });
+        tx.gas_limit =
+            estimate_gas_limit(&client, chain, &from_addr, &tx, estimate_key, now_ms).await?;
+        match replacing {
+            // a replacement must raise both fees, by 12.5% here
+            Some(prev) => {
+                tx.nonce = prev.nonce;
+                tx.max_priority_fee_per_gas = tx
+                    .max_priority_fee_per_gas
+                    .max(prev.max_priority_fee_per_gas * 9 / 8 + 1);
+                tx.max_fee_per_gas = tx
+                    .max_fee_per_gas
+                    .max(prev.max_fee_per_gas * 9 / 8 + 1)
+                    .max(tx.max_priority_fee_per_gas);
+                if let Some(max) = STATE.with_borrow(|s| s.evm_max_fees.get(chain).copied()) {
+                    tx.max_fee_per_gas = tx
+                        .max_fee_per_gas
+                        .min(max)
+                        .max(prev.max_fee_per_gas * 9 / 8 + 1);
+                    tx.max_priority_fee_per_gas =
+                        tx.max_priority_fee_per_gas.min(tx.max_fee_per_gas);
+                }
+            }
+            None => tx.nonce = assign_evm_nonce(chain, from, tx.nonce),
+        }
+        let signed_tx = sign_evm_tx(key_name, chain, from, &from_pk, tx).await?;
+        Ok((client, signed_tx))
+    }
+
+    // Fetches the gas price (and the max priority fee of EIP-1559 chains) of a chain into
+    // `evm_latest_gas`.
+    async fn refresh_evm_gas(
+        client: &EvmClient<RpcOutcall>,
+        chain: &str,
+        tx_type: EvmTxType,
+        now_ms: u64,
+    ) -> Result<(u128, u128), String> {
+        let (gas_price, max_priority_fee_per_gas) = match tx_type {
+            EvmTxType::Eip1559 => {
+                futures::future::try_join(
+                    client.gas_price(now_ms),
+                    client.max_priority_fee_per_gas(now_ms),
+                )
+                .await?
+            }
+            EvmTxType::Legacy | EvmTxType::Eip2930 => (client.gas_price(now_ms).await?, 0),
+        };
+        STATE.with_borrow_mut(|s| {
+            s.evm_latest_gas.insert(
+                chain.to_string(),
+                (now_ms, gas_price, max_priority_fee_per_gas),
+            );
+        });
+        Ok((gas_price, max_priority_fee_per_gas))
+    }
+
+    // Fetches the gas price of an EVM chain if it is not known yet, the bridge fee can not be
+    // computed without it.
+    async fn ensure_evm_gas(chain: &str, now_ms: u64) {
+        let tx_type = STATE.with_borrow(|s| {
+            if chain == "ICP"
+                || chain == "SOL"
+                || s.evm_latest_gas.get(chain).is_some_and(|(_, p, _)| *p > 0)
+            {
+                None
+            } else {
+                Some(s.evm_tx_types.get(chain).copied().unwrap_or_default())
+            }
+        });
+        if let Some(tx_type) = tx_type
+            && let Err(err) = refresh_evm_gas(&evm_client(chain), chain, tx_type, now_ms).await
+        {
+            ic_cdk::api::debug_print(format!("{chain}: failed to fetch the gas price: {err}"));
+        }
+    }
+
+    // Fetches the nonce of an address and the fees of a transaction on a chain, as
+    // (nonce, max_fee_per_gas, max_priority_fee_per_gas). Gas prices are cached for 2 minutes.
+    // For legacy and EIP-2930 transactions, max_fee_per_gas is the gas price and
+    // `eth_maxPriorityFeePerGas` is not called.
+    async fn evm_nonce_and_fees(
+        client: &EvmClient<RpcOutcall>,
+        chain: &str,
+        from_addr: &Address,
+        now_ms: u64,
+    ) -> Result<(u64, u128, u128), String> {
+        let (tx_type, (gas_updated_at, gas_price, max_priority_fee_per_gas)) =
+            STATE.with_borrow(|s| {
+                (
+                    s.evm_tx_types.get(chain).copied().unwrap_or_default(),
+                    s.evm_latest_gas.get(chain).cloned().unwrap_or_default(),
+                )
+            });
+
+        let (nonce, gas_price, max_priority_fee_per_gas) = if gas_updated_at + 120_000 >= now_ms {
+            let nonce = client
+                .get_transaction_count(now_ms, from_addr, "pending")
+                .await?;
+            (nonce, gas_price, max_priority_fee_per_gas)
         } else {
-            let (nonce, gas_price, max_priority_fee_per_gas) = futures::future::try_join3(
-                client.get_transaction_count(now_ms, &from_addr),
-                client.gas_price(now_ms),
-                client.max_priority_fee_per_gas(now_ms),
+            let (nonce, (gas_price, max_priority_fee_per_gas)) = futures::future::try_join(
+                client.get_transaction_count(now_ms, from_addr, "pending"),
+                refresh_evm_gas(client, chain, tx_type, now_ms),
             )
             .await?;
-            tx.nonce = nonce;
-            tx.max_priority_fee_per_gas = max_priority_fee_per_gas + max_priority_fee_per_gas / 5;
-            tx.max_fee_per_gas = gas_price * 2 + tx.max_priority_fee_per_gas;
-            STATE.with_borrow_mut(|s| {
-                s.evm_latest_gas.insert(
-                    chain.to_string(),
-                    (now_ms, gas_price, max_priority_fee_per_gas),
-                );
-            })
+            (nonce, gas_price, max_priority_fee_per_gas)
+        };
+
+        match tx_type {
+            EvmTxType::Eip1559 => {
+                let max_priority_fee_per_gas =
+                    max_priority_fee_per_gas + max_priority_fee_per_gas / 5;
+                Ok((
+                    nonce,
+                    gas_price * 2 + max_priority_fee_per_gas,
+                    max_priority_fee_per_gas,
+                ))
+            }
+            EvmTxType::Legacy | EvmTxType::Eip2930 => Ok((nonce, gas_price + gas_price / 5, 0)),
+        }
+    }
+
+    // Signs a transaction built as EIP-1559 with the transaction type of the chain. Legacy and
+    // EIP-2930 transactions pay max_fee_per_gas as their gas price.
+    async fn sign_evm_tx(
+        key_name: String,
+        chain: &str,
+        from: &Principal,
+        from_pk: &PublicKeyOutput,
+        tx: TxEip1559,
+    ) -> Result<TxEnvelope, String> {
+        let tx_type = STATE.with_borrow(|s| s.evm_tx_types.get(chain).copied().unwrap_or_default());
+        match tx_type {
+            EvmTxType::Legacy => {
+                let tx = TxLegacy {
+                    chain_id: Some(tx.chain_id), // EIP-155
+                    nonce: tx.nonce,
+                    gas_price: tx.max_fee_per_gas,
+                    gas_limit: tx.gas_limit,
+                    to: tx.to,
+                    value: tx.value,
+                    input: tx.input,
+                };
+                Ok(sign_tx(key_name, from, from_pk, tx).await?.into())
+            }
+            EvmTxType::Eip2930 => {
+                let tx = TxEip2930 {
+                    chain_id: tx.chain_id,
+                    nonce: tx.nonce,
+                    gas_price: tx.max_fee_per_gas,
+                    gas_limit: tx.gas_limit,
+                    to: tx.to,
+                    value: tx.value,
+                    access_list: tx.access_list,
+                    input: tx.input,
+                };
+                Ok(sign_tx(key_name, from, from_pk, tx).await?.into())
+            }
+            EvmTxType::Eip1559 => Ok(sign_tx(key_name, from, from_pk, tx).await?.into()),
         }
+    }
 
+    async fn sign_tx<T: SignableTransaction<Signature>>(
+        key_name: String,
+        from: &Principal,
+        from_pk: &PublicKeyOutput,
+        tx: T,
+    ) -> Result<Signed<T>, String> {
         let msg_hash = tx.signature_hash();
         let sig =
             sign_with_ecdsa(key_name, vec![from.as_slice().to_vec()], msg_hash.to_vec()).await?;
@@ -1260,8 +4536,85 @@ pub mod state {
             y_parity(msg_hash.as_slice(), &sig, from_pk.public_key.as_slice())?,
         );
 
-        let signed_tx = tx.into_signed(signature);
-        Ok((client, signed_tx))
+        Ok(tx.into_signed(signature))
+    }
+
+    fn evm_gas_estimate_key<A>(chain: &str, token: &str, call: &TokenCall<A>) -> String {
+        format!("{chain}/{token}/{}", call.name())
+    }
+
+    // Estimates the gas limit of a transaction with the chain's safety multiplier and cap,
+    // so that a transaction that would revert fails before it is signed. The estimate is
+    // recorded under `estimate_key` if given.
+    async fn estimate_gas_limit(
+        client: &EvmClient<RpcOutcall>,
+        chain: &str,
+        from: &Address,
+        tx: &TxEip1559,
+        estimate_key: Option<String>,
+        now_ms: u64,
+    ) -> Result<u64, String> {
+        let to = tx
+            .to
+            .to()
+            .copied()
+            .ok_or_else(|| "missing transaction recipient".to_string())?;
+        let value: u128 = tx
+            .value
+            .try_into()
+            .map_err(|_| "invalid amount".to_string())?;
+        let estimated = client
+            .estimate_gas(now_ms, from, &to, tx.input.to_string(), value)
+            .await
+            .map_err(|err| format!("transaction would revert, gas estimation failed: {err}"))?;
+        STATE.with_borrow_mut(|s| {
+            if let Some(key) = estimate_key {
+                s.evm_gas_estimates.insert(key, estimated);
+            }
+            s.evm_gas_limits
+                .get(chain)
+                .cloned()
+                .unwrap_or_default()
+                .gas_limit(estimated)
+        })
+    }
+
+    // Assigns the nonce of a transaction, given the nonce of the sender on the chain. The
+    // transactions of the canister's own EVM address get locally tracked nonces, so that
+    // many of them can be sent to a chain in one round.
+    fn assign_evm_nonce(chain: &str, from: &Principal, chain_nonce: u64) -> u64 {
+        STATE.with_borrow_mut(|s| {
+            if *from != s.icp_address {
+                return chain_nonce;
+            }
+
+            let mut in_flight: BTreeSet<u64> = s
+                .pending
+                .iter()
+                .chain(s.dead_letters.iter())
+                .filter_map(|t| t.pending_nonce(chain))
+                .collect();
+            if let Some(reserved) = s.evm_nonces_reserved.get(chain) {
+                in_flight.extend(reserved);
+            }
+
+            let next = s
+                .evm_nonces
+                .get(chain)
+                .copied()
+                .unwrap_or_default()
+                .max(chain_nonce);
+            // fill the gaps left by transactions that failed to be sent or were abandoned
+            let nonce = (chain_nonce..next)
+                .find(|n| !in_flight.contains(n))
+                .unwrap_or(next);
+            s.evm_nonces.insert(chain.to_string(), next.max(nonce + 1));
+            s.evm_nonces_reserved
+                .entry(chain.to_string())
+                .or_default()
+                .insert(nonce);
+            nonce
+        })
     }
 
     pub async fn build_evm_transfer_tx(
@@ -1270,35 +4623,27 @@ pub mod state {
         to_addr: &Address,
         amount: u128,
         now_ms: u64,
-    ) -> Result<(EvmClient<DefaultHttpOutcall>, Signed<TxEip1559>), String> {
-        let (key_name, from_pk, mut tx, gas_updated_at) = STATE.with_borrow(|s| {
+    ) -> Result<(EvmClient<RpcOutcall>, TxEnvelope), String> {
+        let (key_name, from_pk, mut tx) = STATE.with_borrow(|s| {
             let chain_id = s
-                .evm_token_contracts
-                .get(chain)
-                .map(|(_, _, chain_id)| *chain_id)
+                .evm_chain_id(chain)
                 .ok_or_else(|| "chain not found".to_string())?;
 
             let from_pk = derive_public_key(&s.ecdsa_public_key, vec![from.as_slice().to_vec()])
                 .expect("derive_public_key failed");
-            let (gas_updated_at, gas_price, max_priority_fee_per_gas) =
-                s.evm_latest_gas.get(chain).cloned().unwrap_or_default();
-            let max_priority_fee_per_gas = max_priority_fee_per_gas + max_priority_fee_per_gas / 5;
             Ok::<_, String>((
                 s.key_name.clone(),
                 from_pk,
                 TxEip1559 {
                     chain_id,
                     nonce: 0u64,
-                    gas_limit: 32_000u64, // sample: ~21,000
-                    max_fee_per_gas: gas_price * 2 + max_priority_fee_per_gas,
-                    max_priority_fee_per_gas,
+                    gas_limit: 0, // estimated
                     to: (*to_addr).into(),
                     value: amount
                         .try_into()
                         .map_err(|_| "invalid amount".to_string())?,
                     ..Default::default()
                 },
-                gas_updated_at,
             ))
         })?;
 
@@ -1308,45 +4653,21 @@ pub mod state {
         }
 
         let client = evm_client(chain);
-        if gas_updated_at + 120_000 >= now_ms {
-            let nonce = client.get_transaction_count(now_ms, &from_addr).await?;
-            tx.nonce = nonce;
-        } else {
-            let (nonce, gas_price, max_priority_fee_per_gas) = futures::future::try_join3(
-                client.get_transaction_count(now_ms, &from_addr),
-                client.gas_price(now_ms),
-                client.max_priority_fee_per_gas(now_ms),
-            )
-            .await?;
-            tx.nonce = nonce;
-            tx.max_priority_fee_per_gas = max_priority_fee_per_gas + max_priority_fee_per_gas / 5;
-            tx.max_fee_per_gas = gas_price * 2 + tx.max_priority_fee_per_gas;
-            STATE.with_borrow_mut(|s| {
-                s.evm_latest_gas.insert(
-                    chain.to_string(),
-                    (now_ms, gas_price, max_priority_fee_per_gas),
-                );
-            })
-        }
-
-        let msg_hash = tx.signature_hash();
-        let sig =
-            sign_with_ecdsa(key_name, vec![from.as_slice().to_vec()], msg_hash.to_vec()).await?;
-        let signature = Signature::new(
-            U256::from_be_slice(&sig[0..32]),  // r
-            U256::from_be_slice(&sig[32..64]), // s
-            y_parity(msg_hash.as_slice(), &sig, from_pk.public_key.as_slice())?,
-        );
+        (tx.nonce, tx.max_fee_per_gas, tx.max_priority_fee_per_gas) =
+            evm_nonce_and_fees(&client, chain, &from_addr, now_ms).await?;
 
-        let signed_tx = tx.into_signed(signature);
+        tx.gas_limit = estimate_gas_limit(&client, chain, &from_addr, &tx, None, now_ms).await?;
+        tx.nonce = assign_evm_nonce(chain, from, tx.nonce);
+        let signed_tx = sign_evm_tx(key_name, chain, from, &from_pk, tx).await?;
         Ok((client, signed_tx))
     }
 
     async fn check_evm_tx_finalized(
         chain: &str,
         tx_hash: &TxHash,
+        expected: &Erc20TransferLog,
         now_ms: u64,
-    ) -> Result<bool, String> {
+    ) -> Result<bool, TaskError> {
         let client = evm_client(chain);
         let (latest_block, receipt) = futures::future::join(
             client.block_number(now_ms),
@@ -1359,19 +4680,29 @@ pub mod state {
                     && *tx_hash == receipt.transaction_hash
                     && latest >= block_number + client.max_confirmations
                 {
-                    // We don't need to check the logs here.
-                    // log.address == 代币合约地址
-                    // log.topics[0] == keccak256("Transfer(address,address,uint256)") = 0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef
-                    // log.topics[1] == from 地址（32 字节左填充）
-                    // log.topics[2] == to 地址（32 字节左填充）
-                    // log.data 为 uint256 的转账数量（ABI 编码）
-                    return Ok(receipt.status());
+                    if !receipt.status() {
+                        return Err(TaskError::Refund(format!("{chain}: tx {tx_hash} reverted")));
+                    }
+                    // the receipt must carry the exact transfer, otherwise the provider
+                    // returned a receipt for a wrong transaction
+                    if !receipt
+                        .inner
+                        .logs()
+                        .iter()
+                        .filter_map(decode_erc20_transfer_log)
+                        .any(|log| &log == expected)
+                    {
+                        return Err(TaskError::Fatal(format!(
+                            "{chain}: transfer log mismatch in tx {tx_hash}, expected {expected:?}"
+                        )));
+                    }
+                    return Ok(true);
                 }
                 Ok(false)
             }
-            (Err(err), _) | (_, Err(err)) => Err(format!(
+            (Err(err), _) | (_, Err(err)) => Err(TaskError::Retry(format!(
                 "{chain}: failed to check evm tx finalized, error: {err}"
-            )),
+            ))),
             _ => Ok(false),
         }
     }
@@ -1390,15 +4721,39 @@ pub mod state {
     }
 
     pub async fn build_spl_transfer_tx(
+        token: &str,
         from: &Principal,
         to_addr: &Pubkey,
         icp_amount: u128,
         now_ms: u64,
+    ) -> Result<(SvmClient<DefaultHttpOutcall>, Transaction), String> {
+        build_spl_tx(
+            token,
+            from,
+            TokenCall::Transfer(*to_addr),
+            icp_amount,
+            None,
+            now_ms,
+        )
+        .await
+    }
+
+    // Builds a signed SPL token transaction, with the latest blockhash if none is given.
+    pub async fn build_spl_tx(
+        token: &str,
+        from: &Principal,
+        call: TokenCall<Pubkey>,
+        icp_amount: u128,
+        blockhash: Option<SvmBlockhash>,
+        now_ms: u64,
     ) -> Result<(SvmClient<DefaultHttpOutcall>, Transaction), String> {
         let (key_name, from_addr, ixs) = STATE.with_borrow(|s| {
-            let (mint_pubkey, decimals, token_program_id) = s.svm_token_address;
+            let t = s.token(token)?;
+            let (mint_pubkey, decimals, token_program_id) = t
+                .svm_contract
+                .ok_or_else(|| format!("SOL is not supported for token {token}"))?;
 
-            let amount = convert_amount(icp_amount, s.token_decimals, decimals)?;
+            let amount = convert_amount(icp_amount, t.decimals, decimals)?;
             let amount: u64 = amount
                 .try_into()
                 .map_err(|_| format!("amount is too large: {}", amount))?;
@@ -1409,38 +4764,87 @@ pub mod state {
             )
             .map_err(|e| format!("derive_schnorr_public_key failed: {e}"))?;
             let from_addr = from_pk.to_svm_pubkey()?;
-            if &from_addr == to_addr {
+            if call == TokenCall::Transfer(from_addr) {
                 return Err("from and to cannot be the same".to_string());
             }
 
             let from_pubkey =
                 get_associated_token_address(&from_addr, &mint_pubkey, &token_program_id);
-            let to_pubkey = get_associated_token_address(to_addr, &mint_pubkey, &token_program_id);
-            let ix0 = create_associated_token_account_idempotent(
-                &from_addr,
-                to_addr,
-                &mint_pubkey,
-                &token_program_id,
-            );
-            let ix = transfer_checked_instruction(
-                &token_program_id,
-                &from_pubkey,
-                &mint_pubkey,
-                &to_pubkey,
-                &from_addr,
-                &[],
-                amount,
-                decimals,
-            );
+            let ixs = match &call {
+                TokenCall::Transfer(to_addr) => {
+                    let to_pubkey =
+                        get_associated_token_address(to_addr, &mint_pubkey, &token_program_id);
+                    vec![
+                        create_associated_token_account_idempotent(
+                            &from_addr,
+                            to_addr,
+                            &mint_pubkey,
+                            &token_program_id,
+                        ),
+                        transfer_checked_instruction(
+                            &token_program_id,
+                            &from_pubkey,
+                            &mint_pubkey,
+                            &to_pubkey,
+                            &from_addr,
+                            &[],
+                            amount,
+                            decimals,
+                        ),
+                    ]
+                }
+                TokenCall::Mint(to_addr) => {
+                    let to_pubkey =
+                        get_associated_token_address(to_addr, &mint_pubkey, &token_program_id);
+                    vec![
+                        create_associated_token_account_idempotent(
+                            &from_addr,
+                            to_addr,
+                            &mint_pubkey,
+                            &token_program_id,
+                        ),
+                        mint_to_checked_instruction(
+                            &token_program_id,
+                            &mint_pubkey,
+                            &to_pubkey,
+                            &from_addr,
+                            &[],
+                            amount,
+                            decimals,
+                        ),
+                    ]
+                }
+                TokenCall::Burn => vec![burn_checked_instruction(
+                    &token_program_id,
+                    &from_pubkey,
+                    &mint_pubkey,
+                    &from_addr,
+                    &[],
+                    amount,
+                    decimals,
+                )],
+            };
 
-            Ok::<_, String>((s.key_name.clone(), from_addr, vec![ix0, ix]))
+            Ok::<_, String>((s.key_name.clone(), from_addr, ixs))
         })?;
 
         let client = svm_client();
-        let block = client
-            .get_latest_blockhash(now_ms)
-            .await
-            .map_err(|err| format!("SOL: failed to get latest blockhash, error: {}", err))?;
+        let mut ixs = with_compute_budget(&client, ixs, now_ms).await?;
+        let block = match blockhash {
+            Some(SvmBlockhash::Recent(hash)) => hash,
+            Some(SvmBlockhash::DurableNonce(nonce_account, nonce)) => {
+                // the nonce must be advanced by the first instruction
+                ixs.insert(
+                    0,
+                    instruction::advance_nonce_account(&nonce_account, &from_addr),
+                );
+                nonce
+            }
+            None => client
+                .get_latest_blockhash(now_ms)
+                .await
+                .map_err(|err| format!("SOL: failed to get latest blockhash, error: {}", err))?,
+        };
 
         let message = Message::new_with_blockhash(&ixs, Some(&from_addr), &block);
         let msg = bincode::serialize(&message).map_err(|err| format!("SOL: {err}"))?;
@@ -1454,6 +4858,42 @@ pub mod state {
         Ok((client, transaction))
     }
 
+    // Prepends the ComputeBudget instructions to a Solana transaction. The compute unit price
+    // is the configured percentile of the recent prioritization fees on the accounts that the
+    // transaction writes, capped.
+    async fn with_compute_budget(
+        client: &SvmClient<DefaultHttpOutcall>,
+        ixs: Vec<Instruction>,
+        now_ms: u64,
+    ) -> Result<Vec<Instruction>, String> {
+        let config = STATE.with_borrow(|s| s.svm_priority_fee.clone());
+        let mut budget = vec![set_compute_unit_limit_instruction(
+            config.compute_unit_limit,
+        )];
+        if config.max_micro_lamports > 0 {
+            let mut accounts: Vec<String> = Vec::new();
+            for meta in ixs.iter().flat_map(|ix| ix.accounts.iter()) {
+                let account = meta.pubkey.to_string();
+                if meta.is_writable && !accounts.contains(&account) {
+                    accounts.push(account);
+                }
+            }
+            let fees = client
+                .get_recent_prioritization_fees(now_ms, &accounts)
+                .await
+                .map_err(|err| format!("SOL: failed to get prioritization fees, error: {err}"))?;
+            let price = prioritization_fee_percentile(&fees, config.percentile)
+                .min(config.max_micro_lamports);
+            STATE.with_borrow_mut(|s| s.svm_latest_priority_fee = (now_ms, price));
+            if price > 0 {
+                budget.push(set_compute_unit_price_instruction(price));
+            }
+        }
+
+        budget.extend(ixs);
+        Ok(budget)
+    }
+
     pub async fn build_sol_transfer_tx(
         from: &Principal,
         to_addr: &Pubkey,
@@ -1477,6 +4917,7 @@ pub mod state {
         })?;
 
         let client = svm_client();
+        let ixs = with_compute_budget(&client, ixs, now_ms).await?;
         let block = client
             .get_latest_blockhash(now_ms)
             .await
@@ -1493,6 +4934,452 @@ pub mod state {
 
         Ok((client, transaction))
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const WINDOW_MS: u64 = 24_000; // buckets of 1s
+
+        fn test_token() -> Token {
+            let mut t = Token::new(
+                "Panda".to_string(),
+                "PANDA".to_string(),
+                8,
+                String::new(),
+                Principal::anonymous(),
+                0,
+                0,
+            );
+            t.evm_contracts
+                .insert("ETH".to_string(), (Address::ZERO, 18, 1));
+            t.evm_contracts
+                .insert("BNB".to_string(), (Address::ZERO, 8, 56));
+            t.svm_contract = Some((Pubkey::default(), 6, Pubkey::default()));
+            t
+        }
+
+        fn init_token(rate_limit: Option<RateLimit>) {
+            let mut s = State::with_icp_address(Principal::management_canister());
+            let mut t = test_token();
+            t.rate_limit = rate_limit;
+            s.tokens.insert(t.symbol.clone(), t);
+            STATE.set(s);
+        }
+
+        fn test_task(from: BridgeTarget, to: BridgeTarget, icp_amount: u128) -> BridgeLog {
+            BridgeLog {
+                id: None,
+                user: Principal::anonymous(),
+                token: "PANDA".to_string(),
+                from,
+                to,
+                icp_amount,
+                fee: 0,
+                from_tx: BridgeTx::Icp(true, 1),
+                to_tx: None,
+                to_addr: None,
+                from_addr: None,
+                from_log_index: None,
+                created_at: 0,
+                finalized_at: 0,
+                error: None,
+                failed: false,
+                retries: 0,
+                refund: None,
+                refund_tx: None,
+                errors: 0,
+                next_retry_at: 0,
+                release_at: 0,
+                fee_detail: None,
+                dust: 0,
+                evm_tx: None,
+                svm_tx: None,
+            }
+        }
+
+        #[test]
+        fn test_split_dust() {
+            let t = test_token();
+
+            // exact on chains of the same or more decimals
+            assert_eq!(
+                t.split_dust(&BridgeTarget::Icp, 123_456_789),
+                Ok((123_456_789, 0))
+            );
+            assert_eq!(
+                t.split_dust(&BridgeTarget::Evm("ETH".to_string()), 123_456_789),
+                Ok((123_456_789, 0))
+            );
+            assert_eq!(
+                t.split_dust(&BridgeTarget::Evm("BNB".to_string()), 123_456_789),
+                Ok((123_456_789, 0))
+            );
+            // truncated on chains of less decimals
+            assert_eq!(
+                t.split_dust(&BridgeTarget::Sol, 123_456_789),
+                Ok((123_456_700, 89))
+            );
+            assert_eq!(t.split_dust(&BridgeTarget::Sol, 99), Ok((0, 99)));
+            assert_eq!(t.split_dust(&BridgeTarget::Sol, 100), Ok((100, 0)));
+            // chains without a contract use the token's decimals
+            assert_eq!(
+                t.split_dust(&BridgeTarget::Evm("ARB".to_string()), 123_456_789),
+                Ok((123_456_789, 0))
+            );
+        }
+
+        #[test]
+        fn test_bridge_fee_gas() {
+            let mut s = State::with_icp_address(Principal::management_canister());
+            let mut t = test_token();
+            t.fee_schedules.insert(
+                "ETH".to_string(),
+                FeeSchedule {
+                    flat: 0,
+                    bps: 0,
+                    min: 0,
+                    max: 0,
+                    native_price: 10u128.pow(18), // 1 token unit per wei
+                },
+            );
+            let eth = BridgeTarget::Evm("ETH".to_string());
+
+            assert!(bridge_fee(&s, &t, &eth, 1_000).is_err());
+            s.evm_latest_gas.insert("ETH".to_string(), (0, 10, 1));
+            // the cap of the gas limit until estimated
+            assert_eq!(bridge_fee(&s, &t, &eth, 1_000).unwrap().gas, 500_000 * 11);
+
+            s.evm_gas_estimates
+                .insert("ETH/PANDA/transfer".to_string(), 50_000);
+            s.evm_gas_estimates
+                .insert("ETH/PANDA/mint".to_string(), 60_000);
+            s.evm_gas_estimates
+                .insert("ETH/DOGE/transfer".to_string(), 21_000);
+            assert_eq!(bridge_fee(&s, &t, &eth, 1_000).unwrap().gas, 65_000 * 11);
+            t.mint_burn_chains.insert("ETH".to_string());
+            assert_eq!(bridge_fee(&s, &t, &eth, 1_000).unwrap().gas, 78_000 * 11);
+        }
+
+        #[test]
+        fn test_bridge_log_same_with() {
+            let mut task = test_task(BridgeTarget::Evm("ETH".to_string()), BridgeTarget::Icp, 100);
+            task.from_tx = BridgeTx::Evm(false, [1u8; 32].into());
+            task.from_log_index = Some(3);
+            let mut other = task.clone();
+            other.from_tx = BridgeTx::Evm(true, [1u8; 32].into());
+            assert!(task.same_with(&other));
+
+            // deposits of the same transaction
+            other.from_log_index = Some(4);
+            assert!(!task.same_with(&other));
+            other.from_log_index = None;
+            assert!(!task.same_with(&other));
+        }
+
+        #[test]
+        fn test_find_task() {
+            let mut s = State::with_icp_address(Principal::management_canister());
+            let from_tx = BridgeTx::Evm(true, [1u8; 32].into());
+            for (index, amount) in [(3, 100), (4, 200)] {
+                let mut task = test_task(
+                    BridgeTarget::Evm("ETH".to_string()),
+                    BridgeTarget::Icp,
+                    amount,
+                );
+                task.from_tx = from_tx.clone();
+                task.from_log_index = Some(index);
+                s.pending.push_back(task);
+            }
+            let task = s.pending.pop_back().unwrap();
+            s.dead_letters.push(task);
+
+            let err = find_task(&s, "PANDA", &from_tx, None).err().unwrap();
+            assert!(err.contains("ambiguous"), "{err}");
+            assert_eq!(
+                find_task(&s, "PANDA", &from_tx, Some(4))
+                    .unwrap()
+                    .icp_amount,
+                200
+            );
+            assert!(find_task(&s, "PANDA", &from_tx, Some(5)).is_err());
+            assert!(find_task(&s, "DOGE", &from_tx, Some(3)).is_err());
+
+            let task = take_task(&mut s, "PANDA", &from_tx, Some(4)).unwrap();
+            assert_eq!(task.icp_amount, 200);
+            assert!(s.dead_letters.is_empty());
+            // the only task left is found without the log index
+            assert_eq!(
+                find_task(&s, "PANDA", &from_tx, None).unwrap().icp_amount,
+                100
+            );
+        }
+
+        #[test]
+        fn test_assign_evm_nonce() {
+            init_token(None);
+            let canister = Principal::management_canister();
+            let user = Principal::from_slice(&[1]);
+
+            // the chain nonce of a user's address is used as is
+            assert_eq!(assign_evm_nonce("ETH", &user, 7), 7);
+            assert_eq!(assign_evm_nonce("ETH", &user, 7), 7);
+
+            assert_eq!(assign_evm_nonce("ETH", &canister, 5), 5);
+            assert_eq!(assign_evm_nonce("ETH", &canister, 5), 6);
+            assert_eq!(assign_evm_nonce("ETH", &canister, 5), 7);
+            assert_eq!(assign_evm_nonce("BNB", &canister, 0), 0);
+            STATE.with_borrow(|s| {
+                assert_eq!(s.evm_nonces.get("ETH"), Some(&8));
+                assert_eq!(
+                    s.evm_nonces_reserved.get("ETH"),
+                    Some(&BTreeSet::from([5, 6, 7]))
+                );
+            });
+
+            // after the round, only the nonce of the pending task 6 is in flight
+            STATE.with_borrow_mut(|s| {
+                s.evm_nonces_reserved.clear();
+                let mut task =
+                    test_task(BridgeTarget::Icp, BridgeTarget::Evm("ETH".to_string()), 100);
+                task.to_tx = Some(BridgeTx::Evm(false, [0u8; 32].into()));
+                task.evm_tx = Some(EvmOutboundTx {
+                    nonce: 6,
+                    ..Default::default()
+                });
+                s.pending.push_back(task);
+            });
+            // the gaps of 5 and 7 are filled before the next nonce
+            assert_eq!(assign_evm_nonce("ETH", &canister, 5), 5);
+            assert_eq!(assign_evm_nonce("ETH", &canister, 5), 7);
+            assert_eq!(assign_evm_nonce("ETH", &canister, 5), 8);
+            // the chain nonce moved past the local one
+            assert_eq!(assign_evm_nonce("ETH", &canister, 10), 10);
+            assert_eq!(assign_evm_nonce("ETH", &canister, 5), 9);
+            assert_eq!(assign_evm_nonce("ETH", &canister, 5), 11);
+        }
+
+        #[test]
+        fn test_retry_delay_ms() {
+            assert_eq!(retry_delay_ms(0), TASK_RETRY_DELAY_MS);
+            assert_eq!(retry_delay_ms(1), TASK_RETRY_DELAY_MS);
+            assert_eq!(retry_delay_ms(2), TASK_RETRY_DELAY_MS * 2);
+            assert_eq!(retry_delay_ms(3), TASK_RETRY_DELAY_MS * 4);
+            assert_eq!(retry_delay_ms(10), TASK_RETRY_DELAY_MS * 512);
+            assert_eq!(retry_delay_ms(11), MAX_TASK_RETRY_DELAY_MS);
+            assert_eq!(retry_delay_ms(u32::MAX), MAX_TASK_RETRY_DELAY_MS);
+        }
+
+        #[test]
+        fn test_on_to_tx_error() {
+            init_token(None);
+            STATE.with_borrow_mut(|s| {
+                s.refund_after_retries = 3;
+                s.tokens.get_mut("PANDA").unwrap().refund_fee = 100;
+            });
+            let evm = BridgeTarget::Evm("ETH".to_string());
+
+            // fatal errors are not counted
+            let mut task = test_task(BridgeTarget::Sol, evm.clone(), 123_456_789);
+            on_to_tx_error(&mut task, TaskError::Fatal("fatal".to_string()));
+            assert_eq!(task.retries, 0);
+
+            // refunded once the retries are exhausted
+            on_to_tx_error(&mut task, TaskError::Retry("retry".to_string()));
+            on_to_tx_error(&mut task, TaskError::Retry("retry".to_string()));
+            assert_eq!(task.retries, 2);
+            assert!(task.refund.is_none());
+            on_to_tx_error(&mut task, TaskError::Retry("retry".to_string()));
+            assert_eq!(task.retries, 3);
+            assert_eq!(task.fee, 100);
+            assert_eq!(task.refund, Some(123_456_600));
+            assert_eq!(task.dust, 89);
+
+            // never refunded while the destination transaction may land
+            let mut task = test_task(BridgeTarget::Icp, evm.clone(), 1_000);
+            task.to_tx = Some(BridgeTx::Evm(false, [0u8; 32].into()));
+            for _ in 0..5 {
+                on_to_tx_error(&mut task, TaskError::Retry("retry".to_string()));
+            }
+            assert_eq!(task.retries, 5);
+            assert!(task.refund.is_none());
+
+            // refunded at once on definitive failures
+            on_to_tx_error(&mut task, TaskError::Refund("reverted".to_string()));
+            assert_eq!(task.refund, Some(900));
+
+            // refunds are disabled without refund_after_retries
+            STATE.with_borrow_mut(|s| s.refund_after_retries = 0);
+            let mut task = test_task(BridgeTarget::Icp, evm, 1_000);
+            for _ in 0..5 {
+                on_to_tx_error(&mut task, TaskError::Retry("retry".to_string()));
+            }
+            assert!(task.refund.is_none());
+        }
+
+        #[test]
+        fn test_set_refund() {
+            let mut t = test_token();
+            t.refund_fee = 100;
+
+            let mut task = test_task(BridgeTarget::Icp, BridgeTarget::Sol, 1_000);
+            task.fee = 10;
+            task.fee_detail = Some(FeeDetail::default());
+            task.dust = 1;
+            set_refund(&mut task, &t);
+            assert_eq!(task.fee, 100);
+            assert!(task.fee_detail.is_none());
+            assert_eq!((task.refund, task.dust), (Some(900), 0));
+
+            // the dust of the source chain is withheld
+            let mut task = test_task(BridgeTarget::Sol, BridgeTarget::Icp, 1_099);
+            set_refund(&mut task, &t);
+            assert_eq!((task.refund, task.dust), (Some(900), 99));
+
+            // the refund fee is capped by the amount
+            let mut task = test_task(BridgeTarget::Icp, BridgeTarget::Sol, 50);
+            set_refund(&mut task, &t);
+            assert_eq!((task.fee, task.refund, task.dust), (50, Some(0), 0));
+        }
+
+        #[test]
+        fn test_rate_usage_window() {
+            let mut usage = RateUsage::default();
+            usage.add(WINDOW_MS, 10, 1_500);
+            usage.add(WINDOW_MS, 5, 1_999);
+            assert_eq!(usage.buckets, VecDeque::from([(1_000, 15)]));
+            usage.add(WINDOW_MS, 7, 2_000);
+            assert_eq!(usage.buckets, VecDeque::from([(1_000, 15), (2_000, 7)]));
+
+            assert_eq!(usage.used(WINDOW_MS, 24_999), 22);
+            // the first bucket leaves the window once its start is a full window ago
+            assert_eq!(usage.used(WINDOW_MS, 25_000), 7);
+            assert_eq!(usage.buckets.len(), 1);
+            assert_eq!(usage.used(WINDOW_MS, 26_000), 0);
+            assert!(usage.buckets.is_empty());
+        }
+
+        #[test]
+        fn test_rate_usage_buckets() {
+            // windows shorter than the bucket count fall back to buckets of 1ms
+            let mut usage = RateUsage::default();
+            usage.add(10, 1, 5);
+            usage.add(10, 1, 6);
+            assert_eq!(usage.buckets, VecDeque::from([(5, 1), (6, 1)]));
+
+            let mut usage = RateUsage::default();
+            usage.add(3_600_000, 1, 149_999);
+            usage.add(3_600_000, 1, 150_000);
+            assert_eq!(usage.buckets, VecDeque::from([(0, 1), (150_000, 1)]));
+
+            // released amounts are taken from the latest buckets first
+            usage.add(3_600_000, 5, 150_001);
+            usage.sub(4);
+            assert_eq!(usage.buckets, VecDeque::from([(0, 1), (150_000, 2)]));
+            usage.sub(10);
+            assert_eq!(usage.buckets, VecDeque::from([(0, 0), (150_000, 0)]));
+        }
+
+        #[test]
+        fn test_bridge_allowance() {
+            let limit = RateLimit {
+                window_secs: 24,
+                per_user: 100,
+                per_chain: 150,
+                global: 0,
+            };
+            init_token(Some(limit.clone()));
+            let alice = Principal::from_slice(&[1]);
+            let bob = Principal::from_slice(&[2]);
+
+            assert_eq!(
+                bridge_allowance("PANDA", &alice, "ICP", "ETH", 1_000),
+                Ok(Some(100))
+            );
+            consume_rate_limit(Some(&limit), "PANDA", &alice, "ICP", "ETH", 80, true, 1_000)
+                .unwrap();
+            // per user
+            assert_eq!(
+                bridge_allowance("PANDA", &alice, "ICP", "ETH", 1_000),
+                Ok(Some(20))
+            );
+            assert_eq!(
+                bridge_allowance("PANDA", &alice, "SOL", "BNB", 1_000),
+                Ok(Some(20))
+            );
+            // per source and destination chain
+            assert_eq!(
+                bridge_allowance("PANDA", &bob, "ICP", "SOL", 1_000),
+                Ok(Some(70))
+            );
+            assert_eq!(
+                bridge_allowance("PANDA", &bob, "BNB", "ETH", 1_000),
+                Ok(Some(70))
+            );
+            assert_eq!(
+                bridge_allowance("PANDA", &bob, "SOL", "BNB", 1_000),
+                Ok(Some(100))
+            );
+            // the window expires
+            assert_eq!(
+                bridge_allowance("PANDA", &alice, "ICP", "ETH", 25_000),
+                Ok(Some(100))
+            );
+
+            assert!(bridge_allowance("DOGE", &alice, "ICP", "ETH", 1_000).is_err());
+            init_token(None);
+            assert_eq!(
+                bridge_allowance("PANDA", &alice, "ICP", "ETH", 1_000),
+                Ok(None)
+            );
+        }
+
+        #[test]
+        fn test_consume_rate_limit() {
+            let limit = RateLimit {
+                window_secs: 24,
+                per_user: 0,
+                per_chain: 0,
+                global: 100,
+            };
+            init_token(Some(limit.clone()));
+            let alice = Principal::from_slice(&[1]);
+            let bob = Principal::from_slice(&[2]);
+
+            consume_rate_limit(Some(&limit), "PANDA", &alice, "ICP", "ETH", 60, true, 1_000)
+                .unwrap();
+            let err =
+                consume_rate_limit(Some(&limit), "PANDA", &bob, "SOL", "BNB", 41, true, 2_000)
+                    .unwrap_err();
+            assert!(err.contains("PANDA/global"), "{err}");
+            // nothing is recorded when refused
+            assert_eq!(
+                bridge_allowance("PANDA", &bob, "SOL", "BNB", 2_000),
+                Ok(Some(40))
+            );
+
+            // deposits are recorded without being enforced
+            consume_rate_limit(Some(&limit), "PANDA", &bob, "SOL", "BNB", 50, false, 2_000)
+                .unwrap();
+            assert_eq!(
+                bridge_allowance("PANDA", &bob, "SOL", "BNB", 2_000),
+                Ok(Some(0))
+            );
+            release_rate_limit(Some(&limit), "PANDA", &bob, "SOL", "BNB", 50);
+            assert_eq!(
+                bridge_allowance("PANDA", &bob, "SOL", "BNB", 2_000),
+                Ok(Some(40))
+            );
+            assert_eq!(
+                bridge_allowance("PANDA", &bob, "SOL", "BNB", 25_000),
+                Ok(Some(100))
+            );
+
+            // no limit
+            consume_rate_limit(None, "PANDA", &alice, "ICP", "ETH", u128::MAX, true, 1_000)
+                .unwrap();
+        }
+    }
 }
 
 fn y_parity(prehash: &[u8], sig: &[u8], pubkey: &[u8]) -> Result<bool, String> {
@@ -1,9 +1,12 @@
+mod compute_budget;
 mod rpc;
 mod spl;
 mod types;
 
+pub use compute_budget::*;
 pub use rpc::*;
 pub use spl::*;
 pub use types::*;
 
-pub use solana_system_interface::instruction;
+pub use solana_instruction::Instruction;
+pub use solana_system_interface::{instruction, program::ID as SYSTEM_PROGRAM_ID};
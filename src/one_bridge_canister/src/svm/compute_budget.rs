@@ -0,0 +1,30 @@
+use solana_instruction::Instruction;
+
+use super::types::Pubkey;
+
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ComputeBudget111111111111111111111111111111");
+
+pub fn set_compute_unit_limit_instruction(units: u32) -> Instruction {
+    let mut data = Vec::with_capacity(5);
+    // ComputeBudget program "SetComputeUnitLimit" instruction
+    data.push(2);
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction {
+        program_id: COMPUTE_BUDGET_PROGRAM_ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+pub fn set_compute_unit_price_instruction(micro_lamports: u64) -> Instruction {
+    let mut data = Vec::with_capacity(9);
+    // ComputeBudget program "SetComputeUnitPrice" instruction
+    data.push(3);
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+    Instruction {
+        program_id: COMPUTE_BUDGET_PROGRAM_ID,
+        accounts: vec![],
+        data,
+    }
+}
@@ -6,7 +6,7 @@ use serde_json::{Map, Value, json};
 use super::types::*;
 use crate::{
     helper::APP_AGENT,
-    outcall::HttpOutcall,
+    outcall::{HttpOutcall, quorum_agree, quorum_reached},
     types::{RPCRequest, RPCResponse},
 };
 
@@ -14,6 +14,8 @@ pub struct SvmClient<T: HttpOutcall> {
     pub providers: Vec<String>,
     pub commitment: Option<String>,
     pub api_token: Option<String>,
+    // the number of providers that must agree on safety-critical reads, 1 means no quorum
+    pub quorum: usize,
     outcall: T,
 }
 
@@ -39,11 +41,27 @@ impl<H: HttpOutcall> SvmClient<H> {
             providers,
             commitment: commitment.or_else(|| Some("confirmed".to_string())),
             api_token,
+            quorum: 1,
             outcall,
         }
     }
 
+    pub fn with_quorum(mut self, quorum: usize) -> Self {
+        self.quorum = quorum;
+        self
+    }
+
     pub async fn get_latest_blockhash(&self, now_ms: u64) -> Result<Hash, String> {
+        self.get_latest_blockhash_with_height(now_ms)
+            .await
+            .map(|(hash, _)| hash)
+    }
+
+    // Returns the latest blockhash and the last block height at which it is valid.
+    pub async fn get_latest_blockhash_with_height(
+        &self,
+        now_ms: u64,
+    ) -> Result<(Hash, u64), String> {
         let mut config = Map::new();
         self.insert_commitment(&mut config);
         let params: Vec<Value> = if config.is_empty() {
@@ -60,10 +78,9 @@ impl<H: HttpOutcall> SvmClient<H> {
             )
             .await?;
 
-        res.value.to_hash()
+        Ok((res.value.to_hash()?, res.value.last_valid_block_height))
     }
 
-    #[allow(dead_code)]
     pub async fn get_block_height(&self, now_ms: u64) -> Result<u64, String> {
         let mut config = Map::new();
         self.insert_commitment(&mut config);
@@ -73,6 +90,18 @@ impl<H: HttpOutcall> SvmClient<H> {
             vec![Value::Object(config)]
         };
 
+        if self.quorum > 1 {
+            let results = self
+                .call_all(
+                    format!("getBlockHeight-{now_ms}"),
+                    "getBlockHeight",
+                    params.as_slice(),
+                )
+                .await?;
+            return quorum_reached(results, self.quorum)
+                .map_err(|err| format!("getBlockHeight: {err}"));
+        }
+
         self.call(
             format!("getBlockHeight-{now_ms}"),
             "getBlockHeight",
@@ -93,15 +122,30 @@ impl<H: HttpOutcall> SvmClient<H> {
             }),
         ];
 
-        let mut res: RpcContextValue<Vec<Option<SignatureStatus>>> = self
+        if self.quorum > 1 {
+            let results = self
+                .call_all::<RpcContextValue<Vec<Option<SignatureStatus>>>>(
+                    format!("getSignatureStatuses-{now_ms}"),
+                    "getSignatureStatuses",
+                    params.as_slice(),
+                )
+                .await?;
+            let results = results
+                .into_iter()
+                .map(|res| res.map(|v| v.value.into_iter().next().flatten()))
+                .collect();
+            return quorum_signature_status(results, self.quorum)
+                .map_err(|err| format!("getSignatureStatuses: {err}"));
+        }
+
+        let res: RpcContextValue<Vec<Option<SignatureStatus>>> = self
             .call(
                 format!("getSignatureStatuses-{now_ms}"),
                 "getSignatureStatuses",
                 params.as_slice(),
             )
             .await?;
-        let status = res.value.remove(0);
-        Ok(status)
+        Ok(res.value.into_iter().next().flatten())
     }
 
     #[allow(dead_code)]
@@ -216,7 +260,85 @@ impl<H: HttpOutcall> SvmClient<H> {
         Ok(res.value)
     }
 
-    #[allow(dead_code)]
+    pub async fn get_durable_nonce(&self, now_ms: u64, pubkey: &str) -> Result<Hash, String> {
+        let account = self
+            .get_account_info(now_ms, pubkey)
+            .await?
+            .ok_or_else(|| format!("nonce account {pubkey} not found"))?;
+        get_durable_nonce(account)
+    }
+
+    // Returns the durable nonce of a nonce account at finalized commitment, agreed by the
+    // quorum of providers. An unlanded transaction signed with an older nonce can never land.
+    pub async fn get_finalized_durable_nonce(
+        &self,
+        now_ms: u64,
+        pubkey: &str,
+    ) -> Result<Hash, String> {
+        let params = vec![
+            Value::String(pubkey.to_string()),
+            json!({
+                "commitment": "finalized",
+                "encoding": "jsonParsed"
+            }),
+        ];
+
+        let res: RpcContextValue<Option<UiAccount>> = self
+            .call_quorum(
+                format!("getAccountInfo-finalized-{now_ms}-{pubkey}"),
+                "getAccountInfo",
+                params.as_slice(),
+                |v: &RpcContextValue<Option<UiAccount>>| {
+                    v.value.clone().map(|a| get_durable_nonce(a).ok())
+                },
+            )
+            .await?;
+        let account = res
+            .value
+            .ok_or_else(|| format!("nonce account {pubkey} not found"))?;
+        get_durable_nonce(account)
+    }
+
+    pub async fn get_balance(&self, now_ms: u64, pubkey: &str) -> Result<u64, String> {
+        let mut config = Map::new();
+        self.insert_commitment(&mut config);
+
+        let params = if config.is_empty() {
+            vec![Value::String(pubkey.to_string())]
+        } else {
+            vec![Value::String(pubkey.to_string()), Value::Object(config)]
+        };
+
+        let res: RpcContextValue<u64> = self
+            .call(
+                format!("getBalance-{now_ms}-{pubkey}"),
+                "getBalance",
+                params.as_slice(),
+            )
+            .await?;
+
+        Ok(res.value)
+    }
+
+    // Returns the prioritization fees paid in the recent slots by transactions
+    // that lock all the given accounts as writable.
+    pub async fn get_recent_prioritization_fees(
+        &self,
+        now_ms: u64,
+        accounts: &[String],
+    ) -> Result<Vec<PrioritizationFee>, String> {
+        let params = vec![Value::Array(
+            accounts.iter().map(|a| Value::String(a.clone())).collect(),
+        )];
+
+        self.call(
+            format!("getRecentPrioritizationFees-{now_ms}"),
+            "getRecentPrioritizationFees",
+            params.as_slice(),
+        )
+        .await
+    }
+
     pub async fn get_token_account_balance(
         &self,
         now_ms: u64,
@@ -242,6 +364,27 @@ impl<H: HttpOutcall> SvmClient<H> {
         Ok(res.value)
     }
 
+    pub async fn get_token_supply(&self, now_ms: u64, mint: &str) -> Result<UiTokenAmount, String> {
+        let mut config = Map::new();
+        self.insert_commitment(&mut config);
+
+        let params = if config.is_empty() {
+            vec![Value::String(mint.to_string())]
+        } else {
+            vec![Value::String(mint.to_string()), Value::Object(config)]
+        };
+
+        let res: RpcContextValue<UiTokenAmount> = self
+            .call(
+                format!("getTokenSupply-{now_ms}-{mint}"),
+                "getTokenSupply",
+                params.as_slice(),
+            )
+            .await?;
+
+        Ok(res.value)
+    }
+
     pub async fn call<T: DeserializeOwned>(
         &self,
         idempotency_key: String,
@@ -260,18 +403,55 @@ impl<H: HttpOutcall> SvmClient<H> {
         };
         let input = serde_json::to_vec(&input).map_err(|err| err.to_string())?;
         let data = self.http_request(idempotency_key, input).await?;
+        RPCResponse::<T>::decode(&data)
+    }
 
-        let output: RPCResponse<T> =
-            serde_json::from_slice(&data).map_err(|err| err.to_string())?;
-
-        if let Some(error) = output.error {
-            return Err(serde_json::to_string(&error).map_err(|err| err.to_string())?);
+    /// Calls all providers concurrently if the quorum is more than 1, and returns
+    /// the result that at least `quorum` providers agree on by `key`.
+    pub async fn call_quorum<T: DeserializeOwned, K: PartialEq>(
+        &self,
+        idempotency_key: String,
+        method: &str,
+        params: &[Value],
+        key: impl Fn(&T) -> K,
+    ) -> Result<T, String> {
+        if self.quorum <= 1 {
+            return self.call(idempotency_key, method, params).await;
         }
 
-        match output.result {
-            Some(result) => Ok(result),
-            None => serde_json::from_value(Value::Null).map_err(|_| "missing result".to_string()),
+        let results = self.call_all(idempotency_key, method, params).await?;
+        quorum_agree(results, self.quorum, key).map_err(|err| format!("{method}: {err}"))
+    }
+
+    async fn call_all<T: DeserializeOwned>(
+        &self,
+        idempotency_key: String,
+        method: &str,
+        params: &[Value],
+    ) -> Result<Vec<Result<T, String>>, String> {
+        if self.providers.is_empty() {
+            return Err("no available provider".to_string());
         }
+
+        let input = RPCRequest {
+            jsonrpc: "2.0",
+            method,
+            params,
+            id: 1,
+        };
+        let input = serde_json::to_vec(&input).map_err(|err| err.to_string())?;
+        let args = self.request_args(&idempotency_key, input);
+        let results = futures::future::join_all(self.providers.iter().map(|p| {
+            let mut args = args.clone();
+            args.url = p.clone();
+            let idempotency_key = &idempotency_key;
+            async move {
+                let data = self.request_provider(&args, idempotency_key).await?;
+                RPCResponse::<T>::decode(&data)
+            }
+        }))
+        .await;
+        Ok(results)
     }
 
     fn insert_commitment(&self, config: &mut Map<String, Value>) {
@@ -287,6 +467,20 @@ impl<H: HttpOutcall> SvmClient<H> {
         idempotency_key: String,
         body: Vec<u8>,
     ) -> Result<Vec<u8>, String> {
+        let mut args = self.request_args(&idempotency_key, body);
+        let mut last_err = "No provider succeeded".to_string();
+        for p in &self.providers {
+            args.url = p.clone();
+            match self.request_provider(&args, &idempotency_key).await {
+                Ok(body) => return Ok(body),
+                Err(err) => last_err = err,
+            }
+        }
+
+        Err(last_err)
+    }
+
+    fn request_args(&self, idempotency_key: &str, body: Vec<u8>) -> HttpRequestArgs {
         let mut request_headers = vec![
             HttpHeader {
                 name: "content-type".to_string(),
@@ -298,7 +492,7 @@ impl<H: HttpOutcall> SvmClient<H> {
             },
             HttpHeader {
                 name: "idempotency-key".to_string(),
-                value: idempotency_key.clone(),
+                value: idempotency_key.to_string(),
             },
         ];
 
@@ -309,7 +503,7 @@ impl<H: HttpOutcall> SvmClient<H> {
             });
         }
 
-        let mut args = HttpRequestArgs {
+        HttpRequestArgs {
             url: "".to_string(),
             max_response_bytes: None,
             method: HttpMethod::POST,
@@ -317,35 +511,66 @@ impl<H: HttpOutcall> SvmClient<H> {
             body: Some(body),
             transform: self.outcall.transform_context(),
             is_replicated: Some(false),
-        };
+        }
+    }
 
-        let mut last_err = "No provider succeeded".to_string();
-        for p in &self.providers {
-            args.url = p.clone();
-            match self.outcall.request(&args).await {
-                Ok(res) => {
-                    if res.status >= 200u64 && res.status < 300u64 {
-                        return Ok(res.body);
-                    } else {
-                        last_err = format!(
-                            "request provider: {}, idempotency-key: {}, status: {}, body: {}",
-                            p,
-                            idempotency_key,
-                            res.status,
-                            String::from_utf8(res.body.clone()).unwrap_or_default(),
-                        );
-                    }
-                }
-                Err(err) => {
-                    last_err = format!("failed to request provider: {p}, error: {err}");
+    async fn request_provider(
+        &self,
+        args: &HttpRequestArgs,
+        idempotency_key: &str,
+    ) -> Result<Vec<u8>, String> {
+        let p = &args.url;
+        match self.outcall.request(args).await {
+            Ok(res) => {
+                if res.status >= 200u64 && res.status < 300u64 {
+                    Ok(res.body)
+                } else {
+                    Err(format!(
+                        "request provider: {}, idempotency-key: {}, status: {}, body: {}",
+                        p,
+                        idempotency_key,
+                        res.status,
+                        String::from_utf8(res.body.clone()).unwrap_or_default(),
+                    ))
                 }
             }
+            Err(err) => Err(format!("failed to request provider: {p}, error: {err}")),
         }
-
-        Err(last_err)
     }
 }
 
+// Returns the signature status that at least `quorum` providers agree on by whether the
+// transaction landed and its error. The slot, confirmations and confirmation status vary
+// between providers, the status is the highest one reached by `quorum` of the agreeing ones.
+fn quorum_signature_status(
+    results: Vec<Result<Option<SignatureStatus>, String>>,
+    quorum: usize,
+) -> Result<Option<SignatureStatus>, String> {
+    let key = |s: &Option<SignatureStatus>| s.as_ref().map(|s| s.err.clone());
+    let agreed = quorum_agree(results.clone(), quorum, key)?;
+    let Some(mut status) = agreed else {
+        return Ok(None);
+    };
+
+    const LEVELS: [&str; 3] = ["processed", "confirmed", "finalized"];
+    let levels = results
+        .into_iter()
+        .filter_map(|res| res.ok().flatten())
+        .filter(|s| s.err == status.err)
+        .map(|s| {
+            let level = s
+                .confirmation_status
+                .as_deref()
+                .and_then(|c| LEVELS.iter().position(|l| *l == c))
+                .unwrap_or_default();
+            Ok(level as u64)
+        })
+        .collect();
+    let level = quorum_reached(levels, quorum)?;
+    status.confirmation_status = Some(LEVELS[level as usize].to_string());
+    Ok(Some(status))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -386,6 +611,40 @@ mod tests {
         assert_eq!(mock.urls(), vec!["https://solana.rpc".to_string()]);
     }
 
+    #[test]
+    fn test_blockhash_expiry_reads() {
+        let mock = MockHttpOutcall::new(vec![
+            success_response(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "context": { "slot": 1234 },
+                    "value": {
+                        "blockhash": "3Xdj6drp4pKAM9PH2vZ4w8NHygd8Epp7FKCvzX29VLLH",
+                        "lastValidBlockHeight": 355385114
+                    }
+                }
+            })),
+            success_response(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": 355385200
+            })),
+        ]);
+
+        let client = SvmClient::new(vec!["https://solana.rpc".to_string()], None, None, mock);
+        let (hash, last_valid_block_height) =
+            futures::executor::block_on(client.get_latest_blockhash_with_height(1000)).unwrap();
+        let block_height = futures::executor::block_on(client.get_block_height(1001)).unwrap();
+
+        assert_eq!(
+            hash.to_string(),
+            "3Xdj6drp4pKAM9PH2vZ4w8NHygd8Epp7FKCvzX29VLLH"
+        );
+        assert_eq!(last_valid_block_height, 355385114);
+        assert!(block_height > last_valid_block_height);
+    }
+
     #[test]
     fn test_http_request_fallbacks_between_providers() {
         let mock = MockHttpOutcall::new(vec![
@@ -413,6 +672,99 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_signature_statuses_with_quorum() {
+        let status = |slot: u64, confirmations: u64, status: &str| {
+            success_response(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "context": { "slot": slot },
+                    "value": [{
+                        "slot": slot,
+                        "confirmations": confirmations,
+                        "confirmationStatus": status,
+                        "err": null
+                    }]
+                }
+            }))
+        };
+
+        let mock = MockHttpOutcall::new(vec![
+            status(100, 10, "confirmed"),
+            status(101, 12, "confirmed"),
+            status(101, 0, "processed"),
+        ]);
+        let client = SvmClient::new(
+            vec![
+                "https://first".to_string(),
+                "https://second".to_string(),
+                "https://third".to_string(),
+            ],
+            None,
+            None,
+            mock,
+        )
+        .with_quorum(2);
+        let res = futures::executor::block_on(client.get_signature_statuses(1_000, "sig"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(res.confirmation_status.as_deref(), Some("confirmed"));
+
+        // providers at different commitments agree that the transaction landed
+        let mock = MockHttpOutcall::new(vec![
+            status(100, 10, "confirmed"),
+            status(101, 0, "finalized"),
+        ]);
+        let client = SvmClient::new(
+            vec!["https://first".to_string(), "https://second".to_string()],
+            None,
+            None,
+            mock,
+        )
+        .with_quorum(2);
+        let res = futures::executor::block_on(client.get_signature_statuses(1_000, "sig"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(res.confirmation_status.as_deref(), Some("confirmed"));
+        assert!(!res.is_finalized());
+
+        let statuses = vec![
+            Ok(Some(SignatureStatus {
+                slot: 100,
+                confirmations: None,
+                confirmation_status: Some("finalized".to_string()),
+                err: None,
+            })),
+            Ok(Some(SignatureStatus {
+                slot: 101,
+                confirmations: None,
+                confirmation_status: Some("finalized".to_string()),
+                err: None,
+            })),
+            Ok(Some(SignatureStatus {
+                slot: 102,
+                confirmations: Some(1),
+                confirmation_status: Some("processed".to_string()),
+                err: Some(json!({"InstructionError": [0, "Custom"]})),
+            })),
+            Ok(None),
+        ];
+        assert!(
+            quorum_signature_status(statuses.clone(), 2)
+                .unwrap()
+                .unwrap()
+                .is_finalized()
+        );
+        // landed vs not found, and different errors disagree
+        let err = quorum_signature_status(statuses[1..].to_vec(), 2).unwrap_err();
+        assert!(err.contains(crate::outcall::QUORUM_DISAGREEMENT));
+        assert_eq!(
+            quorum_signature_status(vec![Ok(None), Ok(None)], 2),
+            Ok(None)
+        );
+    }
+
     #[test]
     fn test_call_handles_error_payload() {
         let mock = MockHttpOutcall::new(vec![success_response(json!({
@@ -485,6 +837,174 @@ mod tests {
         assert_eq!(balance.ui_amount_string, "0.012345");
     }
 
+    #[test]
+    fn test_get_durable_nonce() {
+        let mock = MockHttpOutcall::new(vec![success_response(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "context": {"slot": 322},
+                "value": {
+                    "data": {
+                        "program": "nonce",
+                        "parsed": {
+                            "type": "initialized",
+                            "info": {
+                                "authority": "8bZsjXGSkuqZnQZbbNGv8mBAqPPoW6ZfvJ8UqJZnEe2X",
+                                "blockhash": "9zb7ngJpNFjfN8UJ8yS5JkU1WB7oquEvQxS6QRmLXaS2",
+                                "feeCalculator": {"lamportsPerSignature": "5000"}
+                            }
+                        },
+                        "space": 80
+                    },
+                    "executable": false,
+                    "lamports": 1447680,
+                    "owner": "11111111111111111111111111111111",
+                    "rentEpoch": 18446744073709551615u64,
+                    "space": 80
+                }
+            }
+        }))]);
+
+        let client = SvmClient::new(vec!["https://sol".to_string()], None, None, mock);
+        let nonce =
+            futures::executor::block_on(client.get_durable_nonce(1_112, "NoncePubkey")).unwrap();
+
+        assert_eq!(
+            nonce.to_string(),
+            "9zb7ngJpNFjfN8UJ8yS5JkU1WB7oquEvQxS6QRmLXaS2"
+        );
+    }
+
+    #[test]
+    fn test_get_finalized_durable_nonce() {
+        let nonce_account = |blockhash: &str| {
+            success_response(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "context": {"slot": 322},
+                    "value": {
+                        "data": {
+                            "program": "nonce",
+                            "parsed": {
+                                "type": "initialized",
+                                "info": {
+                                    "authority": "8bZsjXGSkuqZnQZbbNGv8mBAqPPoW6ZfvJ8UqJZnEe2X",
+                                    "blockhash": blockhash,
+                                    "feeCalculator": {"lamportsPerSignature": "5000"}
+                                }
+                            },
+                            "space": 80
+                        },
+                        "executable": false,
+                        "lamports": 1447680,
+                        "owner": "11111111111111111111111111111111",
+                        "rentEpoch": 18446744073709551615u64,
+                        "space": 80
+                    }
+                }
+            }))
+        };
+        let providers = vec!["https://first".to_string(), "https://second".to_string()];
+
+        let mock = MockHttpOutcall::new(vec![
+            nonce_account("9zb7ngJpNFjfN8UJ8yS5JkU1WB7oquEvQxS6QRmLXaS2"),
+            nonce_account("9zb7ngJpNFjfN8UJ8yS5JkU1WB7oquEvQxS6QRmLXaS2"),
+        ]);
+        let client = SvmClient::new(providers.clone(), None, None, mock.clone()).with_quorum(2);
+        let nonce =
+            futures::executor::block_on(client.get_finalized_durable_nonce(1_112, "NoncePubkey"))
+                .unwrap();
+        assert_eq!(
+            nonce.to_string(),
+            "9zb7ngJpNFjfN8UJ8yS5JkU1WB7oquEvQxS6QRmLXaS2"
+        );
+        assert_eq!(mock.urls(), providers);
+
+        let mock = MockHttpOutcall::new(vec![
+            nonce_account("9zb7ngJpNFjfN8UJ8yS5JkU1WB7oquEvQxS6QRmLXaS2"),
+            nonce_account("11111111111111111111111111111111"),
+        ]);
+        let client = SvmClient::new(providers, None, None, mock).with_quorum(2);
+        let err =
+            futures::executor::block_on(client.get_finalized_durable_nonce(1_112, "NoncePubkey"))
+                .unwrap_err();
+        assert!(err.contains(crate::outcall::QUORUM_DISAGREEMENT));
+    }
+
+    #[test]
+    fn test_get_balance() {
+        let mock = MockHttpOutcall::new(vec![success_response(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "context": {"slot": 322},
+                "value": 2_500_000_000u64
+            }
+        }))]);
+
+        let client = SvmClient::new(vec!["https://sol".to_string()], None, None, mock);
+        let balance =
+            futures::executor::block_on(client.get_balance(1_112, "OwnerPubkey")).unwrap();
+
+        assert_eq!(balance, 2_500_000_000);
+    }
+
+    #[test]
+    fn test_get_recent_prioritization_fees() {
+        let mock = MockHttpOutcall::new(vec![success_response(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": [
+                {"slot": 348125, "prioritizationFee": 0},
+                {"slot": 348126, "prioritizationFee": 1000},
+                {"slot": 348127, "prioritizationFee": 500},
+                {"slot": 348128, "prioritizationFee": 0},
+                {"slot": 348129, "prioritizationFee": 20000}
+            ]
+        }))]);
+
+        let client = SvmClient::new(vec!["https://sol".to_string()], None, None, mock);
+        let fees = futures::executor::block_on(
+            client.get_recent_prioritization_fees(1_112, &["AccountPubkey".to_string()]),
+        )
+        .unwrap();
+
+        assert_eq!(fees.len(), 5);
+        assert_eq!(fees[1].slot, 348126);
+        assert_eq!(fees[1].prioritization_fee, 1000);
+        assert_eq!(prioritization_fee_percentile(&fees, 0), 0);
+        assert_eq!(prioritization_fee_percentile(&fees, 50), 500);
+        assert_eq!(prioritization_fee_percentile(&fees, 75), 1000);
+        assert_eq!(prioritization_fee_percentile(&fees, 100), 20000);
+        assert_eq!(prioritization_fee_percentile(&[], 75), 0);
+    }
+
+    #[test]
+    fn test_get_token_supply() {
+        let mock = MockHttpOutcall::new(vec![success_response(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "context": {"slot": 322},
+                "value": {
+                    "amount": "1000000",
+                    "decimals": 9,
+                    "uiAmount": 0.001,
+                    "uiAmountString": "0.001"
+                }
+            }
+        }))]);
+
+        let client = SvmClient::new(vec!["https://sol".to_string()], None, None, mock);
+        let supply =
+            futures::executor::block_on(client.get_token_supply(1_112, "MintPubkey")).unwrap();
+
+        assert_eq!(supply.amount, "1000000");
+        assert_eq!(supply.decimals, 9);
+    }
+
     #[derive(Clone, Default)]
     struct MockHttpOutcall {
         responses: Arc<Mutex<VecDeque<Result<HttpRequestResult, String>>>>,
@@ -45,6 +45,42 @@ impl SignatureStatus {
     }
 }
 
+/// Returns the durable nonce stored in an initialized nonce account, parsed as JSON.
+pub fn get_durable_nonce(val: UiAccount) -> Result<Hash, String> {
+    match val.data {
+        UiAccountData::Json(parsed_account) => {
+            let parsed = &parsed_account.parsed;
+            if parsed["type"] != "initialized" {
+                return Err("nonce account is not initialized".to_string());
+            }
+            let blockhash = parsed["info"]["blockhash"]
+                .as_str()
+                .ok_or_else(|| "nonce account has no blockhash".to_string())?;
+            Hash::from_str(blockhash).map_err(|e| format!("Failed to parse durable nonce: {}", e))
+        }
+        _ => Err("UiAccount data is not in JSON format".to_string()),
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PrioritizationFee {
+    pub slot: u64,
+    // micro-lamports per compute unit
+    pub prioritization_fee: u64,
+}
+
+/// Returns the fee at the percentile (0-100) of the recent prioritization fees, 0 if none.
+pub fn prioritization_fee_percentile(fees: &[PrioritizationFee], percentile: u8) -> u64 {
+    let mut fees: Vec<u64> = fees.iter().map(|f| f.prioritization_fee).collect();
+    if fees.is_empty() {
+        return 0;
+    }
+    fees.sort_unstable();
+    let idx = (fees.len() - 1) * (percentile.min(100) as usize) / 100;
+    fees[idx]
+}
+
 pub fn get_token_account(val: UiAccount) -> Result<TokenAccountType, String> {
     match val.data {
         UiAccountData::Json(parsed_account) => {
@@ -1,5 +1,5 @@
 use candid::CandidType;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_bytes::ByteBuf;
 use serde_json::Value;
 
@@ -39,3 +39,18 @@ pub struct RPCResponse<T> {
     pub result: Option<T>,
     pub error: Option<Value>,
 }
+
+impl<T: DeserializeOwned> RPCResponse<T> {
+    pub fn decode(data: &[u8]) -> Result<T, String> {
+        let output: RPCResponse<T> = serde_json::from_slice(data).map_err(|err| err.to_string())?;
+
+        if let Some(error) = output.error {
+            return Err(serde_json::to_string(&error).map_err(|err| err.to_string())?);
+        }
+
+        match output.result {
+            Some(result) => Ok(result),
+            None => serde_json::from_value(Value::Null).map_err(|_| "missing result".to_string()),
+        }
+    }
+}